@@ -81,10 +81,14 @@ pub struct CloneSummary {
     pub tracked: usize,
     pub ok: usize,
     pub skipped: usize,
+    #[serde(default)]
+    pub oversized: usize,
     pub failed: usize,
     pub success_rate: f64,
     pub failed_plugins: Vec<String>,
     pub status_counts: Vec<CountShare>,
+    #[serde(default)]
+    pub failure_category_counts: Vec<CountShare>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -131,6 +135,12 @@ pub struct PluginPageCloneFreshness {
     pub last_attempt_unix: i64,
     pub last_success_unix: Option<i64>,
     pub status: String,
+    #[serde(default)]
+    pub clone_strategy: Option<String>,
+    #[serde(default)]
+    pub clone_mode: Option<String>,
+    #[serde(default)]
+    pub failure_category: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -183,6 +183,10 @@ fn build_clone_summary(clone_entries: &HashMap<String, PluginPageCloneFreshness>
         .values()
         .filter(|entry| matches!(entry.status.as_str(), "skipped" | "skipped_removed"))
         .count();
+    let clone_oversized = clone_entries
+        .values()
+        .filter(|entry| entry.status == "oversized")
+        .count();
     let clone_failed = clone_failed_entries.len();
     let clone_attempted = clone_ok + clone_failed;
 
@@ -190,6 +194,7 @@ fn build_clone_summary(clone_entries: &HashMap<String, PluginPageCloneFreshness>
         tracked: clone_entries.len(),
         ok: clone_ok,
         skipped: clone_skipped,
+        oversized: clone_oversized,
         failed: clone_failed,
         success_rate: clamp_rate(clone_ok, clone_attempted),
         failed_plugins: clone_failed_entries
@@ -203,6 +208,15 @@ fn build_clone_summary(clone_entries: &HashMap<String, PluginPageCloneFreshness>
                 .map(|entry| clone_status_label(&entry.status).to_string()),
             clone_entries.len(),
         ),
+        failure_category_counts: status_counts(
+            clone_failed_entries.iter().map(|(_, entry)| {
+                entry
+                    .failure_category
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string())
+            }),
+            clone_failed,
+        ),
     }
 }
 
@@ -397,6 +411,7 @@ fn is_release_acquisition_error(status: &str) -> bool {
         || status.contains("failed")
         || status.contains("rate_limit")
         || status.contains("rate_limited")
+        || status.contains("quarantined")
         || is_target_release_error(status)
 }
 
@@ -461,6 +476,10 @@ mod tests {
             PluginRepoAnalysisError::from_raw("main_js_analysis_too_large"),
             PluginRepoAnalysisError::MainJsAnalysisTooLarge
         );
+        assert_eq!(
+            PluginRepoAnalysisError::from_raw("lockfile_parse_error"),
+            PluginRepoAnalysisError::LockfileParse
+        );
     }
 
     #[test]
@@ -470,6 +489,9 @@ mod tests {
             "main_js_download_failed:write:disk_full"
         ));
         assert!(is_release_acquisition_error("rate_limited"));
+        assert!(is_release_acquisition_error(
+            "main_js_quarantined:not_javascript:markup"
+        ));
         assert!(is_release_acquisition_error("manifest_version_prefixed"));
         assert!(is_release_acquisition_error(
             "release_for_manifest_version_missing"
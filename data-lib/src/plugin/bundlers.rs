@@ -1,74 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Bundler {
-    Esbuild,
-    Rollup,
-    Webpack,
-    Vite,
-    Turbo,
-}
+use super::detectors::{DetectedTool, ToolDetector, ToolKind, deserialize_tool, serialize_tool};
 
-impl Bundler {
-    pub fn get_package_name(&self) -> Vec<&'static str> {
-        match self {
-            Bundler::Esbuild => vec!["esbuild"],
-            Bundler::Rollup => vec!["rollup"],
-            Bundler::Webpack => vec!["webpack"],
-            Bundler::Vite => vec!["vite"],
-            Bundler::Turbo => vec!["turbo"],
-        }
-    }
+/// A bundler from the detector registry, e.g. esbuild or Rollup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bundler(&'static ToolDetector);
 
-    pub fn get_identifier(&self) -> &'static str {
-        match self {
-            Bundler::Esbuild => "esbuild",
-            Bundler::Rollup => "Rollup",
-            Bundler::Webpack => "Webpack",
-            Bundler::Vite => "Vite",
-            Bundler::Turbo => "Turbo",
-        }
-    }
-
-    pub fn from_identifier(identifier: &str) -> Option<Self> {
-        match identifier.to_lowercase().as_str() {
-            "esbuild" => Some(Bundler::Esbuild),
-            "rollup" => Some(Bundler::Rollup),
-            "webpack" => Some(Bundler::Webpack),
-            "vite" => Some(Bundler::Vite),
-            "turbo" => Some(Bundler::Turbo),
-            _ => None,
-        }
-    }
+impl DetectedTool for Bundler {
+    const KIND: ToolKind = ToolKind::Bundler;
 
-    pub fn matches_dependency(&self, dependency: &str) -> bool {
-        self.get_package_name().contains(&dependency)
+    fn from_detector(detector: &'static ToolDetector) -> Self {
+        Self(detector)
     }
 
-    pub fn iter_variants() -> impl Iterator<Item = Bundler> {
-        [
-            Bundler::Esbuild,
-            Bundler::Rollup,
-            Bundler::Webpack,
-            Bundler::Vite,
-            Bundler::Turbo,
-        ]
-        .iter()
-        .cloned()
-    }
-
-    pub fn find_bundlers(dependencies: &[&String]) -> Vec<Self> {
-        let mut package_managers = Vec::new();
-
-        for dependency in dependencies {
-            for tf in Bundler::iter_variants() {
-                if tf.matches_dependency(dependency) && !package_managers.contains(&tf) {
-                    package_managers.push(tf);
-                }
-            }
-        }
-
-        package_managers
+    fn detector(&self) -> &'static ToolDetector {
+        self.0
     }
 }
 
@@ -77,8 +23,7 @@ impl Serialize for Bundler {
     where
         S: serde::Serializer,
     {
-        let identifier = self.get_identifier();
-        serializer.serialize_str(identifier)
+        serialize_tool(self, serializer)
     }
 }
 
@@ -87,9 +32,6 @@ impl<'de> Deserialize<'de> for Bundler {
     where
         D: serde::Deserializer<'de>,
     {
-        let identifier = String::deserialize(deserializer)?;
-        Bundler::from_identifier(&identifier).ok_or_else(|| {
-            serde::de::Error::custom(format!("Unknown bundler identifier: {identifier}"))
-        })
+        deserialize_tool(deserializer, "bundler identifier")
     }
 }
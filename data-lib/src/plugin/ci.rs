@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::version::Version;
+
+/// CI, release automation and contributor-facing files of a plugin repo.
+#[derive(Tsify, Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct PluginCiData {
+    /// GitHub Actions workflows under `.github/workflows`.
+    pub workflows: Vec<CiWorkflow>,
+    /// The entries of `versions.json`, or `None` if the file is missing or
+    /// not a version map.
+    pub versions_json: Option<Vec<VersionsJsonEntry>>,
+    pub has_version_bump_script: bool,
+    pub tools: Vec<RepoTool>,
+    pub has_contributing_guide: bool,
+    pub has_security_policy: bool,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct CiWorkflow {
+    pub file: String,
+    pub purposes: Vec<CiWorkflowPurpose>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct VersionsJsonEntry {
+    pub version: String,
+    pub min_app_version: String,
+}
+
+/// A problem with `versions.json`, which Obsidian reads to pick the newest
+/// plugin version an older app can install.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct VersionsJsonIssue {
+    pub kind: VersionsJsonIssueKind,
+    /// The plugin version the issue is about.
+    pub version: String,
+    /// The offending value, e.g. the `minAppVersion` that goes backwards.
+    pub detail: Option<String>,
+}
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionsJsonIssueKind {
+    /// A published release has no entry.
+    MissingRelease,
+    /// An entry requires an older app than the entry before it.
+    NonMonotoneMinAppVersion,
+    /// A version or minimum app version is not a valid version.
+    UnparsableVersion,
+    /// The entry for the manifest version disagrees with its `minAppVersion`.
+    ManifestMinAppVersionMismatch,
+}
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum CiWorkflowPurpose {
+    Build,
+    Lint,
+    Test,
+    Release,
+    Other,
+}
+
+impl CiWorkflowPurpose {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Build => "Build",
+            Self::Lint => "Lint",
+            Self::Test => "Test",
+            Self::Release => "Release",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// Linters, formatters and dependency update bots configured in the repo.
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoTool {
+    Eslint,
+    Prettier,
+    Biome,
+    Dependabot,
+    Renovate,
+}
+
+impl RepoTool {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Eslint => "ESLint",
+            Self::Prettier => "Prettier",
+            Self::Biome => "Biome",
+            Self::Dependabot => "Dependabot",
+            Self::Renovate => "Renovate",
+        }
+    }
+}
+
+impl PluginCiData {
+    pub fn has_workflow_purpose(&self, purpose: CiWorkflowPurpose) -> bool {
+        self.workflows
+            .iter()
+            .any(|workflow| workflow.purposes.contains(&purpose))
+    }
+
+    /// Whether `versions.json` has an entry for `version`, ignoring a leading
+    /// `v` of release tags. `None` without a `versions.json`.
+    pub fn versions_json_lists(&self, version: &str) -> Option<bool> {
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let parsed = Version::parse(version);
+        self.versions_json.as_ref().map(|entries| {
+            entries.iter().any(|entry| {
+                entry.version == version
+                    || parsed.is_some()
+                        && Version::parse(&entry.version).as_ref() == parsed.as_ref()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PluginCiData, VersionsJsonEntry};
+
+    #[test]
+    fn versions_json_lookup_ignores_tag_prefixes() {
+        let ci = PluginCiData {
+            versions_json: Some(vec![VersionsJsonEntry {
+                version: "1.2.0".to_string(),
+                min_app_version: "1.4.0".to_string(),
+            }]),
+            ..Default::default()
+        };
+
+        assert_eq!(ci.versions_json_lists("v1.2.0"), Some(true));
+        assert_eq!(ci.versions_json_lists("1.3.0"), Some(false));
+        assert_eq!(PluginCiData::default().versions_json_lists("1.2.0"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use hashbrown::HashSet;
+
+use crate::common::{NamedDataPoint, increment_named_data_points};
+
+use super::{PluginDataArray, PluginDataArrayView};
+
+#[wasm_bindgen]
+impl PluginDataArrayView {
+    /// Number of plugins by the most severe advisory affecting their
+    /// dependencies. Plugins without advisories are counted as "None".
+    pub fn dependency_advisory_severity_distribution(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            let label = repo_data
+                .dependency_advisories
+                .iter()
+                .map(|advisory| advisory.severity)
+                .max()
+                .map_or("None", |severity| severity.as_label());
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+
+    /// Number of plugins affected per vulnerable package, most common first.
+    pub fn vulnerable_dependency_distribution(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            let mut seen = HashSet::new();
+            for advisory in &repo_data.dependency_advisories {
+                if seen.insert(advisory.package.as_str()) {
+                    increment_named_data_points(&mut points, &advisory.package, 1.0);
+                }
+            }
+        });
+
+        sort_descending(&mut points);
+        points
+    }
+
+    /// Number of plugins affected per advisory id, most common first.
+    pub fn dependency_advisory_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            let mut seen = HashSet::new();
+            for advisory in &repo_data.dependency_advisories {
+                if seen.insert(advisory.id.as_str()) {
+                    increment_named_data_points(&mut points, &advisory.id, 1.0);
+                }
+            }
+        });
+
+        sort_descending(&mut points);
+        points
+    }
+
+    /// Return plugin IDs with at least one advisory for `package`.
+    pub fn vulnerable_dependency_plugin_ids(
+        &self,
+        data: &PluginDataArray,
+        package: &str,
+    ) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .iter_data(data)
+            .filter_map(|item| {
+                let repo_data = item.repo_data()?;
+                repo_data
+                    .dependency_advisories
+                    .iter()
+                    .any(|advisory| advisory.package == package)
+                    .then(|| item.id())
+            })
+            .collect();
+
+        ids.sort();
+        ids
+    }
+}
+
+fn sort_descending(points: &mut [NamedDataPoint]) {
+    points.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
@@ -0,0 +1,124 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    common::{NamedDataPoint, increment_named_data_points, to_percentage},
+    plugin::ci::CiWorkflowPurpose,
+};
+
+use super::{PluginDataArray, PluginDataArrayView};
+
+#[wasm_bindgen]
+impl PluginDataArrayView {
+    /// Percentage of plugins with a GitHub Actions workflow for each purpose.
+    pub fn ci_workflow_purpose_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            if repo_data.ci.workflows.is_empty() {
+                increment_named_data_points(&mut points, "No workflows", 1.0);
+                return;
+            }
+            for purpose in [
+                CiWorkflowPurpose::Build,
+                CiWorkflowPurpose::Lint,
+                CiWorkflowPurpose::Test,
+                CiWorkflowPurpose::Release,
+                CiWorkflowPurpose::Other,
+            ] {
+                if repo_data.ci.has_workflow_purpose(purpose) {
+                    increment_named_data_points(&mut points, purpose.as_label(), 1.0);
+                }
+            }
+        });
+
+        points.iter_mut().for_each(|point| {
+            to_percentage(&mut point.value, self.indices.len() as f64);
+        });
+        points
+    }
+
+    /// Percentage of plugins with each release automation, tooling and
+    /// community file.
+    pub fn repo_hygiene_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let ci = &repo_data.ci;
+
+            if ci.versions_json.is_some() {
+                increment_named_data_points(&mut points, "versions.json", 1.0);
+            }
+            if ci.has_version_bump_script {
+                increment_named_data_points(&mut points, "Version bump script", 1.0);
+            }
+            for tool in &ci.tools {
+                increment_named_data_points(&mut points, tool.as_label(), 1.0);
+            }
+            if ci.has_contributing_guide {
+                increment_named_data_points(&mut points, "Contributing guide", 1.0);
+            }
+            if ci.has_security_policy {
+                increment_named_data_points(&mut points, "Security policy", 1.0);
+            }
+        });
+
+        points.iter_mut().for_each(|point| {
+            to_percentage(&mut point.value, self.indices.len() as f64);
+        });
+        points
+    }
+
+    /// Number of plugins whose `versions.json` lists their latest release.
+    pub fn versions_json_release_consistency(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let Some(latest_version) = repo_data.latest_release_tag.as_deref().or_else(|| {
+                repo_data
+                    .manifest
+                    .as_ref()
+                    .and_then(|manifest| manifest.version.as_deref())
+            }) else {
+                return;
+            };
+
+            let label = match repo_data.ci.versions_json_lists(latest_version) {
+                Some(true) => "Lists latest release",
+                Some(false) => "Missing latest release",
+                None => "No versions.json",
+            };
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+
+    /// Return plugin IDs that configure `tool`, e.g. "ESLint".
+    pub fn repo_tool_plugin_ids(&self, data: &PluginDataArray, tool: &str) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .iter_data(data)
+            .filter_map(|item| {
+                let repo_data = item.repo_data()?;
+                repo_data
+                    .ci
+                    .tools
+                    .iter()
+                    .any(|configured| configured.as_label() == tool)
+                    .then(|| item.id())
+            })
+            .collect();
+
+        ids.sort();
+        ids
+    }
+}
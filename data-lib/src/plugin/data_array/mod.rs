@@ -5,9 +5,14 @@ use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::plugin::{PluginData, PluginExtraData, full::FullPluginData};
 
+mod advisories;
+mod ci;
 mod downloads;
 mod licenses;
+mod obsidian_api;
 mod repo_metrics;
+mod testing;
+mod tsconfig;
 
 #[derive(Debug, Clone)]
 #[wasm_bindgen]
@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    common::{NamedDataPoint, StackedNamedDataPoint, increment_named_data_points},
+    version::Version,
+};
+
+use super::{
+    PluginDataArray, PluginDataArrayView, repo_metrics::increment_stacked_named_data_points,
+};
+
+#[wasm_bindgen]
+impl PluginDataArrayView {
+    /// Number of plugins using each deprecated Obsidian API.
+    pub fn deprecated_api_usage_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            for usage in &repo_data.deprecated_api_usages {
+                increment_named_data_points(&mut points, &usage.label, 1.0);
+            }
+        });
+
+        points
+    }
+
+    /// Number of plugins per minor version of the declared `obsidian`
+    /// typings, e.g. "1.4".
+    pub fn obsidian_typings_version_distribution(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            if repo_data.obsidian_typings_range.is_none() {
+                return;
+            }
+
+            let label = repo_data.obsidian_typings_version().map_or_else(
+                || "Unknown".to_string(),
+                |version| format!("{}.{}", version.major, version.minor),
+            );
+            increment_named_data_points(&mut points, &label, 1.0);
+        });
+
+        points.sort_by(|a, b| {
+            let version = |name: &str| Version::parse(name);
+            match (version(&a.name), version(&b.name)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.name.cmp(&b.name),
+            }
+        });
+        points
+    }
+
+    /// How the declared `obsidian` typings compare to the manifest's
+    /// `minAppVersion`.
+    pub fn obsidian_typings_min_app_version_comparison(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let Some(typings) = repo_data.obsidian_typings_version() else {
+                return;
+            };
+            let Some(min_app_version) = item
+                .min_app_version()
+                .and_then(|version| Version::parse(&version))
+            else {
+                return;
+            };
+
+            let label = match typings.get_patch().cmp(&min_app_version.get_patch()) {
+                Ordering::Greater => "Typings newer than minAppVersion",
+                Ordering::Equal => "Typings match minAppVersion",
+                Ordering::Less => "Typings older than minAppVersion",
+            };
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+
+    /// Number of plugins per year of their last update, stacked by the minor
+    /// version of the declared `minAppVersion`.
+    pub fn min_app_version_over_time(&self, data: &PluginDataArray) -> Vec<StackedNamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(min_app_version) = item
+                .min_app_version()
+                .and_then(|version| Version::parse(&version))
+            else {
+                return;
+            };
+
+            let year = item.last_updated().year.to_string();
+            let layer = format!("{}.{}", min_app_version.major, min_app_version.minor);
+            increment_stacked_named_data_points(&mut points, &year, &layer, 1.0);
+        });
+
+        points.sort_by(|a, b| {
+            a.name.cmp(&b.name).then_with(|| {
+                match (Version::parse(&a.layer), Version::parse(&b.layer)) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    _ => a.layer.cmp(&b.layer),
+                }
+            })
+        });
+        points
+    }
+
+    /// How the declared `minAppVersion` compares to the newest Obsidian API
+    /// the plugin calls.
+    pub fn min_app_version_api_usage_comparison(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let Some(required) = repo_data.required_min_app_version() else {
+                return;
+            };
+            let Some(min_app_version) = item
+                .min_app_version()
+                .and_then(|version| Version::parse(&version))
+            else {
+                return;
+            };
+
+            let label = if required.get_patch() > min_app_version.get_patch() {
+                "minAppVersion older than used APIs"
+            } else {
+                "minAppVersion covers used APIs"
+            };
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+
+    /// Return plugin IDs whose `minAppVersion` is older than an API they call.
+    pub fn min_app_version_older_than_api_usage_plugin_ids(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .iter_data(data)
+            .filter_map(|item| {
+                let repo_data = item.repo_data()?;
+                let min_app_version = Version::parse(&item.min_app_version()?)?;
+                if repo_data
+                    .api_requirements_newer_than(&min_app_version)
+                    .is_empty()
+                {
+                    None
+                } else {
+                    Some(item.id())
+                }
+            })
+            .collect();
+
+        ids.sort();
+        ids
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
         FILE_EXT_INCLUDED, LOC_EXCLUDED, NamedDataPoint, StackedNamedDataPoint,
         increment_named_data_points, to_percentage,
     },
-    plugin::PluginRepoDataPoints,
+    plugin::{PluginRepoDataPoints, detectors::DetectedTool},
 };
 
 use super::{PluginDataArray, PluginDataArrayView};
@@ -536,6 +536,129 @@ impl PluginDataArrayView {
         data_points
     }
 
+    /// Number of plugins shipping each locale.
+    pub fn i18n_locale_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut data_points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            for locale in &repo_data.i18n_locales {
+                increment_named_data_points(&mut data_points, &locale.locale, 1.0);
+            }
+        });
+
+        data_points.sort_by(|a, b| {
+            b.value
+                .total_cmp(&a.value)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        data_points
+    }
+
+    /// Number of plugins by how many locales they ship.
+    pub fn i18n_locale_count_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        const BUCKETS: &[(usize, &str)] = &[(1, "1"), (3, "2-3"), (6, "4-6"), (10, "7-10")];
+        let mut data_points = BUCKETS
+            .iter()
+            .map(|(_, name)| *name)
+            .chain(["11+"])
+            .map(|name| NamedDataPoint {
+                name: name.to_string(),
+                value: 0.0,
+            })
+            .collect::<Vec<_>>();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let count = repo_data.i18n_locales.len();
+            if count == 0 {
+                return;
+            }
+
+            let index = BUCKETS
+                .iter()
+                .position(|(max, _)| count <= *max)
+                .unwrap_or(BUCKETS.len());
+            data_points[index].value += 1.0;
+        });
+
+        data_points
+    }
+
+    /// Number of translated locales by completeness relative to each plugin's
+    /// base locale.
+    pub fn i18n_completeness_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut data_points = ["100%", "90-99%", "50-89%", "<50%"]
+            .into_iter()
+            .map(|name| NamedDataPoint {
+                name: name.to_string(),
+                value: 0.0,
+            })
+            .collect::<Vec<_>>();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+
+            for locale in &repo_data.i18n_locales {
+                if repo_data.i18n_base_locale.as_deref() == Some(locale.locale.as_str()) {
+                    continue;
+                }
+                let Some(completeness) = locale.completeness else {
+                    continue;
+                };
+
+                let index = match completeness {
+                    c if c >= 1.0 => 0,
+                    c if c >= 0.9 => 1,
+                    c if c >= 0.5 => 2,
+                    _ => 3,
+                };
+                data_points[index].value += 1.0;
+            }
+        });
+
+        data_points
+    }
+
+    /// Number of plugins by how they follow the language Obsidian runs in.
+    pub fn i18n_language_detection_distribution(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        let mut data_points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            if repo_data.i18n_locales.is_empty() && !repo_data.has_i18n_dependencies {
+                return;
+            }
+
+            if repo_data.i18n_language_detection.is_empty() {
+                increment_named_data_points(&mut data_points, "None detected", 1.0);
+            }
+            for detection in &repo_data.i18n_language_detection {
+                let label = match detection.as_str() {
+                    "moment_locale" => "moment.locale()",
+                    "get_language" => "getLanguage()",
+                    "local_storage_language" => "localStorage language",
+                    other => other,
+                };
+                increment_named_data_points(&mut data_points, label, 1.0);
+            }
+        });
+
+        data_points
+    }
+
     pub fn i18n_plugin_ids(&self, data: &PluginDataArray) -> Vec<String> {
         let mut ids = Vec::new();
 
@@ -573,7 +696,7 @@ fn top_release_sizes(view: &PluginDataArrayView, data: &PluginDataArray) -> Vec<
     tmp
 }
 
-fn increment_stacked_named_data_points(
+pub(super) fn increment_stacked_named_data_points(
     points: &mut Vec<StackedNamedDataPoint>,
     name: &str,
     layer: &str,
@@ -0,0 +1,122 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    common::{NamedDataPoint, increment_named_data_points, to_percentage},
+    plugin::testing::PluginTestData,
+};
+
+use super::{PluginDataArray, PluginDataArrayView};
+
+#[wasm_bindgen]
+impl PluginDataArrayView {
+    /// Number of plugins by the number of test files in the repo.
+    pub fn test_file_count_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        const BUCKETS: &[(u32, &str)] = &[(0, "0"), (1, "1"), (5, "2-5"), (20, "6-20")];
+        bucketed_test_counts(self, data, BUCKETS, "21+", |tests| tests.test_file_count)
+    }
+
+    /// Number of plugins by the number of `it(...)` and `test(...)` cases.
+    pub fn test_case_count_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        const BUCKETS: &[(u32, &str)] = &[(0, "0"), (10, "1-10"), (50, "11-50"), (200, "51-200")];
+        bucketed_test_counts(self, data, BUCKETS, "201+", |tests| tests.test_case_count)
+    }
+
+    /// Number of plugins by lines of test code per line of source code.
+    pub fn test_to_source_ratio_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut data_points = ["No tests", "<5%", "5-19%", "20-49%", "50%+"]
+            .into_iter()
+            .map(|name| NamedDataPoint {
+                name: name.to_string(),
+                value: 0.0,
+            })
+            .collect::<Vec<_>>();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let Some(ratio) = repo_data.tests.test_to_source_ratio() else {
+                return;
+            };
+
+            let index = match ratio {
+                _ if repo_data.tests.test_lines_of_code == 0 => 0,
+                r if r < 0.05 => 1,
+                r if r < 0.2 => 2,
+                r if r < 0.5 => 3,
+                _ => 4,
+            };
+            data_points[index].value += 1.0;
+        });
+
+        data_points
+    }
+
+    /// Percentage of plugins with test files, a test script, a coverage
+    /// config and a testing framework dependency.
+    pub fn test_setup_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let tests = &repo_data.tests;
+
+            for (label, present) in [
+                ("Test files", tests.test_file_count > 0),
+                ("Test cases", tests.test_case_count > 0),
+                ("Test script", tests.has_test_script),
+                ("Coverage config", tests.has_coverage_config),
+                (
+                    "Testing framework",
+                    !repo_data.testing_frameworks.is_empty(),
+                ),
+            ] {
+                if present {
+                    increment_named_data_points(&mut points, label, 1.0);
+                }
+            }
+        });
+
+        points.iter_mut().for_each(|point| {
+            to_percentage(&mut point.value, self.indices.len() as f64);
+        });
+        points
+    }
+}
+
+/// Number of plugins per bucket of a test count. Each bucket holds counts up
+/// to its maximum, `overflow` everything above the last one.
+fn bucketed_test_counts(
+    view: &PluginDataArrayView,
+    data: &PluginDataArray,
+    buckets: &[(u32, &str)],
+    overflow: &str,
+    count: impl Fn(&PluginTestData) -> u32,
+) -> Vec<NamedDataPoint> {
+    let mut data_points = buckets
+        .iter()
+        .map(|(_, name)| *name)
+        .chain([overflow])
+        .map(|name| NamedDataPoint {
+            name: name.to_string(),
+            value: 0.0,
+        })
+        .collect::<Vec<_>>();
+
+    view.iter_data(data).for_each(|item| {
+        let Some(repo_data) = item.repo_data() else {
+            return;
+        };
+        let count = count(&repo_data.tests);
+
+        let index = buckets
+            .iter()
+            .position(|(max, _)| count <= *max)
+            .unwrap_or(buckets.len());
+        data_points[index].value += 1.0;
+    });
+
+    data_points
+}
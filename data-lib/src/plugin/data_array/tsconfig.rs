@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    common::{NamedDataPoint, increment_named_data_points, to_percentage},
+    plugin::tsconfig::TsConfigData,
+};
+
+use super::{PluginDataArray, PluginDataArrayView};
+
+#[wasm_bindgen]
+impl PluginDataArrayView {
+    /// Number of TypeScript plugins by how strict their tsconfig is.
+    pub fn tsconfig_strictness_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            if !repo_data.uses_typescript {
+                return;
+            }
+
+            let label = match &repo_data.tsconfig {
+                Some(tsconfig) if tsconfig.strict => "strict",
+                Some(tsconfig) if tsconfig.no_implicit_any => "noImplicitAny only",
+                Some(_) => "Not strict",
+                None => "No tsconfig.json",
+            };
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+
+    /// Number of plugins per tsconfig `target`.
+    pub fn tsconfig_target_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        tsconfig_option_values(self, data, |tsconfig| tsconfig.target.as_deref())
+    }
+
+    /// Number of plugins per tsconfig `module`.
+    pub fn tsconfig_module_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        tsconfig_option_values(self, data, |tsconfig| tsconfig.module.as_deref())
+    }
+
+    /// Number of plugins per tsconfig `moduleResolution`.
+    pub fn tsconfig_module_resolution_distribution(
+        &self,
+        data: &PluginDataArray,
+    ) -> Vec<NamedDataPoint> {
+        tsconfig_option_values(self, data, |tsconfig| tsconfig.module_resolution.as_deref())
+    }
+
+    /// Percentage of plugins with a tsconfig that enable each flag.
+    pub fn tsconfig_flag_distribution(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+        let mut total = 0;
+
+        self.iter_data(data).for_each(|item| {
+            let Some(tsconfig) = item.repo_data().and_then(|repo| repo.tsconfig.as_ref()) else {
+                return;
+            };
+            total += 1;
+
+            for (label, enabled) in [
+                ("strict", tsconfig.strict),
+                ("noImplicitAny", tsconfig.no_implicit_any),
+                ("Source maps", tsconfig.source_map),
+                ("isolatedModules", tsconfig.isolated_modules),
+                (
+                    "Extends a shared config",
+                    !tsconfig.external_extends.is_empty(),
+                ),
+            ] {
+                if enabled {
+                    increment_named_data_points(&mut points, label, 1.0);
+                }
+            }
+        });
+
+        points.iter_mut().for_each(|point| {
+            to_percentage(&mut point.value, total as f64);
+        });
+        points
+    }
+
+    /// Number of plugins whose released main.js uses older, the same or newer
+    /// syntax than their tsconfig `target`. Older syntax usually means the
+    /// bundler downlevels the output.
+    pub fn tsconfig_target_bundle_comparison(&self, data: &PluginDataArray) -> Vec<NamedDataPoint> {
+        let mut points = Vec::new();
+
+        self.iter_data(data).for_each(|item| {
+            let Some(repo_data) = item.repo_data() else {
+                return;
+            };
+            let (Some(tsconfig), Some(estimated)) = (
+                &repo_data.tsconfig,
+                repo_data.estimated_target_es_version.as_deref(),
+            ) else {
+                return;
+            };
+
+            let label = match tsconfig.bundle_compared_to_target(estimated) {
+                Some(Ordering::Less) => "Bundle older than target",
+                Some(Ordering::Equal) => "Bundle matches target",
+                Some(Ordering::Greater) => "Bundle newer than target",
+                None => "Unknown target",
+            };
+            increment_named_data_points(&mut points, label, 1.0);
+        });
+
+        points
+    }
+}
+
+/// Number of plugins per value of a tsconfig option, most common first.
+fn tsconfig_option_values(
+    view: &PluginDataArrayView,
+    data: &PluginDataArray,
+    option: impl Fn(&TsConfigData) -> Option<&str>,
+) -> Vec<NamedDataPoint> {
+    let mut points = Vec::new();
+
+    view.iter_data(data).for_each(|item| {
+        let Some(tsconfig) = item.repo_data().and_then(|repo| repo.tsconfig.as_ref()) else {
+            return;
+        };
+        increment_named_data_points(&mut points, option(tsconfig).unwrap_or("Not set"), 1.0);
+    });
+
+    points.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    points
+}
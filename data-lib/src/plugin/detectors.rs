@@ -0,0 +1,447 @@
+//! Registry of the bundlers, package managers and testing frameworks found in
+//! plugin repos. Each tool is one [`ToolDetector`] entry listing the signals it
+//! is detected from, so supporting a new tool means adding an entry to
+//! [`TOOL_DETECTORS`].
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use DetectionConfidence::{High, Low, Medium};
+use DetectorSignal::{ConfigFile, Dependency, File, Script};
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    Bundler,
+    PackageManager,
+    TestingFramework,
+}
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionConfidence {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionSignalKind {
+    Dependency,
+    File,
+    ConfigFile,
+    Script,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DetectorSignal {
+    /// A `dependencies` or `devDependencies` entry.
+    Dependency(&'static str),
+    /// A file with this name in any directory, e.g. a lock file.
+    File(&'static str),
+    /// A root file whose name starts with this, e.g. `rollup.config.`.
+    ConfigFile(&'static str),
+    /// A package.json script running this command, matched on whole words.
+    Script(&'static str),
+}
+
+impl DetectorSignal {
+    pub fn kind(&self) -> DetectionSignalKind {
+        match self {
+            Dependency(_) => DetectionSignalKind::Dependency,
+            File(_) => DetectionSignalKind::File,
+            ConfigFile(_) => DetectionSignalKind::ConfigFile,
+            Script(_) => DetectionSignalKind::Script,
+        }
+    }
+
+    fn matches(&self, input: &DetectionInput) -> bool {
+        match self {
+            Dependency(name) => input
+                .dependencies
+                .iter()
+                .any(|dependency| dependency == name),
+            File(name) => input.files.iter().any(|file| {
+                file.rsplit_once('/')
+                    .map_or(file.as_str(), |(_, file_name)| file_name)
+                    == *name
+            }),
+            ConfigFile(prefix) => input
+                .files
+                .iter()
+                .any(|file| !file.contains('/') && file.starts_with(prefix)),
+            Script(command) => {
+                let command = command.split_whitespace().collect::<Vec<_>>();
+                input.scripts.values().any(|script| {
+                    script
+                        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|'))
+                        .filter(|token| !token.is_empty())
+                        .collect::<Vec<_>>()
+                        .windows(command.len())
+                        .any(|window| window == command.as_slice())
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ToolDetector {
+    pub kind: ToolKind,
+    /// The serialized name, also shown on the website.
+    pub identifier: &'static str,
+    /// Signals and how certain each one is on its own.
+    pub signals: &'static [(DetectorSignal, DetectionConfidence)],
+}
+
+pub static TOOL_DETECTORS: &[ToolDetector] = &[
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "esbuild",
+        signals: &[
+            (Dependency("esbuild"), Medium),
+            (ConfigFile("esbuild.config."), High),
+            (Script("esbuild"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Rollup",
+        signals: &[
+            (Dependency("rollup"), Medium),
+            (ConfigFile("rollup.config."), High),
+            (Script("rollup"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Webpack",
+        signals: &[
+            (Dependency("webpack"), Medium),
+            (ConfigFile("webpack.config."), High),
+            (Script("webpack"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Vite",
+        signals: &[
+            (Dependency("vite"), Medium),
+            (ConfigFile("vite.config."), High),
+            (Script("vite build"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Turbo",
+        signals: &[(Dependency("turbo"), Medium), (File("turbo.json"), High)],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Rolldown",
+        signals: &[
+            (Dependency("rolldown"), Medium),
+            (ConfigFile("rolldown.config."), High),
+            (Script("rolldown"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "tsup",
+        signals: &[
+            (Dependency("tsup"), Medium),
+            (ConfigFile("tsup.config."), High),
+            (Script("tsup"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::Bundler,
+        identifier: "Bun",
+        signals: &[(Script("bun build"), High)],
+    },
+    ToolDetector {
+        kind: ToolKind::PackageManager,
+        identifier: "npm",
+        signals: &[(File("package-lock.json"), High)],
+    },
+    ToolDetector {
+        kind: ToolKind::PackageManager,
+        identifier: "yarn",
+        signals: &[(File("yarn.lock"), High), (File(".yarnrc.yml"), Medium)],
+    },
+    ToolDetector {
+        kind: ToolKind::PackageManager,
+        identifier: "pnpm",
+        signals: &[
+            (File("pnpm-lock.yaml"), High),
+            (File("pnpm-workspace.yaml"), Medium),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::PackageManager,
+        identifier: "bun",
+        signals: &[
+            (File("bun.lockb"), High),
+            (File("bun.lock"), High),
+            (Script("bun run"), Low),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::PackageManager,
+        identifier: "deno",
+        signals: &[
+            (File("deno.lock"), High),
+            (File("lock.json"), Medium),
+            (File("deno.json"), Medium),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::TestingFramework,
+        identifier: "Jest",
+        signals: &[
+            (Dependency("jest"), Medium),
+            (ConfigFile("jest.config."), High),
+            (Script("jest"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::TestingFramework,
+        identifier: "Mocha",
+        signals: &[
+            (Dependency("mocha"), Medium),
+            (ConfigFile(".mocharc"), High),
+            (Script("mocha"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::TestingFramework,
+        identifier: "Vitest",
+        signals: &[
+            (Dependency("vitest"), Medium),
+            (ConfigFile("vitest.config."), High),
+            (Script("vitest"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::TestingFramework,
+        identifier: "BunTest",
+        signals: &[
+            (Dependency("@types/bun"), Medium),
+            (Dependency("bun-types"), Medium),
+            (Script("bun test"), High),
+        ],
+    },
+    ToolDetector {
+        kind: ToolKind::TestingFramework,
+        identifier: "NodeTest",
+        signals: &[(Script("node --test"), High)],
+    },
+];
+
+/// What the detectors look at in a repo.
+pub struct DetectionInput<'a> {
+    pub files: &'a [String],
+    pub dependencies: &'a [&'a String],
+    pub scripts: &'a BTreeMap<String, String>,
+}
+
+/// A tool found in a repo, with the kinds of signals it was found from.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct ToolDetection {
+    pub kind: ToolKind,
+    pub identifier: String,
+    pub confidence: DetectionConfidence,
+    pub signals: Vec<DetectionSignalKind>,
+}
+
+/// Runs every detector of the registry. A tool gets the confidence of its
+/// strongest matching signal, raised by one level if signals of different
+/// kinds agree.
+pub fn detect_tools(input: &DetectionInput) -> Vec<ToolDetection> {
+    TOOL_DETECTORS
+        .iter()
+        .filter_map(|detector| {
+            let matched = detector
+                .signals
+                .iter()
+                .filter(|(signal, _)| signal.matches(input))
+                .collect::<Vec<_>>();
+            let strongest = matched.iter().map(|(_, confidence)| *confidence).max()?;
+
+            let mut signals = matched
+                .iter()
+                .map(|(signal, _)| signal.kind())
+                .collect::<Vec<_>>();
+            signals.sort();
+            signals.dedup();
+
+            let confidence = match (strongest, signals.len()) {
+                (Low, 2..) => Medium,
+                (_, 2..) => High,
+                (confidence, _) => confidence,
+            };
+            Some(ToolDetection {
+                kind: detector.kind,
+                identifier: detector.identifier.to_string(),
+                confidence,
+                signals,
+            })
+        })
+        .collect()
+}
+
+/// A tool of one [`ToolKind`], serialized as its registry identifier.
+pub trait DetectedTool: Sized {
+    const KIND: ToolKind;
+
+    fn from_detector(detector: &'static ToolDetector) -> Self;
+
+    fn detector(&self) -> &'static ToolDetector;
+
+    fn get_identifier(&self) -> &'static str {
+        self.detector().identifier
+    }
+
+    /// Looks up a tool by identifier, ignoring case.
+    fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::iter_variants().find(|tool| tool.get_identifier().eq_ignore_ascii_case(identifier))
+    }
+
+    fn iter_variants() -> impl Iterator<Item = Self> {
+        TOOL_DETECTORS
+            .iter()
+            .filter(|detector| detector.kind == Self::KIND)
+            .map(Self::from_detector)
+    }
+
+    /// The tools of this kind detected with at least medium confidence.
+    fn from_detections(detections: &[ToolDetection]) -> Vec<Self> {
+        detections
+            .iter()
+            .filter(|detection| detection.kind == Self::KIND && detection.confidence >= Medium)
+            .filter_map(|detection| Self::from_identifier(&detection.identifier))
+            .collect()
+    }
+}
+
+pub(crate) fn serialize_tool<T: DetectedTool, S: serde::Serializer>(
+    tool: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(tool.get_identifier())
+}
+
+pub(crate) fn deserialize_tool<'de, T: DetectedTool, D: serde::Deserializer<'de>>(
+    deserializer: D,
+    name: &str,
+) -> Result<T, D::Error> {
+    let identifier = String::deserialize(deserializer)?;
+    T::from_identifier(&identifier)
+        .ok_or_else(|| serde::de::Error::custom(format!("Unknown {name}: {identifier}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{
+        DetectedTool, DetectionConfidence, DetectionInput, DetectionSignalKind, ToolKind,
+        detect_tools,
+    };
+    use crate::plugin::{bundlers::Bundler, packages::PackageManager, testing::TestingFramework};
+
+    #[test]
+    fn detects_tools_from_dependencies_files_and_scripts() {
+        let files = [
+            "package-lock.json",
+            "esbuild.config.mjs",
+            "docs/yarn.lock",
+            "src/jest.config.js",
+        ]
+        .map(String::from);
+        let esbuild = "esbuild".to_string();
+        let jest = "jest".to_string();
+        let scripts = BTreeMap::from([
+            (
+                "build".to_string(),
+                "tsc -noEmit && node esbuild.config.mjs".to_string(),
+            ),
+            (
+                "test".to_string(),
+                "node --test && bun run lint".to_string(),
+            ),
+        ]);
+
+        let detections = detect_tools(&DetectionInput {
+            files: &files,
+            dependencies: &[&esbuild, &jest],
+            scripts: &scripts,
+        });
+        let summary = detections
+            .iter()
+            .map(|detection| (detection.identifier.as_str(), detection.confidence))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            [
+                ("esbuild", DetectionConfidence::High),
+                ("npm", DetectionConfidence::High),
+                ("yarn", DetectionConfidence::High),
+                ("bun", DetectionConfidence::Low),
+                ("Jest", DetectionConfidence::Medium),
+                ("NodeTest", DetectionConfidence::High),
+            ]
+        );
+        assert_eq!(
+            detections[0].signals,
+            [
+                DetectionSignalKind::Dependency,
+                DetectionSignalKind::ConfigFile
+            ]
+        );
+
+        let package_managers = PackageManager::from_detections(&detections)
+            .iter()
+            .map(|tool| tool.get_identifier())
+            .collect::<Vec<_>>();
+        assert_eq!(package_managers, ["npm", "yarn"]);
+        assert_eq!(Bundler::from_detections(&detections).len(), 1);
+        assert_eq!(
+            TestingFramework::from_detections(&detections)
+                .iter()
+                .map(|tool| tool.detector().kind)
+                .collect::<Vec<_>>(),
+            [ToolKind::TestingFramework; 2]
+        );
+    }
+
+    #[test]
+    fn keeps_serialized_identifiers() {
+        let bundlers: Vec<Bundler> =
+            serde_json::from_str(r#"["esbuild", "rollup", "Vite"]"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&bundlers).unwrap(),
+            r#"["esbuild","Rollup","Vite"]"#
+        );
+
+        let testing_frameworks: Vec<TestingFramework> =
+            serde_json::from_str(r#"["Jest", "BunTest"]"#).unwrap();
+        assert_eq!(testing_frameworks[1].get_identifier(), "BunTest");
+
+        assert!(serde_json::from_str::<PackageManager>(r#""Rollup""#).is_err());
+    }
+}
@@ -9,6 +9,8 @@ use crate::{
     plugin::{
         FundingUrl, LicenseInfo, MainJsApiCapability, MainJsApiDisclosure, PluginData,
         PluginExtraData, PluginRepoData,
+        detectors::DetectedTool,
+        timeline::MainJsReleaseEvent,
         warnings::{PluginWarning, get_plugin_warnings},
     },
 };
@@ -201,6 +203,26 @@ impl FullPluginData {
             .and_then(|manifest| manifest.min_app_version.clone())
     }
 
+    pub fn obsidian_typings_range(&self) -> Option<String> {
+        self.repo_data()
+            .and_then(|r| r.obsidian_typings_range.clone())
+    }
+
+    /// The `minAppVersion` the plugin needs once it stops using deprecated
+    /// APIs.
+    pub fn recommended_min_app_version(&self) -> Option<String> {
+        self.repo_data()
+            .and_then(|r| r.recommended_min_app_version())
+            .map(|version| version.to_fancy_string())
+    }
+
+    /// The newest Obsidian version introducing an API the plugin calls.
+    pub fn required_min_app_version(&self) -> Option<String> {
+        self.repo_data()
+            .and_then(|r| r.required_min_app_version())
+            .map(|version| version.to_fancy_string())
+    }
+
     pub fn is_desktop_only(&self) -> Option<bool> {
         self.repo_data()
             .and_then(|r| r.manifest.as_ref())
@@ -362,6 +384,10 @@ impl FullPluginData {
         self.repo_data().map(|r| r.main_js_api_disclosures.clone())
     }
 
+    pub fn main_js_release_events(&self) -> Option<Vec<MainJsReleaseEvent>> {
+        self.repo_data().map(|r| r.main_js_release_events.clone())
+    }
+
     pub fn latest_release_tag(&self) -> Option<String> {
         self.repo_data().and_then(|r| r.latest_release_tag.clone())
     }
@@ -371,6 +397,11 @@ impl FullPluginData {
             .and_then(|r| r.latest_release_published_at.clone())
     }
 
+    pub fn latest_release_main_js_sha256(&self) -> Option<String> {
+        self.repo_data()
+            .and_then(|r| r.latest_release_main_js_sha256.clone())
+    }
+
     pub fn latest_release_fetch_status(&self) -> Option<String> {
         self.repo_data()
             .and_then(|r| r.latest_release_fetch_status.clone())
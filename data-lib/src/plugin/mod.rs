@@ -9,15 +9,28 @@ use crate::{
     common::{DownloadHistory, EntryChange, NamedDataPoint, VersionHistory},
     input_data::ObsCommunityPlugin,
     license::LicenseDescriptionNested,
-    plugin::{bundlers::Bundler, packages::PackageManager, testing::TestingFramework},
+    plugin::{
+        bundlers::Bundler,
+        ci::{PluginCiData, VersionsJsonIssue},
+        detectors::ToolDetection,
+        packages::PackageManager,
+        testing::{PluginTestData, TestingFramework},
+        timeline::MainJsReleaseEvent,
+        tsconfig::TsConfigData,
+    },
 };
 
 pub mod bundlers;
+pub mod ci;
 pub mod data_array;
+pub mod detectors;
 pub mod full;
 pub mod milestones;
+pub mod obsidian_api;
 pub mod packages;
 pub mod testing;
+pub mod timeline;
+pub mod tsconfig;
 pub mod warnings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,10 +83,34 @@ pub struct PluginRepoData {
     pub package_managers: Vec<PackageManager>,
     pub testing_frameworks: Vec<TestingFramework>,
     pub bundlers: Vec<Bundler>,
+    /// Every registry tool found in the repo, with the signals and confidence
+    /// of the detection. The lists above only hold confident detections.
+    #[serde(default)]
+    pub tool_detections: Vec<ToolDetection>,
     pub dependencies: Vec<String>,
     pub dev_dependencies: Vec<String>,
+    /// Packages and versions resolved by the repository's lockfile, including
+    /// transitive dependencies.
+    #[serde(default)]
+    pub resolved_dependencies: Vec<ResolvedDependency>,
+    /// The version range of the `obsidian` typings declared in package.json.
+    #[serde(default)]
+    pub obsidian_typings_range: Option<String>,
+    /// Known advisories affecting `resolved_dependencies`, from the local
+    /// advisory database.
+    #[serde(default)]
+    pub dependency_advisories: Vec<DependencyAdvisory>,
     pub has_test_files: bool,
+    /// Test files, test cases and test tooling.
+    #[serde(default)]
+    pub tests: PluginTestData,
     pub has_beta_manifest: bool,
+    /// Workflows, release automation and repo hygiene files.
+    #[serde(default)]
+    pub ci: PluginCiData,
+    /// The root `tsconfig.json`, if the repo has one that parses.
+    #[serde(default)]
+    pub tsconfig: Option<TsConfigData>,
     pub file_type_counts: HashMap<String, usize>,
     /// The license identifier from the package.json file.
     pub package_json_license: LicenseInfo,
@@ -83,6 +120,19 @@ pub struct PluginRepoData {
     pub lines_of_code: HashMap<String, usize>,
     pub has_i18n_dependencies: bool,
     pub has_i18n_files: bool,
+    /// Locales shipped as translation files, sorted by locale code.
+    #[serde(default)]
+    pub i18n_locales: Vec<LocaleCoverage>,
+    /// The locale other translations are compared against.
+    #[serde(default)]
+    pub i18n_base_locale: Option<String>,
+    /// How the plugin follows Obsidian's language, e.g. `moment_locale` or
+    /// `get_language`.
+    #[serde(default)]
+    pub i18n_language_detection: Vec<String>,
+    /// Whether a built `main.js` is committed at the analyzed tag.
+    #[serde(default)]
+    pub repo_has_committed_main_js: bool,
     pub latest_release_main_js_size_bytes: Option<u64>,
     pub main_js_parse_succeeded: Option<bool>,
     pub main_js_tolerant_parse_required: Option<bool>,
@@ -110,10 +160,42 @@ pub struct PluginRepoData {
     pub main_js_api_capabilities: Vec<MainJsApiCapability>,
     #[serde(default)]
     pub main_js_api_disclosures: Vec<MainJsApiDisclosure>,
+    #[serde(default)]
+    pub deprecated_api_usages: Vec<DeprecatedApiUsage>,
+    /// Obsidian APIs found in main.js whose introduction version is known.
+    #[serde(default)]
+    pub api_version_requirements: Vec<ApiVersionRequirement>,
+    /// How plausibly the released main.js was built from the repository at the
+    /// release tag, from 0 (unrelated) to 1 (matches).
+    #[serde(default)]
+    pub main_js_reproducibility_score: Option<f32>,
+    #[serde(default)]
+    pub main_js_reproducibility_mismatches: Vec<String>,
+    /// Backfilled releases whose main.js added or removed API capabilities or
+    /// hosts compared to the previous one, oldest first. Releases that only
+    /// changed in size are left out.
+    #[serde(default)]
+    pub main_js_release_events: Vec<MainJsReleaseEvent>,
     pub latest_release_tag: Option<String>,
     pub latest_release_published_at: Option<String>,
     pub latest_release_fetch_status: Option<String>,
     #[serde(default)]
+    pub latest_release_styles_css_size_bytes: Option<u64>,
+    /// Fields where the `manifest.json` release asset differs from the
+    /// repository manifest at the release tag or from the community list.
+    #[serde(default)]
+    pub release_manifest_mismatches: Vec<ReleaseManifestMismatch>,
+    /// Problems of `versions.json` compared to the published releases and the
+    /// manifest.
+    #[serde(default)]
+    pub versions_json_issues: Vec<VersionsJsonIssue>,
+    /// SHA-256 of the cached latest release `main.js`.
+    #[serde(default)]
+    pub latest_release_main_js_sha256: Option<String>,
+    /// Release assets whose content changed after the tag was first seen.
+    #[serde(default)]
+    pub release_asset_reuploads: Vec<ReleaseAssetReupload>,
+    #[serde(default)]
     pub analysis_errors: Vec<PluginRepoAnalysisError>,
 }
 
@@ -135,6 +217,119 @@ pub struct MainJsApiDisclosure {
     pub from_capability: String,
 }
 
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[tsify(into_wasm_abi)]
+pub struct ResolvedDependency {
+    pub name: String,
+    pub version: String,
+    /// Whether `name` is listed in package.json. Other versions of the same
+    /// package can still be pulled in transitively.
+    pub direct: bool,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct DeprecatedApiUsage {
+    pub id: String,
+    pub label: String,
+    pub replacement: String,
+    /// The first Obsidian version that ships `replacement`.
+    pub replacement_since: String,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[tsify(into_wasm_abi)]
+pub struct LocaleCoverage {
+    pub locale: String,
+    pub key_count: u32,
+    /// Share of the base locale's keys this locale translates, from 0 to 1.
+    pub completeness: Option<f32>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct ApiVersionRequirement {
+    /// The API capability the symbol was matched by.
+    pub id: String,
+    pub label: String,
+    pub symbol: String,
+    /// The first Obsidian version that ships `symbol`.
+    pub since: String,
+    /// The date of the first Obsidian desktop release at or after `since`.
+    pub since_date: Option<String>,
+}
+
+#[derive(
+    Tsify, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvisorySeverity {
+    Unknown,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl AdvisorySeverity {
+    /// Parses the GitHub advisory labels used in OSV `database_specific`.
+    pub fn from_label(label: &str) -> Self {
+        match label.to_ascii_lowercase().as_str() {
+            "low" => Self::Low,
+            "moderate" | "medium" => Self::Moderate,
+            "high" => Self::High,
+            "critical" => Self::Critical,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Unknown => "Unknown",
+            Self::Low => "Low",
+            Self::Moderate => "Moderate",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct DependencyAdvisory {
+    /// The OSV id, e.g. `GHSA-xxxx-xxxx-xxxx`.
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub severity: AdvisorySeverity,
+    pub package: String,
+    pub version: String,
+    /// Versions that fix the advisory, if the advisory names any.
+    pub fixed_versions: Vec<String>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct ReleaseAssetReupload {
+    pub tag: String,
+    /// File name of the asset, e.g. `main.js`.
+    pub asset: String,
+    pub previous_sha256: String,
+    pub sha256: String,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct ReleaseManifestMismatch {
+    pub field: String,
+    pub release_value: String,
+    /// `None` if the repository manifest is missing or lacks the field.
+    pub repo_value: Option<String>,
+    /// `None` for fields that the community list does not contain.
+    pub community_list_value: Option<String>,
+}
+
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[tsify(into_wasm_abi)]
 pub struct MainJsApiEvidence {
@@ -163,6 +358,8 @@ pub enum PluginRepoAnalysisError {
     RepositoryScan,
     #[serde(rename = "main_js_analysis_too_large")]
     MainJsAnalysisTooLarge,
+    #[serde(rename = "lockfile_parse_error")]
+    LockfileParse,
 }
 
 #[derive(Debug, Error)]
@@ -197,6 +394,13 @@ pub enum PluginRepoAnalysisDetailError {
         #[source]
         source: serde_json::Error,
     },
+
+    #[error("lockfile_parse_error: plugin {plugin_id}: {lock_file}: {message}")]
+    LockfileParse {
+        plugin_id: String,
+        lock_file: String,
+        message: String,
+    },
 }
 
 impl PluginRepoAnalysisDetailError {
@@ -207,6 +411,7 @@ impl PluginRepoAnalysisDetailError {
             Self::ManifestParse { .. } => PluginRepoAnalysisError::ManifestParse,
             Self::PackageJsonRead { .. } => PluginRepoAnalysisError::PackageJsonRead,
             Self::PackageJsonParse { .. } => PluginRepoAnalysisError::PackageJsonParse,
+            Self::LockfileParse { .. } => PluginRepoAnalysisError::LockfileParse,
         }
     }
 }
@@ -223,6 +428,7 @@ impl PluginRepoAnalysisError {
             Self::RepoAnalysis => "repo_analysis_error",
             Self::RepositoryScan => "repository_scan_error",
             Self::MainJsAnalysisTooLarge => "main_js_analysis_too_large",
+            Self::LockfileParse => "lockfile_parse_error",
         }
     }
 
@@ -240,6 +446,7 @@ impl PluginRepoAnalysisError {
             "repo_missing" => Self::RepoMissing,
             "repository_scan_error" => Self::RepositoryScan,
             "main_js_analysis_too_large" => Self::MainJsAnalysisTooLarge,
+            "lockfile_parse_error" => Self::LockfileParse,
             _ => {
                 if error.contains("does not exist") {
                     Self::RepoMissing
@@ -0,0 +1,94 @@
+use crate::{
+    plugin::{ApiVersionRequirement, PluginRepoData},
+    version::Version,
+};
+
+/// The lowest version allowed by an npm range such as `^1.4.11`,
+/// `>=0.15.0 <2` or `1.x`. Tags like `latest` and git URLs have no version.
+pub fn range_minimum_version(range: &str) -> Option<Version> {
+    range
+        .split("||")
+        .filter_map(|alternative| {
+            let lower = alternative.split_whitespace().next()?;
+            if lower.starts_with('<') {
+                return Some(Version::default());
+            }
+            let lower = lower.trim_start_matches(['^', '~', '>', '=', 'v']);
+            let lower = lower
+                .split('.')
+                .map(|part| {
+                    if part == "x" || part == "*" {
+                        "0"
+                    } else {
+                        part
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            Version::parse(&lower)
+        })
+        .min()
+}
+
+impl PluginRepoData {
+    /// The lowest `obsidian` typings version the declared range allows.
+    pub fn obsidian_typings_version(&self) -> Option<Version> {
+        self.obsidian_typings_range
+            .as_deref()
+            .and_then(range_minimum_version)
+    }
+
+    /// The `minAppVersion` needed to replace every deprecated API the plugin
+    /// uses.
+    pub fn recommended_min_app_version(&self) -> Option<Version> {
+        self.deprecated_api_usages
+            .iter()
+            .filter_map(|usage| Version::parse(&usage.replacement_since))
+            .max()
+    }
+
+    /// The newest Obsidian version introducing an API the plugin calls.
+    pub fn required_min_app_version(&self) -> Option<Version> {
+        self.api_version_requirements
+            .iter()
+            .filter_map(|requirement| Version::parse(&requirement.since))
+            .max()
+    }
+
+    /// API usages that need a newer app than `min_app_version`.
+    pub fn api_requirements_newer_than(
+        &self,
+        min_app_version: &Version,
+    ) -> Vec<ApiVersionRequirement> {
+        self.api_version_requirements
+            .iter()
+            .filter(|requirement| {
+                Version::parse(&requirement.since)
+                    .is_some_and(|since| since.get_patch() > min_app_version.get_patch())
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::range_minimum_version;
+    use crate::version::Version;
+
+    #[test]
+    fn reads_the_lower_bound_of_npm_ranges() {
+        let version = |major, minor, patch| Some(Version::new(major, minor, patch, None));
+
+        assert_eq!(range_minimum_version("^1.4.11"), version(1, 4, 11));
+        assert_eq!(range_minimum_version("~0.15.9"), version(0, 15, 9));
+        assert_eq!(range_minimum_version(">=1.1.0 <2"), version(1, 1, 0));
+        assert_eq!(range_minimum_version("1.x"), version(1, 0, 0));
+        assert_eq!(range_minimum_version("^1.5.0 || ^1.2.0"), version(1, 2, 0));
+        assert_eq!(range_minimum_version("latest"), None);
+        assert_eq!(
+            range_minimum_version("github:obsidianmd/obsidian-api"),
+            None
+        );
+    }
+}
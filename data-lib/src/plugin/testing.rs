@@ -1,69 +1,44 @@
-use serde::Serialize;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TestingFramework {
-    Jest,
-    Mocha,
-    Vitest,
-    BunTest,
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use super::detectors::{DetectedTool, ToolDetector, ToolKind, deserialize_tool, serialize_tool};
+
+/// How much of a plugin's code is covered by tests, measured on the repo.
+#[derive(Tsify, Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct PluginTestData {
+    pub test_file_count: u32,
+    /// `it(...)` and `test(...)` calls in the test files.
+    pub test_case_count: u32,
+    pub test_lines_of_code: u32,
+    /// Lines of code of the source files that end up in the bundle.
+    pub source_lines_of_code: u32,
+    pub has_coverage_config: bool,
+    /// A `test` or `test:*` script in package.json other than npm's
+    /// placeholder.
+    pub has_test_script: bool,
 }
 
-impl TestingFramework {
-    pub fn get_package_name(&self) -> Vec<&'static str> {
-        match self {
-            TestingFramework::Jest => vec!["jest"],
-            TestingFramework::Mocha => vec!["mocha"],
-            TestingFramework::Vitest => vec!["vitest"],
-            TestingFramework::BunTest => vec!["@types/bun", "bun-types"],
-        }
-    }
-
-    pub fn get_identifier(&self) -> &'static str {
-        match self {
-            TestingFramework::Jest => "Jest",
-            TestingFramework::Mocha => "Mocha",
-            TestingFramework::Vitest => "Vitest",
-            TestingFramework::BunTest => "BunTest",
-        }
+impl PluginTestData {
+    pub fn test_to_source_ratio(&self) -> Option<f64> {
+        (self.source_lines_of_code > 0)
+            .then(|| f64::from(self.test_lines_of_code) / f64::from(self.source_lines_of_code))
     }
+}
 
-    pub fn from_identifier(identifier: &str) -> Option<Self> {
-        match identifier {
-            "Jest" => Some(TestingFramework::Jest),
-            "Mocha" => Some(TestingFramework::Mocha),
-            "Vitest" => Some(TestingFramework::Vitest),
-            "BunTest" => Some(TestingFramework::BunTest),
-            _ => None,
-        }
-    }
+/// A testing framework from the detector registry, e.g. Jest or Vitest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestingFramework(&'static ToolDetector);
 
-    pub fn matches_dependency(&self, dependency: &str) -> bool {
-        self.get_package_name().contains(&dependency)
-    }
+impl DetectedTool for TestingFramework {
+    const KIND: ToolKind = ToolKind::TestingFramework;
 
-    pub fn iter_variants() -> impl Iterator<Item = TestingFramework> {
-        [
-            TestingFramework::Jest,
-            TestingFramework::Mocha,
-            TestingFramework::Vitest,
-            TestingFramework::BunTest,
-        ]
-        .iter()
-        .cloned()
+    fn from_detector(detector: &'static ToolDetector) -> Self {
+        Self(detector)
     }
 
-    pub fn find_testing_frameworks(dependencies: &[&String]) -> Vec<Self> {
-        let mut package_managers = Vec::new();
-
-        for dependency in dependencies {
-            for tf in TestingFramework::iter_variants() {
-                if tf.matches_dependency(dependency) && !package_managers.contains(&tf) {
-                    package_managers.push(tf);
-                }
-            }
-        }
-
-        package_managers
+    fn detector(&self) -> &'static ToolDetector {
+        self.0
     }
 }
 
@@ -72,18 +47,15 @@ impl Serialize for TestingFramework {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(self.get_identifier())
+        serialize_tool(self, serializer)
     }
 }
 
-impl<'de> serde::Deserialize<'de> for TestingFramework {
+impl<'de> Deserialize<'de> for TestingFramework {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let identifier = String::deserialize(deserializer)?;
-        TestingFramework::from_identifier(&identifier).ok_or_else(|| {
-            serde::de::Error::custom(format!("Unknown testing framework: {identifier}"))
-        })
+        deserialize_tool(deserializer, "testing framework")
     }
 }
@@ -0,0 +1,61 @@
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::plugin::MainJsApiCapability;
+
+/// Release-by-release analysis of a plugin's released `main.js`, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginReleaseTimeline {
+    pub id: String,
+    pub releases: Vec<MainJsReleaseAnalysis>,
+    /// One event per release after the first, in the same order.
+    #[serde(default)]
+    pub events: Vec<MainJsReleaseEvent>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[tsify(into_wasm_abi)]
+pub struct MainJsReleaseAnalysis {
+    pub tag: String,
+    pub published_at: Option<String>,
+    pub size_bytes: u64,
+    /// Content hash from the release asset index. The analysis is only reused
+    /// while the asset still has this hash.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub estimated_target_es_version: Option<String>,
+    pub is_probably_minified: Option<bool>,
+    pub minification_score: Option<f32>,
+    #[serde(default)]
+    pub bundler_fingerprints: Vec<String>,
+    #[serde(default)]
+    pub api_capabilities: Vec<MainJsApiCapability>,
+    #[serde(default)]
+    pub known_api_host_counts: HashMap<String, u32>,
+}
+
+/// What changed in the released `main.js` compared to the previous release
+/// in the timeline.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct MainJsReleaseEvent {
+    pub tag: String,
+    pub previous_tag: String,
+    pub published_at: Option<String>,
+    pub size_delta_bytes: i64,
+    pub added_capabilities: Vec<String>,
+    pub removed_capabilities: Vec<String>,
+    pub added_hosts: Vec<String>,
+    pub removed_hosts: Vec<String>,
+}
+
+impl MainJsReleaseEvent {
+    /// Whether the release changed anything besides the bundle size.
+    pub fn has_changes(&self) -> bool {
+        !self.added_capabilities.is_empty()
+            || !self.removed_capabilities.is_empty()
+            || !self.added_hosts.is_empty()
+            || !self.removed_hosts.is_empty()
+    }
+}
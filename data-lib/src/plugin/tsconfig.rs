@@ -0,0 +1,79 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Compiler options of the repo's `tsconfig.json`, merged along its `extends`
+/// chain.
+#[derive(Tsify, Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[tsify(into_wasm_abi)]
+pub struct TsConfigData {
+    /// The config files that were read, starting with `tsconfig.json`.
+    pub files: Vec<String>,
+    /// `extends` targets outside the repo, e.g. shared configs from npm
+    /// packages. Options they set are unknown.
+    pub external_extends: Vec<String>,
+    pub strict: bool,
+    /// `noImplicitAny`, which defaults to `strict`.
+    pub no_implicit_any: bool,
+    /// Lowercased, e.g. `es2018` or `esnext`.
+    pub target: Option<String>,
+    pub module: Option<String>,
+    pub module_resolution: Option<String>,
+    /// `sourceMap` or `inlineSourceMap`.
+    pub source_map: bool,
+    pub isolated_modules: bool,
+}
+
+impl TsConfigData {
+    /// How the ES version estimated from the bundled main.js compares to the
+    /// tsconfig `target`. `Less` means the bundle uses older syntax than the
+    /// target, e.g. because the bundler downlevels it.
+    pub fn bundle_compared_to_target(&self, estimated_es_version: &str) -> Option<Ordering> {
+        Some(es_version_rank(estimated_es_version)?.cmp(&es_version_rank(self.target.as_deref()?)?))
+    }
+}
+
+/// Orders ES version labels such as `ES5`, `ES6`, `es2020` and `ESNext`.
+pub fn es_version_rank(label: &str) -> Option<u32> {
+    let version = label.to_ascii_lowercase();
+    let version = version.strip_prefix("es")?;
+    match version {
+        "next" => Some(u32::MAX),
+        "3" => Some(3),
+        "5" => Some(5),
+        "6" => Some(2015),
+        year => year.parse().ok().filter(|year| *year >= 2015),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{TsConfigData, es_version_rank};
+
+    #[test]
+    fn compares_bundle_and_target_es_versions() {
+        assert_eq!(es_version_rank("ES6"), es_version_rank("es2015"));
+        assert!(es_version_rank("ESNext") > es_version_rank("ES2022"));
+        assert_eq!(es_version_rank("commonjs"), None);
+
+        let tsconfig = TsConfigData {
+            target: Some("es2018".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            tsconfig.bundle_compared_to_target("ES6"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            tsconfig.bundle_compared_to_target("ES2018"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            TsConfigData::default().bundle_compared_to_target("ES6"),
+            None
+        );
+    }
+}
@@ -4,7 +4,12 @@ use tsify::Tsify;
 use crate::{
     commit::StringCommit,
     date::Date,
-    plugin::{LicenseInfo, full::FullPluginData},
+    plugin::{
+        AdvisorySeverity, ApiVersionRequirement, DependencyAdvisory, DeprecatedApiUsage,
+        LicenseInfo, ReleaseAssetReupload, ReleaseManifestMismatch, ci::VersionsJsonIssue,
+        full::FullPluginData,
+    },
+    version::Version,
 };
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -23,6 +28,12 @@ pub enum PluginWarning {
     Removed(PluginWarningRemoved),
     MissingRequiredManifestFields(PluginWarningMissingRequiredManifestFields),
     MismatchedManifestData(PluginWarningMismatchedManifestData),
+    MismatchedReleaseManifest(PluginWarningMismatchedReleaseManifest),
+    ReuploadedReleaseAsset(PluginWarningReuploadedReleaseAsset),
+    VersionsJsonInconsistent(PluginWarningVersionsJsonInconsistent),
+    VulnerableDependency(PluginWarningVulnerableDependency),
+    DeprecatedApiUsage(PluginWarningDeprecatedApiUsage),
+    MinAppVersionOlderThanApiUsage(PluginWarningMinAppVersionOlderThanApiUsage),
     Unlicensed(PluginWarningUnlicensed),
     NoLicense(PluginWarningNoLicense),
     MismatchedLicense(PluginWarningMismatchedLicense),
@@ -61,6 +72,56 @@ pub struct PluginWarningMismatchedManifestDataField {
     pub community_list_value: String,
 }
 
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningMismatchedReleaseManifest {
+    pub severity: PluginWarningSeverity,
+    pub release_tag: String,
+    pub data: Vec<ReleaseManifestMismatch>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningReuploadedReleaseAsset {
+    pub severity: PluginWarningSeverity,
+    pub data: Vec<ReleaseAssetReupload>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningVersionsJsonInconsistent {
+    pub severity: PluginWarningSeverity,
+    pub data: Vec<VersionsJsonIssue>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningVulnerableDependency {
+    pub severity: PluginWarningSeverity,
+    pub data: Vec<DependencyAdvisory>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningDeprecatedApiUsage {
+    pub severity: PluginWarningSeverity,
+    pub min_app_version: Option<String>,
+    /// The `minAppVersion` needed once all replacements are used.
+    pub recommended_min_app_version: Option<String>,
+    pub data: Vec<DeprecatedApiUsage>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct PluginWarningMinAppVersionOlderThanApiUsage {
+    pub severity: PluginWarningSeverity,
+    pub min_app_version: String,
+    /// The newest version introducing an API the plugin calls.
+    pub required_min_app_version: String,
+    /// The API usages that are missing from `min_app_version`.
+    pub data: Vec<ApiVersionRequirement>,
+}
+
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct PluginWarningMissingRequiredManifestFields {
@@ -107,6 +168,11 @@ pub fn get_plugin_warnings(data: &FullPluginData) -> Vec<PluginWarning> {
 
     get_missing_warnings(data, &mut warnings);
     get_manifest_warnings(data, &mut warnings);
+    get_release_manifest_warnings(data, &mut warnings);
+    get_release_asset_reupload_warnings(data, &mut warnings);
+    get_versions_json_warnings(data, &mut warnings);
+    get_dependency_advisory_warnings(data, &mut warnings);
+    get_obsidian_api_warnings(data, &mut warnings);
     get_license_warnings(data, &mut warnings);
     get_inactivity_warnings(data, &mut warnings);
 
@@ -245,6 +311,123 @@ fn get_manifest_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning
     }
 }
 
+fn get_release_manifest_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
+    let Some(repo) = data.repo_data() else {
+        return;
+    };
+    if repo.release_manifest_mismatches.is_empty() {
+        return;
+    }
+
+    warnings.push(PluginWarning::MismatchedReleaseManifest(
+        PluginWarningMismatchedReleaseManifest {
+            severity: PluginWarningSeverity::CAUTION,
+            release_tag: repo
+                .latest_release_tag
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            data: repo.release_manifest_mismatches.clone(),
+        },
+    ));
+}
+
+fn get_versions_json_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
+    let Some(repo) = data.repo_data() else {
+        return;
+    };
+    if repo.versions_json_issues.is_empty() {
+        return;
+    }
+
+    warnings.push(PluginWarning::VersionsJsonInconsistent(
+        PluginWarningVersionsJsonInconsistent {
+            severity: PluginWarningSeverity::CAUTION,
+            data: repo.versions_json_issues.clone(),
+        },
+    ));
+}
+
+fn get_release_asset_reupload_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
+    let Some(repo) = data.repo_data() else {
+        return;
+    };
+    if repo.release_asset_reuploads.is_empty() {
+        return;
+    }
+
+    warnings.push(PluginWarning::ReuploadedReleaseAsset(
+        PluginWarningReuploadedReleaseAsset {
+            severity: PluginWarningSeverity::CAUTION,
+            data: repo.release_asset_reuploads.clone(),
+        },
+    ));
+}
+
+fn get_dependency_advisory_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
+    let Some(repo) = data.repo_data() else {
+        return;
+    };
+    if repo.dependency_advisories.is_empty() {
+        return;
+    }
+
+    let severity = if repo
+        .dependency_advisories
+        .iter()
+        .any(|advisory| advisory.severity >= AdvisorySeverity::High)
+    {
+        PluginWarningSeverity::DANGER
+    } else {
+        PluginWarningSeverity::CAUTION
+    };
+
+    warnings.push(PluginWarning::VulnerableDependency(
+        PluginWarningVulnerableDependency {
+            severity,
+            data: repo.dependency_advisories.clone(),
+        },
+    ));
+}
+
+fn get_obsidian_api_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
+    let Some(repo) = data.repo_data() else {
+        return;
+    };
+    let min_app_version = data.min_app_version();
+
+    if !repo.deprecated_api_usages.is_empty() {
+        warnings.push(PluginWarning::DeprecatedApiUsage(
+            PluginWarningDeprecatedApiUsage {
+                severity: PluginWarningSeverity::CAUTION,
+                min_app_version: min_app_version.clone(),
+                recommended_min_app_version: repo
+                    .recommended_min_app_version()
+                    .map(|version| version.to_fancy_string()),
+                data: repo.deprecated_api_usages.clone(),
+            },
+        ));
+    }
+
+    // Calling an API the declared minimum app does not have fails at runtime
+    // for users who have not updated Obsidian.
+    if let Some(min_app_version) = &min_app_version
+        && let Some(declared) = Version::parse(min_app_version)
+        && let Some(required) = repo.required_min_app_version()
+    {
+        let missing = repo.api_requirements_newer_than(&declared);
+        if !missing.is_empty() {
+            warnings.push(PluginWarning::MinAppVersionOlderThanApiUsage(
+                PluginWarningMinAppVersionOlderThanApiUsage {
+                    severity: PluginWarningSeverity::CAUTION,
+                    min_app_version: min_app_version.clone(),
+                    required_min_app_version: required.to_fancy_string(),
+                    data: missing,
+                },
+            ));
+        }
+    }
+}
+
 fn get_license_warnings(data: &FullPluginData, warnings: &mut Vec<PluginWarning>) {
     let repo = if let Some(extended) = &data.extended
         && let Ok(repo) = &extended.repo
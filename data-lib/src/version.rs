@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 fn version_parser<'a>() -> impl Parser<'a, &'a str, Version> {
-    let number = text::int(10).map(|s: &str| s.parse::<u32>().unwrap());
+    let number =
+        text::int(10).try_map(|s: &str, _| s.parse::<u32>().map_err(|_| EmptyErr::default()));
     let pre_release = just('-')
         .then(text::ident())
         .map(|(_, s): (_, &str)| s.to_string());
@@ -146,3 +147,10 @@ fn version_compare_with_pre_release() {
     assert!(v1 < v4);
     assert!(v2 < v4);
 }
+
+#[test]
+fn version_parse_rejects_overflowing_components() {
+    assert_eq!(Version::parse("1.2.3"), Some(Version::new(1, 2, 3, None)));
+    assert_eq!(Version::parse("20240615123.0.0"), None);
+    assert!(!Version::validate("1.20240615123.0"));
+}
@@ -19,6 +19,12 @@ pub const PLUGIN_DATA_PATH: &str = "./out/plugin-data";
 pub const PLUGIN_REPO_PATH: &str = "./out/plugin-repos";
 pub const PLUGIN_REPO_DATA_PATH: &str = "./out/plugin-repo-data";
 pub const PLUGIN_RELEASE_MAIN_JS_PATH: &str = "./out/plugin-release-mainjs";
+pub const PLUGIN_RELEASE_ASSET_BLOB_PATH: &str = "./out/plugin-release-blobs";
+pub const PLUGIN_RELEASE_QUARANTINE_PATH: &str = "./out/plugin-release-quarantine";
+pub const PLUGIN_RELEASE_TIMELINE_PATH: &str = "./out/plugin-release-timeline";
+pub const PLUGIN_REPO_ARCHIVE_PATH: &str = "./out/plugin-repo-archive.git";
+pub const PLUGIN_REPO_SNAPSHOT_PATH: &str = "./out/plugin-repo-snapshots";
+pub const PLUGIN_REPO_SNAPSHOT_DATA_PATH: &str = "./out/plugin-repo-snapshot-data";
 pub const THEME_DATA_PATH: &str = "./out/theme-data";
 
 pub const RELEASE_GITHUB_RAW_PATH: &str = "./out/releases-github-raw";
@@ -28,13 +34,40 @@ pub const RELEASE_CHANGELOG_PATH: &str = "./out/releases-changelog";
 pub const STATE_PATH: &str = "./out/state";
 pub const PLUGIN_RELEASE_ENRICHMENT_STATE_PATH: &str =
     "./out/state/plugin-release-enrichment-state.json";
+pub const PLUGIN_RELEASE_BACKFILL_STATE_PATH: &str =
+    "./out/state/plugin-release-backfill-state.json";
 pub const CLONE_STATE_PATH: &str = "./out/state/clone-state.json";
 pub const RELEASE_STATS_STATE_PATH: &str = "./out/state/release-stats-state.json";
+pub const CACHE_ACCESS_STATE_PATH: &str = "./out/state/cache-access-state.json";
 pub const LATEST_DATA_UPDATE_SUMMARY_PATH: &str = "./out/state/latest-data-update-summary.json";
 
+/// A checkout of github/advisory-database, whose advisories are OSV records.
+pub const DEFAULT_ADVISORY_DATABASE_PATH: &str = "../advisory-database/advisories/github-reviewed";
+/// Overrides `DEFAULT_ADVISORY_DATABASE_PATH` with another OSV file or
+/// directory, e.g. an extracted OSV npm export.
+pub const ADVISORY_DATABASE_PATH_ENV: &str = "ADVISORY_DATABASE_PATH";
+
 pub const DEFAULT_CLONE_REFRESH_DAYS: i64 = 3;
 pub const DEFAULT_CLONE_TIMEOUT_SECONDS: u64 = 60;
+pub const DEFAULT_CLONE_BLOB_LIMIT_KB: u64 = 1024;
+pub const DEFAULT_CLONE_MAX_REPO_MB: u64 = 250;
 pub const DEFAULT_MAX_CLONE_THREADS: usize = 8;
 pub const DEFAULT_RELEASE_STATS_REFRESH_DAYS: i64 = 3;
+pub const DEFAULT_RELEASE_BACKFILL_MAX_RELEASES: usize = 10;
+pub const DEFAULT_RELEASE_BACKFILL_MAX_AGE_DAYS: i64 = 730;
+pub const DEFAULT_RELEASE_GRAPHQL_BATCH_SIZE: usize = 50;
 
 pub const GITHUB_RATE_LIMIT_MODE_ENV: &str = "GITHUB_RATE_LIMIT_MODE";
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+/// Comma or whitespace separated tokens that are used in addition to
+/// `GITHUB_TOKEN`.
+pub const GITHUB_TOKENS_ENV: &str = "GITHUB_TOKENS";
+/// Share of the GitHub rate limit that low-priority requests leave for
+/// high-priority ones.
+pub const GITHUB_LOW_PRIORITY_RESERVE_PERCENT: u64 = 10;
+/// Below this share of the rate limit, requests are spread out until the
+/// limit resets instead of being sent as fast as possible.
+pub const GITHUB_THROTTLE_BELOW_PERCENT: u64 = 20;
+/// GitHub asks to wait at least a minute after a secondary rate limit that
+/// comes without a `retry-after` header.
+pub const GITHUB_SECONDARY_LIMIT_WAIT_SECONDS: i64 = 60;
@@ -0,0 +1,249 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use hashbrown::HashMap;
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::security::{validate_existing_path_under, validate_github_repo_slug};
+
+pub const REPO_FORGE_CONFIG_ENV: &str = "REPO_FORGE_CONFIG";
+pub const REPO_MIRROR_DIR_ENV: &str = "REPO_MIRROR_DIR";
+
+const DEFAULT_GITLAB_HOST: &str = "gitlab.com";
+
+/// A host that plugin repositories can be cloned from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Forge {
+    #[default]
+    Github,
+    Gitlab {
+        #[serde(default = "default_gitlab_host")]
+        host: String,
+    },
+    Codeberg,
+    /// A directory of bare repositories laid out as `{dir}/{owner}/{name}.git`.
+    LocalMirror {
+        dir: PathBuf,
+    },
+}
+
+/// Where a single repo (or all repos of an owner) should be cloned from.
+/// `repo` renames the slug on the target forge, e.g. for a mirror that lives
+/// under a different owner.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ForgeTarget {
+    #[serde(flatten)]
+    pub forge: Forge,
+    #[serde(default)]
+    pub repo: Option<String>,
+}
+
+/// Why a GitHub slug could not be resolved to a clone URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoUrlError {
+    /// The slug, the mapped slug or the forge host failed validation.
+    Invalid(String),
+    /// The local mirror has no bare repository for the slug.
+    MirrorMissing(PathBuf),
+}
+
+impl fmt::Display for RepoUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(error) => f.write_str(error),
+            Self::MirrorMissing(path) => {
+                write!(f, "local mirror does not exist: {}", path.display())
+            }
+        }
+    }
+}
+
+/// Mapping from GitHub slugs in the community plugin list to clone sources.
+///
+/// Lookups try the exact `owner/name` key first, then `owner/*`, and fall back
+/// to `default` with the unchanged slug.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+pub struct ForgeConfig {
+    #[serde(default)]
+    pub default: Forge,
+    #[serde(default)]
+    pub repos: HashMap<String, ForgeTarget>,
+}
+
+impl ForgeConfig {
+    /// Reads the mapping from the JSON file named by `REPO_FORGE_CONFIG`.
+    /// `REPO_MIRROR_DIR` overrides the default forge with a local mirror.
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = match std::env::var(REPO_FORGE_CONFIG_ENV) {
+            Ok(path) if !path.is_empty() => {
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|error| format!("failed to read forge config {path}: {error}"))?;
+                serde_json::from_str::<Self>(&content)
+                    .map_err(|error| format!("failed to parse forge config {path}: {error}"))?
+            }
+            _ => Self::default(),
+        };
+
+        if let Ok(dir) = std::env::var(REPO_MIRROR_DIR_ENV)
+            && !dir.is_empty()
+        {
+            config.default = Forge::LocalMirror {
+                dir: PathBuf::from(dir),
+            };
+        }
+
+        Ok(config)
+    }
+
+    fn target_for(&self, repo: &str) -> (&Forge, String) {
+        if let Some(target) = self.repos.get(repo) {
+            return (
+                &target.forge,
+                target.repo.clone().unwrap_or(repo.to_string()),
+            );
+        }
+
+        if let Some((owner, _)) = repo.split_once('/')
+            && let Some(target) = self.repos.get(&format!("{owner}/*"))
+        {
+            return (
+                &target.forge,
+                target.repo.clone().unwrap_or(repo.to_string()),
+            );
+        }
+
+        (&self.default, repo.to_string())
+    }
+
+    /// Resolves the clone URL for a GitHub slug. Both the source slug and the
+    /// mapped slug must pass the usual slug validation.
+    pub fn repo_url(&self, repo: &str) -> Result<String, RepoUrlError> {
+        validate_github_repo_slug(repo).map_err(RepoUrlError::Invalid)?;
+        let (forge, target_repo) = self.target_for(repo);
+        validate_github_repo_slug(&target_repo).map_err(RepoUrlError::Invalid)?;
+
+        match forge {
+            Forge::Github => Ok(format!("https://github.com/{target_repo}.git")),
+            Forge::Gitlab { host } => {
+                validate_forge_host(host).map_err(RepoUrlError::Invalid)?;
+                Ok(format!("https://{host}/{target_repo}.git"))
+            }
+            Forge::Codeberg => Ok(format!("https://codeberg.org/{target_repo}.git")),
+            Forge::LocalMirror { dir } => local_mirror_url(dir, &target_repo),
+        }
+    }
+}
+
+fn default_gitlab_host() -> String {
+    DEFAULT_GITLAB_HOST.to_string()
+}
+
+fn validate_forge_host(host: &str) -> Result<(), String> {
+    if host.is_empty()
+        || host.len() > 253
+        || host.starts_with(['.', '-'])
+        || !host
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch == '-')
+    {
+        return Err(format!("forge host contains unsafe characters: {host:?}"));
+    }
+
+    Ok(())
+}
+
+/// Builds a `file://` URL so that git keeps honoring `--depth` for local
+/// mirrors. The mirror must exist and must not escape `dir` via symlinks.
+fn local_mirror_url(dir: &Path, repo: &str) -> Result<String, RepoUrlError> {
+    let path = dir.join(format!("{repo}.git"));
+    if !path.is_dir() {
+        return Err(RepoUrlError::MirrorMissing(path));
+    }
+    validate_existing_path_under(dir, &path).map_err(RepoUrlError::Invalid)?;
+
+    let path = path.canonicalize().map_err(|error| {
+        RepoUrlError::Invalid(format!("failed to canonicalize local mirror path: {error}"))
+    })?;
+    Url::from_file_path(&path).map(String::from).map_err(|_| {
+        RepoUrlError::Invalid(format!(
+            "local mirror path is not absolute: {}",
+            path.display()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Forge, ForgeConfig, RepoUrlError};
+
+    #[test]
+    fn resolves_mapped_forges_and_falls_back_to_github() {
+        let config: ForgeConfig = serde_json::from_str(
+            r#"{
+                "repos": {
+                    "owner/moved": { "kind": "codeberg", "repo": "mirror/moved" },
+                    "group/*": { "kind": "gitlab" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.repo_url("owner/repo").unwrap(),
+            "https://github.com/owner/repo.git"
+        );
+        assert_eq!(
+            config.repo_url("owner/moved").unwrap(),
+            "https://codeberg.org/mirror/moved.git"
+        );
+        assert_eq!(
+            config.repo_url("group/plugin").unwrap(),
+            "https://gitlab.com/group/plugin.git"
+        );
+        assert!(config.repo_url("../repo").is_err());
+    }
+
+    #[test]
+    fn rejects_unsafe_mapped_slugs_and_hosts() {
+        let config: ForgeConfig = serde_json::from_str(
+            r#"{
+                "repos": {
+                    "owner/escape": { "kind": "github", "repo": "../../etc" },
+                    "owner/host": { "kind": "gitlab", "host": "evil.com/path?" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(config.repo_url("owner/escape").is_err());
+        assert!(config.repo_url("owner/host").is_err());
+    }
+
+    #[test]
+    fn resolves_existing_local_mirrors_only() {
+        let dir = std::env::temp_dir().join(format!(
+            "obsidian-stats-forge-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        std::fs::create_dir_all(dir.join("owner/repo.git")).unwrap();
+        let config = ForgeConfig {
+            default: Forge::LocalMirror { dir: dir.clone() },
+            ..ForgeConfig::default()
+        };
+
+        let existing = config.repo_url("owner/repo");
+        let missing = config.repo_url("owner/missing");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(existing.unwrap().starts_with("file:///"));
+        assert_eq!(
+            missing,
+            Err(RepoUrlError::MirrorMissing(dir.join("owner/missing.git")))
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::pipeline::run_data_pipeline;
 pub mod alerts;
 pub mod constants;
 pub mod file_utils;
+pub mod forge;
 pub mod git_utils;
 pub mod github;
 pub mod latest_data_update;
@@ -24,11 +25,20 @@ fn main() {
     load_env();
     alerts::install_panic_hook();
 
-    let Some(options) = parse_cli_args() else {
+    let Some(command) = parse_cli_args() else {
         return;
     };
 
-    if let Err(error) = run_data_pipeline(options) {
+    let result = match command {
+        CliCommand::Pipeline(options) => run_data_pipeline(options),
+        CliCommand::AnalyzeSnapshot { plugin_id, tag } => {
+            plugins::analysis::analyze_snapshot(&plugin_id, &tag)
+        }
+        CliCommand::BackfillReleases => pipeline::run_release_backfill(),
+        CliCommand::Gc => pipeline::run_cache_gc(),
+    };
+
+    if let Err(error) = result {
         alerts::print_summary();
         eprintln!("Data pipeline failed: {error}");
     }
@@ -38,14 +48,54 @@ fn main() {
     }
 }
 
-fn parse_cli_args() -> Option<pipeline::PipelineOptions> {
+enum CliCommand {
+    Pipeline(pipeline::PipelineOptions),
+    AnalyzeSnapshot { plugin_id: String, tag: String },
+    BackfillReleases,
+    Gc,
+}
+
+fn parse_cli_args() -> Option<CliCommand> {
+    let mut args = std::env::args().skip(1).peekable();
+
+    if args.peek().map(String::as_str) == Some("analyze-snapshot") {
+        args.next();
+        let (Some(plugin_id), Some(tag), None) = (args.next(), args.next(), args.next()) else {
+            eprintln!("analyze-snapshot expects <plugin-id> <tag>");
+            print_usage();
+            std::process::exit(2);
+        };
+        return Some(CliCommand::AnalyzeSnapshot { plugin_id, tag });
+    }
+
+    if args.peek().map(String::as_str) == Some("backfill-releases") {
+        args.next();
+        if let Some(arg) = args.next() {
+            eprintln!("Unknown argument: {arg}");
+            print_usage();
+            std::process::exit(2);
+        }
+        return Some(CliCommand::BackfillReleases);
+    }
+
+    if args.peek().map(String::as_str) == Some("gc") {
+        args.next();
+        if let Some(arg) = args.next() {
+            eprintln!("Unknown argument: {arg}");
+            print_usage();
+            std::process::exit(2);
+        }
+        return Some(CliCommand::Gc);
+    }
+
     let mut options = pipeline::PipelineOptions::default();
 
-    for arg in std::env::args().skip(1) {
+    for arg in args {
         match arg.as_str() {
             "--force" => options.force = true,
             "--no-clone" => options.no_clone = true,
             "--no-release" => options.no_release = true,
+            "--archive" => options.archive = true,
             "-h" | "--help" => {
                 print_usage();
                 return None;
@@ -58,13 +108,25 @@ fn parse_cli_args() -> Option<pipeline::PipelineOptions> {
         }
     }
 
-    Some(options)
+    Some(CliCommand::Pipeline(options))
 }
 
 fn print_usage() {
-    println!("Usage: data [--force] [--no-clone] [--no-release]");
+    println!("Usage: data [--force] [--no-clone] [--no-release] [--archive]");
+    println!("       data analyze-snapshot <plugin-id> <tag>");
+    println!("       data backfill-releases");
+    println!("       data gc");
     println!();
     println!("  --force       Ignore refresh windows and refresh cached GitHub data.");
     println!("  --no-clone    Skip repository recloning but run the remaining pipeline steps.");
     println!("  --no-release  Skip release acquisition but run the remaining pipeline steps.");
+    println!("  --archive     Store a snapshot of every successful clone in the repo archive.");
+    println!();
+    println!(
+        "  analyze-snapshot  Re-run repo analysis against an archived (plugin, tag) snapshot."
+    );
+    println!("  backfill-releases Download main.js of older releases and build release timelines.");
+    println!(
+        "  gc                Prune cached repos and release assets; CACHE_QUOTA_MB caps their size."
+    );
 }
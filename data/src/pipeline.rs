@@ -4,11 +4,14 @@ use crate::{
     alerts,
     latest_data_update::build_latest_data_update_summary,
     plugins::{
-        analysis::extract_analysis_data,
+        analysis::{build_release_timelines, extract_analysis_data},
+        cache_gc::collect_cache_garbage,
         clone_repos::clone_plugin_repos,
         data::{build_plugin_stats, read_plugin_data},
         license::process_licenses,
-        release_acquisition::acquire_plugin_release_main_js,
+        release_acquisition::{
+            ReleaseBackfillLimits, acquire_plugin_release_main_js, backfill_plugin_release_main_js,
+        },
     },
     release::data::build_release_stats,
     theme::data::build_theme_stats,
@@ -37,6 +40,7 @@ pub struct PipelineOptions {
     pub force: bool,
     pub no_clone: bool,
     pub no_release: bool,
+    pub archive: bool,
 }
 
 fn process_plugin_licenses_step() -> Result<(), Box<dyn Error>> {
@@ -53,6 +57,22 @@ fn acquire_plugin_releases_step(force: bool, no_release: bool) -> Result<(), Box
     acquire_plugin_release_main_js(&plugin_data, force)
 }
 
+/// Downloads `main.js` for older releases within the configured limits and
+/// rebuilds the per-plugin release timelines from the cache.
+pub fn run_release_backfill() -> Result<(), Box<dyn Error>> {
+    let limits = ReleaseBackfillLimits::from_env();
+    let plugin_data = read_plugin_data()?;
+    backfill_plugin_release_main_js(&plugin_data, &limits)?;
+    build_release_timelines(&plugin_data, &limits)
+}
+
+/// Removes cache entries of gone plugins and superseded release tags and
+/// enforces the cache quota.
+pub fn run_cache_gc() -> Result<(), Box<dyn Error>> {
+    let plugin_data = read_plugin_data()?;
+    collect_cache_garbage(&plugin_data)
+}
+
 pub fn run_data_pipeline(options: PipelineOptions) -> Result<(), Box<dyn Error>> {
     let pipeline: Vec<PipelineStep> = vec![
         PipelineStep {
@@ -65,7 +85,9 @@ pub fn run_data_pipeline(options: PipelineOptions) -> Result<(), Box<dyn Error>>
         },
         PipelineStep {
             label: "Cloning plugin repositories",
-            run: Box::new(move || clone_plugin_repos(options.force, options.no_clone)),
+            run: Box::new(move || {
+                clone_plugin_repos(options.force, options.no_clone, options.archive)
+            }),
         },
         PipelineStep {
             label: "Acquiring plugin release assets",
@@ -87,6 +109,10 @@ pub fn run_data_pipeline(options: PipelineOptions) -> Result<(), Box<dyn Error>>
             label: "Building latest data update summary",
             run: Box::new(build_latest_data_update_summary),
         },
+        PipelineStep {
+            label: "Collecting cache garbage",
+            run: Box::new(run_cache_gc),
+        },
     ];
 
     for step in &pipeline {
@@ -0,0 +1,427 @@
+//! Matches resolved dependencies against a local advisory database of OSV
+//! records. The database is read from `ADVISORY_DATABASE_PATH`, which can be a
+//! single file or a directory tree of `.json` files. Nothing is fetched over
+//! the network; if the path does not exist, no advisories are reported.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use data_lib::{
+    plugin::{AdvisorySeverity, DependencyAdvisory, ResolvedDependency},
+    version::Version,
+};
+use hashbrown::HashMap;
+use serde::Deserialize;
+
+use crate::constants::{ADVISORY_DATABASE_PATH_ENV, DEFAULT_ADVISORY_DATABASE_PATH};
+
+const NPM_ECOSYSTEM: &str = "npm";
+
+#[derive(Debug, Default)]
+pub(super) struct AdvisoryDatabase {
+    by_package: HashMap<String, Vec<Advisory>>,
+}
+
+/// One affected npm package of an OSV record.
+#[derive(Debug)]
+struct Advisory {
+    id: String,
+    aliases: Vec<String>,
+    summary: Option<String>,
+    severity: AdvisorySeverity,
+    ranges: Vec<Vec<RangeEvent>>,
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RangeEvent {
+    Introduced(Version),
+    Fixed(Version),
+    LastAffected(Version),
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    summary: Option<String>,
+    withdrawn: Option<String>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    database_specific: Option<OsvDatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+    database_specific: Option<OsvDatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvEvent {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvDatabaseSpecific {
+    severity: Option<String>,
+}
+
+impl AdvisoryDatabase {
+    /// Loads the configured database. Files that are not OSV records are
+    /// skipped and counted.
+    pub(super) fn load() -> Self {
+        let path = std::env::var(ADVISORY_DATABASE_PATH_ENV)
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_ADVISORY_DATABASE_PATH.to_string());
+        let path = Path::new(&path);
+        if !path.exists() {
+            println!(
+                "Advisory database: {} not found, skipping dependency advisories",
+                path.display()
+            );
+            return Self::default();
+        }
+
+        let mut database = Self::default();
+        let mut skipped = 0;
+        for file in json_files(path) {
+            let records = fs::read_to_string(&file)
+                .ok()
+                .and_then(|content| parse_osv_records(&content));
+            match records {
+                Some(records) => records
+                    .into_iter()
+                    .for_each(|record| database.insert(record)),
+                None => skipped += 1,
+            }
+        }
+
+        println!(
+            "Advisory database: {} npm packages with advisories from {} ({skipped} files skipped)",
+            database.by_package.len(),
+            path.display()
+        );
+        database
+    }
+
+    fn insert(&mut self, record: OsvRecord) {
+        if record.withdrawn.is_some() {
+            return;
+        }
+        let record_severity = record
+            .database_specific
+            .as_ref()
+            .and_then(|specific| specific.severity.as_deref());
+
+        for affected in record.affected {
+            let Some(package) = affected.package else {
+                continue;
+            };
+            if package.ecosystem != NPM_ECOSYSTEM {
+                continue;
+            }
+
+            let severity = affected
+                .database_specific
+                .as_ref()
+                .and_then(|specific| specific.severity.as_deref())
+                .or(record_severity)
+                .map_or(AdvisorySeverity::Unknown, AdvisorySeverity::from_label);
+            let ranges = affected
+                .ranges
+                .iter()
+                .filter(|range| range.kind == "SEMVER" || range.kind == "ECOSYSTEM")
+                // A range with an unparsable bound is dropped rather than
+                // read as open-ended.
+                .filter_map(|range| range.events.iter().map(RangeEvent::parse).collect())
+                .collect();
+
+            self.by_package
+                .entry(package.name)
+                .or_default()
+                .push(Advisory {
+                    id: record.id.clone(),
+                    aliases: record.aliases.clone(),
+                    summary: record.summary.clone(),
+                    severity,
+                    ranges,
+                    versions: affected.versions,
+                });
+        }
+    }
+
+    /// Advisories affecting `dependencies`, most severe first.
+    pub(super) fn find_advisories(
+        &self,
+        dependencies: &[ResolvedDependency],
+    ) -> Vec<DependencyAdvisory> {
+        let mut findings = Vec::new();
+
+        for dependency in dependencies {
+            let Some(advisories) = self.by_package.get(&dependency.name) else {
+                continue;
+            };
+            let version = parse_version(&dependency.version);
+
+            for advisory in advisories {
+                let listed = advisory.versions.contains(&dependency.version);
+                let in_range = version.as_ref().is_some_and(|version| {
+                    advisory
+                        .ranges
+                        .iter()
+                        .any(|events| range_affects(events, version))
+                });
+                if !listed && !in_range {
+                    continue;
+                }
+
+                findings.push(DependencyAdvisory {
+                    id: advisory.id.clone(),
+                    aliases: advisory.aliases.clone(),
+                    summary: advisory.summary.clone(),
+                    severity: advisory.severity,
+                    package: dependency.name.clone(),
+                    version: dependency.version.clone(),
+                    fixed_versions: advisory.fixed_versions(),
+                });
+            }
+        }
+
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.id.cmp(&b.id))
+                .then_with(|| a.package.cmp(&b.package))
+                .then_with(|| a.version.cmp(&b.version))
+        });
+        findings.dedup_by(|a, b| a.id == b.id && a.package == b.package && a.version == b.version);
+        findings
+    }
+}
+
+impl Advisory {
+    fn fixed_versions(&self) -> Vec<String> {
+        let mut fixed = self
+            .ranges
+            .iter()
+            .flatten()
+            .filter_map(|event| match event {
+                RangeEvent::Fixed(version) => Some(version.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        fixed.sort();
+        fixed.dedup();
+        fixed.iter().map(Version::to_fancy_string).collect()
+    }
+}
+
+impl RangeEvent {
+    fn parse(event: &OsvEvent) -> Option<Self> {
+        if let Some(introduced) = &event.introduced {
+            // "0" means "since the first version".
+            let version = if introduced == "0" {
+                Version::default()
+            } else {
+                parse_version(introduced)?
+            };
+            return Some(Self::Introduced(version));
+        }
+        if let Some(fixed) = &event.fixed {
+            return parse_version(fixed).map(Self::Fixed);
+        }
+        event
+            .last_affected
+            .as_deref()
+            .and_then(parse_version)
+            .map(Self::LastAffected)
+    }
+
+    fn version(&self) -> &Version {
+        match self {
+            Self::Introduced(version) | Self::Fixed(version) | Self::LastAffected(version) => {
+                version
+            }
+        }
+    }
+}
+
+/// Evaluates an OSV range by walking its events in version order.
+fn range_affects(events: &[RangeEvent], version: &Version) -> bool {
+    let mut events = events.iter().collect::<Vec<_>>();
+    events.sort_by(|a, b| a.version().cmp(b.version()));
+
+    let mut affected = false;
+    for event in events {
+        match event {
+            RangeEvent::Introduced(bound) if bound <= version => affected = true,
+            RangeEvent::Fixed(bound) if bound <= version => affected = false,
+            RangeEvent::LastAffected(bound) if bound < version => affected = false,
+            _ => {}
+        }
+    }
+    affected
+}
+
+/// Parses a resolved npm version, ignoring build metadata.
+fn parse_version(version: &str) -> Option<Version> {
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+    Version::parse(version.trim())
+}
+
+/// A file holds either one OSV record or an array of them.
+fn parse_osv_records(content: &str) -> Option<Vec<OsvRecord>> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    match value {
+        serde_json::Value::Array(values) => values
+            .into_iter()
+            .map(|value| serde_json::from_value(value).ok())
+            .collect(),
+        value => serde_json::from_value(value)
+            .ok()
+            .map(|record| vec![record]),
+    }
+}
+
+fn json_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::plugin::{AdvisorySeverity, ResolvedDependency};
+
+    use super::{AdvisoryDatabase, parse_osv_records};
+
+    const LODASH_ADVISORY: &str = r#"{
+        "id": "GHSA-test-0001",
+        "aliases": ["CVE-0000-0001"],
+        "summary": "Prototype pollution in lodash",
+        "affected": [{
+            "package": { "ecosystem": "npm", "name": "lodash" },
+            "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }, { "fixed": "4.17.12" }] }]
+        }, {
+            "package": { "ecosystem": "PyPI", "name": "lodash" },
+            "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }] }]
+        }],
+        "database_specific": { "severity": "HIGH" }
+    }"#;
+
+    fn dependency(name: &str, version: &str) -> ResolvedDependency {
+        ResolvedDependency {
+            name: name.to_string(),
+            version: version.to_string(),
+            direct: false,
+        }
+    }
+
+    fn database(content: &str) -> AdvisoryDatabase {
+        let mut database = AdvisoryDatabase::default();
+        for record in parse_osv_records(content).expect("valid OSV records") {
+            database.insert(record);
+        }
+        database
+    }
+
+    #[test]
+    fn matches_versions_below_the_fix() {
+        let database = database(LODASH_ADVISORY);
+
+        let findings = database.find_advisories(&[
+            dependency("lodash", "4.17.11"),
+            dependency("lodash", "4.17.21"),
+            dependency("moment", "2.29.4"),
+        ]);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "GHSA-test-0001");
+        assert_eq!(findings[0].version, "4.17.11");
+        assert_eq!(findings[0].severity, AdvisorySeverity::High);
+        assert_eq!(findings[0].fixed_versions, vec!["4.17.12".to_string()]);
+    }
+
+    #[test]
+    fn handles_last_affected_listed_versions_and_withdrawn_records() {
+        let database = database(
+            r#"[{
+                "id": "GHSA-test-0002",
+                "affected": [{
+                    "package": { "ecosystem": "npm", "name": "@scope/pkg" },
+                    "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "1.2.0" }, { "last_affected": "1.3.0" }] }],
+                    "versions": ["0.9.0"],
+                    "database_specific": { "severity": "MODERATE" }
+                }]
+            }, {
+                "id": "GHSA-test-0003",
+                "withdrawn": "2024-01-01T00:00:00Z",
+                "affected": [{ "package": { "ecosystem": "npm", "name": "@scope/pkg" }, "versions": ["1.1.0"] }]
+            }]"#,
+        );
+
+        let affected = |version: &str| {
+            !database
+                .find_advisories(&[dependency("@scope/pkg", version)])
+                .is_empty()
+        };
+
+        assert!(affected("0.9.0"));
+        assert!(!affected("1.1.0"));
+        assert!(affected("1.2.0"));
+        assert!(affected("1.3.0"));
+        assert!(!affected("1.3.1"));
+    }
+}
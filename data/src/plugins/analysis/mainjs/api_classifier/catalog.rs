@@ -1,7 +1,9 @@
 use std::{collections::BTreeSet, sync::OnceLock};
 
 use super::custom_matchers;
-use super::rule::{ApiCatalogError, ApiCategory, ApiRule, ApiSeverity, Confidence};
+use super::rule::{
+    ApiCatalogError, ApiCategory, ApiRule, ApiSeverity, Confidence, canonical_rooted_chain,
+};
 
 pub(in crate::plugins::analysis::mainjs) fn obsidian_api_rules() -> &'static [ApiRule] {
     static RULES: OnceLock<Vec<ApiRule>> = OnceLock::new();
@@ -9,6 +11,8 @@ pub(in crate::plugins::analysis::mainjs) fn obsidian_api_rules() -> &'static [Ap
     RULES.get_or_init(build_obsidian_api_rules)
 }
 
+/// `introduced_in` versions follow the `@since` tags of obsidian.d.ts and are
+/// what a plugin's `minAppVersion` is checked against.
 fn build_obsidian_api_rules() -> Vec<ApiRule> {
     let rules = vec![
         ApiRule::builder("network.browser")
@@ -242,6 +246,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
                 "app.vault.process",
                 "app.vault.createFolder",
             ])
+            .introduced_in([("app.vault.process", "1.1.0")])
             .implies(["disclosure.vault_file_write"])
             .build(),
         ApiRule::builder("vault.destructive")
@@ -257,6 +262,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
                 "app.fileManager.renameFile",
                 "app.fileManager.trashFile",
             ])
+            .introduced_in([("app.fileManager.trashFile", "1.6.6")])
             .implies(["disclosure.vault_file_write"])
             .build(),
         ApiRule::builder("vault.enumerate")
@@ -270,6 +276,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
                 "app.vault.getAllLoadedFiles",
                 "app.vault.getAllFolders",
             ])
+            .introduced_in([("app.vault.getAllFolders", "1.6.6")])
             .implies(["disclosure.full_vault_access"])
             .build(),
         ApiRule::builder("vault.folder_ops")
@@ -278,6 +285,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
             .severity(ApiSeverity::Info)
             .confidence(Confidence::High)
             .rooted_member_calls(["app.vault.getFolderByPath", "app.vault.getRoot"])
+            .introduced_in([("app.vault.getFolderByPath", "1.5.7")])
             .build(),
         ApiRule::builder("vault.resources")
             .label("Accesses attachment resource paths")
@@ -419,6 +427,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
             .confidence(Confidence::High)
             .member_reads(["app.workspace.activeEditor"])
             .rooted_member_calls(["app.workspace.getActiveFile"])
+            .introduced_in([("app.workspace.activeEditor", "1.1.1")])
             .build(),
         ApiRule::builder("workspace.editor_commands")
             .label("Registers editor callbacks, menus, or command palette integrations")
@@ -497,6 +506,7 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
             .severity(ApiSeverity::Info)
             .confidence(Confidence::High)
             .member_calls(["this.registerEditorExtension"])
+            .introduced_in([("this.registerEditorExtension", "0.12.8")])
             .implies(["disclosure.editor_behavior"])
             .build(),
         ApiRule::builder("editor.markdown_processing")
@@ -839,6 +849,52 @@ fn build_obsidian_api_rules() -> Vec<ApiRule> {
             )
             .implies(["disclosure.dynamic_code_or_remote_code"])
             .build(),
+        // Deprecated members of obsidian.d.ts. The replacement version is the
+        // minAppVersion a plugin needs once it migrates.
+        ApiRule::builder("deprecated.workspace_active_leaf")
+            .label("Uses the deprecated Workspace.activeLeaf")
+            .category(ApiCategory::Deprecated)
+            .severity(ApiSeverity::Warning)
+            .confidence(Confidence::Medium)
+            .member_reads(["activeLeaf"])
+            .deprecated("Workspace.getActiveViewOfType()", "0.9.16")
+            .build(),
+        ApiRule::builder("deprecated.markdown_view_source_mode")
+            .label("Uses the deprecated MarkdownView.sourceMode")
+            .category(ApiCategory::Deprecated)
+            .severity(ApiSeverity::Warning)
+            .confidence(Confidence::Medium)
+            .member_reads(["sourceMode"])
+            .deprecated("MarkdownView.editor", "0.11.11")
+            .build(),
+        ApiRule::builder("deprecated.codemirror5_editor")
+            .label("Uses the deprecated CodeMirror 5 editor instance")
+            .category(ApiCategory::Deprecated)
+            .severity(ApiSeverity::Warning)
+            .confidence(Confidence::Medium)
+            .member_reads(["cmEditor"])
+            .rooted_member_calls(["app.workspace.iterateCodeMirrors"])
+            .deprecated("MarkdownView.editor", "0.11.11")
+            .build(),
+        ApiRule::builder("deprecated.register_codemirror")
+            .label("Uses the deprecated Plugin.registerCodeMirror")
+            .category(ApiCategory::Deprecated)
+            .severity(ApiSeverity::Warning)
+            .confidence(Confidence::High)
+            .rooted_member_calls(["this.registerCodeMirror"])
+            .deprecated("Plugin.registerEditorExtension()", "0.12.8")
+            .build(),
+        ApiRule::builder("deprecated.workspace_leaf_splitting")
+            .label("Uses deprecated Workspace leaf creation methods")
+            .category(ApiCategory::Deprecated)
+            .severity(ApiSeverity::Warning)
+            .confidence(Confidence::High)
+            .rooted_member_calls([
+                "app.workspace.splitActiveLeaf",
+                "app.workspace.getUnpinnedLeaf",
+            ])
+            .deprecated("Workspace.getLeaf()", "0.16.0")
+            .build(),
     ];
 
     rules
@@ -860,6 +916,19 @@ pub(in crate::plugins::analysis::mainjs) fn validate_catalog(
     }
 
     for rule in rules {
+        let symbols = rule.matcher.evidence_symbols();
+        for introduction in &rule.introduced_in {
+            let introduced = canonical_rooted_chain(&introduction.symbol);
+            if !symbols
+                .iter()
+                .any(|symbol| canonical_rooted_chain(symbol) == introduced)
+            {
+                return Err(ApiCatalogError::UnknownIntroducedSymbol(
+                    introduction.symbol.clone(),
+                ));
+            }
+        }
+
         for reference in &rule.implies {
             if reference.starts_with("disclosure.") && !disclosures.contains(reference) {
                 return Err(ApiCatalogError::UnknownDisclosure(reference.clone()));
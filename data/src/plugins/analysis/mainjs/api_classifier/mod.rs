@@ -12,7 +12,7 @@ pub(in crate::plugins::analysis) use result::ApiClassificationResult;
 pub(in crate::plugins::analysis) use rule::{ApiSeverity, Confidence};
 
 use result::{ApiCapability, ApiEvidence, Disclosure};
-use rule::{ApiCategory, ApiRule};
+use rule::{ApiCategory, ApiRule, canonical_rooted_chain};
 use symbol_index::SymbolIndex;
 
 #[cfg(test)]
@@ -125,6 +125,8 @@ fn emit_static_finding(
             symbol: symbol.to_string(),
             count,
         }],
+        deprecation: None,
+        introductions: Vec::new(),
     });
     result.disclosures.push(Disclosure {
         id: disclosure_id.to_string(),
@@ -150,6 +152,17 @@ fn rule_match(
 }
 
 fn emit_rule(rule: &ApiRule, evidence: Vec<ApiEvidence>, result: &mut ApiClassificationResult) {
+    let introductions = rule
+        .introduced_in
+        .iter()
+        .filter(|introduction| {
+            evidence.iter().any(|evidence| {
+                canonical_rooted_chain(&evidence.symbol)
+                    == canonical_rooted_chain(&introduction.symbol)
+            })
+        })
+        .cloned()
+        .collect();
     result.capabilities.push(ApiCapability {
         id: rule.id.clone(),
         label: rule.label.clone(),
@@ -157,6 +170,8 @@ fn emit_rule(rule: &ApiRule, evidence: Vec<ApiEvidence>, result: &mut ApiClassif
         severity: rule.severity,
         confidence: rule.confidence,
         evidence,
+        deprecation: rule.deprecation.clone(),
+        introductions,
     });
 
     for disclosure_id in &rule.implies {
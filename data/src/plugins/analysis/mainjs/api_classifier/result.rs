@@ -1,4 +1,4 @@
-use super::rule::{ApiCategory, ApiSeverity, Confidence};
+use super::rule::{ApiCategory, ApiDeprecation, ApiIntroduction, ApiSeverity, Confidence};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(in crate::plugins::analysis) struct ApiCapability {
@@ -8,6 +8,9 @@ pub(in crate::plugins::analysis) struct ApiCapability {
     pub(super) severity: ApiSeverity,
     pub(super) confidence: Confidence,
     pub(super) evidence: Vec<ApiEvidence>,
+    pub(super) deprecation: Option<ApiDeprecation>,
+    /// Introductions of the matched symbols only.
+    pub(super) introductions: Vec<ApiIntroduction>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -106,6 +109,14 @@ impl ApiCapability {
     pub(in crate::plugins::analysis) fn evidence(&self) -> &[ApiEvidence] {
         &self.evidence
     }
+
+    pub(in crate::plugins::analysis) fn deprecation(&self) -> Option<&ApiDeprecation> {
+        self.deprecation.as_ref()
+    }
+
+    pub(in crate::plugins::analysis) fn introductions(&self) -> &[ApiIntroduction] {
+        &self.introductions
+    }
 }
 
 impl Disclosure {
@@ -13,4 +13,5 @@ pub(in crate::plugins::analysis::mainjs::api_classifier) enum ApiRuleBuildError
 pub(in crate::plugins::analysis::mainjs) enum ApiCatalogError {
     DuplicateRule(String),
     UnknownDisclosure(String),
+    UnknownIntroducedSymbol(String),
 }
@@ -142,6 +142,27 @@ impl ApiMatcher {
             && self.custom_ast.is_empty()
     }
 
+    /// Symbols this matcher can report as evidence, excluding custom AST
+    /// matchers.
+    pub(in crate::plugins::analysis::mainjs::api_classifier) fn evidence_symbols(
+        &self,
+    ) -> Vec<String> {
+        self.calls
+            .iter()
+            .map(CallMatcher::evidence_symbol)
+            .chain(
+                self.member_calls
+                    .iter()
+                    .map(MemberCallMatcher::evidence_symbol),
+            )
+            .chain(self.member_reads.iter().cloned())
+            .chain(self.imports.iter().cloned())
+            .chain(self.string_literals.iter().cloned())
+            .chain(self.classes.iter().cloned())
+            .chain(self.constructors.iter().cloned())
+            .collect()
+    }
+
     pub(super) fn normalized(mut self) -> Self {
         for call in &mut self.calls {
             call.name = call.name.trim().to_string();
@@ -23,6 +23,43 @@ pub(in crate::plugins::analysis::mainjs) struct ApiRule {
     pub(super) confidence: Confidence,
     pub(super) matcher: ApiMatcher,
     pub(super) implies: Vec<String>,
+    pub(super) deprecation: Option<ApiDeprecation>,
+    pub(super) introduced_in: Vec<ApiIntroduction>,
+}
+
+/// What replaces a deprecated Obsidian API and the first app version that
+/// ships the replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::plugins::analysis) struct ApiDeprecation {
+    pub(super) replacement: String,
+    pub(super) replacement_since: String,
+}
+
+impl ApiDeprecation {
+    pub(in crate::plugins::analysis) fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    pub(in crate::plugins::analysis) fn replacement_since(&self) -> &str {
+        &self.replacement_since
+    }
+}
+
+/// The first Obsidian version that ships the API behind one matcher symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::plugins::analysis) struct ApiIntroduction {
+    pub(super) symbol: String,
+    pub(super) since: String,
+}
+
+impl ApiIntroduction {
+    pub(in crate::plugins::analysis) fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub(in crate::plugins::analysis) fn since(&self) -> &str {
+        &self.since
+    }
 }
 
 impl ApiRule {
@@ -37,6 +74,8 @@ impl ApiRule {
             confidence: None,
             matcher: ApiMatcher::default(),
             implies: Vec::new(),
+            deprecation: None,
+            introduced_in: Vec::new(),
         }
     }
 }
@@ -50,6 +89,8 @@ pub(super) struct ApiRuleBuilder {
     confidence: Option<Confidence>,
     matcher: ApiMatcher,
     implies: Vec<String>,
+    deprecation: Option<ApiDeprecation>,
+    introduced_in: Vec<ApiIntroduction>,
 }
 
 impl ApiRuleBuilder {
@@ -249,6 +290,38 @@ impl ApiRuleBuilder {
         self
     }
 
+    pub(super) fn deprecated(
+        mut self,
+        replacement: impl Into<String>,
+        replacement_since: impl Into<String>,
+    ) -> Self {
+        self.deprecation = Some(ApiDeprecation {
+            replacement: replacement.into(),
+            replacement_since: replacement_since.into(),
+        });
+        self
+    }
+
+    /// Records the Obsidian version that introduced the API behind each
+    /// matcher symbol, as `(symbol, version)` pairs.
+    pub(super) fn introduced_in<I, S, V>(mut self, introductions: I) -> Self
+    where
+        I: IntoIterator<Item = (S, V)>,
+        S: Into<String>,
+        V: Into<String>,
+    {
+        self.introduced_in
+            .extend(
+                introductions
+                    .into_iter()
+                    .map(|(symbol, since)| ApiIntroduction {
+                        symbol: symbol.into(),
+                        since: since.into(),
+                    }),
+            );
+        self
+    }
+
     pub(super) fn build(self) -> Result<ApiRule, ApiRuleBuildError> {
         let label = required_string(self.label, ApiRuleBuildError::MissingLabel)?;
         let category = self.category.ok_or(ApiRuleBuildError::MissingCategory)?;
@@ -275,6 +348,8 @@ impl ApiRuleBuilder {
             confidence,
             matcher,
             implies,
+            deprecation: self.deprecation,
+            introduced_in: self.introduced_in,
         })
     }
 }
@@ -14,6 +14,7 @@ pub(in crate::plugins::analysis) enum ApiCategory {
     Bundle,
     Dependency,
     DynamicCode,
+    Deprecated,
 }
 
 impl ApiCategory {
@@ -33,6 +34,7 @@ impl ApiCategory {
             Self::Bundle => "bundle",
             Self::Dependency => "dependency",
             Self::DynamicCode => "dynamic_code",
+            Self::Deprecated => "deprecated",
         }
     }
 }
@@ -139,3 +139,97 @@ fn parameter_shadowing_does_not_hide_global_calls_in_sibling_scopes() {
 
     assert!(result.has_capability("network.browser"));
 }
+
+#[test]
+fn deprecated_rules_detect_legacy_obsidian_apis_with_their_replacement() {
+    let source = r#"
+            class Plugin {
+                onload() {
+                    const leaf = this.app.workspace.activeLeaf;
+                    const cm = leaf.view.sourceMode.cmEditor;
+                    this.registerCodeMirror((cm) => {});
+                    this.app.workspace.splitActiveLeaf();
+                }
+            }
+        "#;
+    let program = parse_program(source);
+    let result = classify_api_usage(program.as_ref(), obsidian_api_rules());
+
+    for expected in [
+        "deprecated.workspace_active_leaf",
+        "deprecated.markdown_view_source_mode",
+        "deprecated.codemirror5_editor",
+        "deprecated.register_codemirror",
+        "deprecated.workspace_leaf_splitting",
+    ] {
+        assert!(
+            result.has_capability(expected),
+            "missing capability {expected}"
+        );
+    }
+
+    let active_leaf = result
+        .capabilities()
+        .iter()
+        .find(|capability| capability.id() == "deprecated.workspace_active_leaf")
+        .and_then(|capability| capability.deprecation())
+        .expect("deprecated rules carry their replacement");
+    assert_eq!(active_leaf.replacement(), "Workspace.getActiveViewOfType()");
+
+    let modern = parse_program("this.app.workspace.getActiveViewOfType(MarkdownView);");
+    let modern_result = classify_api_usage(modern.as_ref(), obsidian_api_rules());
+    assert!(
+        modern_result
+            .capabilities()
+            .iter()
+            .all(|capability| capability.deprecation().is_none())
+    );
+}
+
+#[test]
+fn capabilities_carry_the_introduction_of_matched_symbols_only() {
+    let source = r#"
+            class Plugin {
+                async onload() {
+                    const editor = this.app.workspace.activeEditor;
+                    await this.app.vault.process(file, (data) => data);
+                    await this.app.vault.modify(file, "");
+                }
+            }
+        "#;
+    let program = parse_program(source);
+    let result = classify_api_usage(program.as_ref(), obsidian_api_rules());
+
+    let introductions = |id: &str| {
+        result
+            .capabilities()
+            .iter()
+            .find(|capability| capability.id() == id)
+            .map(|capability| {
+                capability
+                    .introductions()
+                    .iter()
+                    .map(|introduction| (introduction.symbol(), introduction.since()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    assert_eq!(
+        introductions("workspace.active_file"),
+        vec![("app.workspace.activeEditor", "1.1.1")]
+    );
+    assert_eq!(
+        introductions("vault.write"),
+        vec![("app.vault.process", "1.1.0")]
+    );
+
+    let legacy = parse_program("this.app.vault.modify(file, '');");
+    let legacy_result = classify_api_usage(legacy.as_ref(), obsidian_api_rules());
+    assert!(
+        legacy_result
+            .capabilities()
+            .iter()
+            .all(|capability| capability.introductions().is_empty())
+    );
+}
@@ -85,6 +85,7 @@ fn builder_supports_future_matcher_buckets_and_taxonomy_values() {
         ApiCategory::Browser,
         ApiCategory::Dependency,
         ApiCategory::DynamicCode,
+        ApiCategory::Deprecated,
     ];
     let _severities = [ApiSeverity::Info, ApiSeverity::Warning];
     let _confidences = [Confidence::High, Confidence::Medium, Confidence::Low];
@@ -246,6 +247,31 @@ fn catalog_validation_rejects_unknown_disclosure_ids() {
     );
 }
 
+#[test]
+fn catalog_validation_rejects_introductions_of_unmatched_symbols() {
+    let rules = vec![
+        ApiRule::builder("vault.test")
+            .label("Test vault")
+            .category(ApiCategory::Vault)
+            .severity(ApiSeverity::Info)
+            .confidence(Confidence::High)
+            .rooted_member_calls(["this.app.vault.process"])
+            .introduced_in([
+                ("app.vault.process", "1.1.0"),
+                ("app.vault.getFileByPath", "1.5.7"),
+            ])
+            .build()
+            .unwrap(),
+    ];
+
+    assert_eq!(
+        validate_catalog(&rules),
+        Err(ApiCatalogError::UnknownIntroducedSymbol(
+            "app.vault.getFileByPath".to_string()
+        ))
+    );
+}
+
 mod alias_flow;
 mod capabilities;
 mod classes;
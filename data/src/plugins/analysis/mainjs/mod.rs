@@ -9,11 +9,16 @@ mod check_base64;
 mod check_bundle;
 mod check_es;
 mod check_minified;
+mod check_reproducibility;
 mod check_sourcemap;
 mod check_strings;
 mod check_wasm;
 mod check_worker;
 
+pub(in crate::plugins::analysis) use check_reproducibility::{
+    ReproducibilityResult, SourceFingerprint, compare_release_with_source, fingerprint_source,
+};
+
 pub(super) fn analyze_main_js(source: &str) -> MainJsResult {
     let mut result = MainJsResult::default();
 
@@ -11,29 +11,44 @@ use rayon::{
 };
 
 use self::{
-    pipeline::analyze_plugin,
+    advisories::AdvisoryDatabase,
+    obsidian_releases::ObsidianReleaseTimeline,
+    pipeline::{analyze_plugin, analyze_plugin_snapshot},
     repo_analysis::{read_plugin_version_deprecations, read_removed_plugins},
     run_stats::{ExtraPluginResult, ExtraRunStats},
 };
 
 use crate::{
-    constants::{PLUGIN_RELEASE_ENRICHMENT_STATE_PATH, PLUGIN_REPO_DATA_PATH},
-    file_utils::{read_chunked_data_or_default, write_in_chunks_atomic},
+    constants::{
+        PLUGIN_RELEASE_ENRICHMENT_STATE_PATH, PLUGIN_REPO_DATA_PATH,
+        PLUGIN_REPO_SNAPSHOT_DATA_PATH, PLUGIN_REPO_SNAPSHOT_PATH,
+    },
+    file_utils::{ensure_dir, read_chunked_data_or_default, write_in_chunks_atomic},
     plugins::{
+        cache_gc::record_analyzed_plugins, clone_repos::materialize_snapshot,
         data::read_plugin_data, license::license_compare::LicenseComparer,
         release_acquisition::PluginReleaseState, stats_helper::HelperPluginStore,
     },
     progress::should_log_progress,
-    state::read_json_or_default,
+    security::validated_plugin_path,
+    state::{now_unix_seconds, read_json_or_default, write_json_atomic},
 };
 
+mod advisories;
 mod mainjs;
+mod obsidian_releases;
 mod output;
 mod pipeline;
+mod release_diff;
+mod release_manifest;
 mod repo;
 mod repo_analysis;
 mod run_stats;
+mod timeline;
 mod types;
+mod versions_json;
+
+pub use timeline::build_release_timelines;
 
 const EXTRA_ANALYSIS_THREADS_ENV: &str = "EXTRA_ANALYSIS_THREADS";
 
@@ -64,6 +79,8 @@ pub fn extract_analysis_data() -> Result<(), Box<dyn std::error::Error>> {
     let release_state: PluginReleaseState =
         read_json_or_default(Path::new(PLUGIN_RELEASE_ENRICHMENT_STATE_PATH));
     let helper_store = HelperPluginStore::read()?;
+    let advisory_database = AdvisoryDatabase::load();
+    let obsidian_releases = ObsidianReleaseTimeline::load();
 
     let mut license_comparer = LicenseComparer::new();
     license_comparer.init();
@@ -103,6 +120,8 @@ pub fn extract_analysis_data() -> Result<(), Box<dyn std::error::Error>> {
                         &license_comparer,
                         &release_state,
                         &helper_store,
+                        &advisory_database,
+                        &obsidian_releases,
                         &mut stats,
                     ) {
                         Ok(repo_data) => Ok(repo_data),
@@ -140,6 +159,13 @@ pub fn extract_analysis_data() -> Result<(), Box<dyn std::error::Error>> {
             .collect::<Vec<_>>()
     });
 
+    record_analyzed_plugins(
+        plugin_results
+            .iter()
+            .filter(|result| result.data.repo.is_ok())
+            .map(|result| result.data.id.as_str()),
+    )?;
+
     let mut run_stats = ExtraRunStats::default();
     for result in plugin_results {
         run_stats.merge(result.stats);
@@ -166,10 +192,93 @@ pub fn extract_analysis_data() -> Result<(), Box<dyn std::error::Error>> {
         "  Release main.js scans (failed/skip): {}",
         run_stats.release_main_js_scan_failed
     );
+    println!(
+        "  Release manifest.json mismatches: {}",
+        run_stats.release_manifest_mismatched
+    );
+    println!(
+        "  Plugins with re-uploaded release assets: {}",
+        run_stats.release_asset_reuploaded
+    );
+    println!(
+        "  Plugins with dependency advisories: {}",
+        run_stats.dependency_advisory_matched
+    );
+    println!(
+        "  Plugins with minAppVersion older than used APIs: {}",
+        run_stats.min_app_version_older_than_api_usage
+    );
+    println!(
+        "  Plugins with versions.json issues: {}",
+        run_stats.versions_json_inconsistent
+    );
+
+    Ok(())
+}
+
+/// Re-runs repo analysis against the archived snapshot of `plugin_id` at
+/// `release_tag` and writes the result to
+/// `PLUGIN_REPO_SNAPSHOT_DATA_PATH/{plugin_id}/{release_tag}.json`.
+pub fn analyze_snapshot(
+    plugin_id: &str,
+    release_tag: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let plugin_data = read_plugin_data()?;
+    let plugin = plugin_data
+        .iter()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("unknown plugin id: {plugin_id}"))?;
+
+    let snapshot_path = validated_plugin_path(Path::new(PLUGIN_REPO_SNAPSHOT_PATH), plugin_id)?
+        .join(format!(".tmp-{}", now_unix_seconds()));
+    if let Err(error) = materialize_snapshot(plugin_id, release_tag, &snapshot_path) {
+        let _ = std::fs::remove_dir_all(&snapshot_path);
+        return Err(error.into());
+    }
+
+    let mut license_comparer = LicenseComparer::new();
+    license_comparer.init();
+    let advisory_database = AdvisoryDatabase::load();
+    let obsidian_releases = ObsidianReleaseTimeline::load();
+    let mut stats = ExtraRunStats::default();
+    let result = analyze_plugin_snapshot(
+        plugin,
+        &snapshot_path,
+        release_tag,
+        &license_comparer,
+        &advisory_database,
+        &obsidian_releases,
+        &mut stats,
+    );
+    let _ = std::fs::remove_dir_all(&snapshot_path);
+    let repo_data = result?;
+
+    let output_dir = validated_plugin_path(Path::new(PLUGIN_REPO_SNAPSHOT_DATA_PATH), plugin_id)?;
+    ensure_dir(&output_dir)?;
+    let output_path = output_dir.join(format!("{}.json", sanitize_file_name(release_tag)));
+    write_json_atomic(&output_path, &repo_data)?;
+
+    println!(
+        "Snapshot analysis of {plugin_id} at {release_tag} written to {}",
+        output_path.display()
+    );
 
     Ok(())
 }
 
+fn sanitize_file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
 fn configured_thread_count(env_var: &str, default_threads: usize) -> usize {
     std::env::var(env_var)
         .ok()
@@ -0,0 +1,100 @@
+//! Dates of Obsidian desktop releases, read from the release changelog that
+//! the release step writes to `RELEASE_CHANGELOG_PATH`. Used to date the API
+//! versions a plugin depends on.
+
+use std::path::Path;
+
+use data_lib::{
+    date::Date,
+    release::{ObsidianPlatform, ObsidianReleaseInfo},
+    version::Version,
+};
+
+use crate::{constants::RELEASE_CHANGELOG_PATH, file_utils::read_chunked_data_or_default};
+
+#[derive(Debug, Default)]
+pub(super) struct ObsidianReleaseTimeline {
+    /// Desktop releases ordered by version.
+    releases: Vec<(Version, Date)>,
+}
+
+impl ObsidianReleaseTimeline {
+    pub(super) fn load() -> Self {
+        let changelog: Vec<ObsidianReleaseInfo> =
+            read_chunked_data_or_default(Path::new(RELEASE_CHANGELOG_PATH));
+        let timeline = Self::from_changelog(changelog);
+        if timeline.releases.is_empty() {
+            println!(
+                "Obsidian release changelog not found at {RELEASE_CHANGELOG_PATH}, API versions stay undated"
+            );
+        }
+        timeline
+    }
+
+    fn from_changelog(changelog: Vec<ObsidianReleaseInfo>) -> Self {
+        let mut releases = changelog
+            .into_iter()
+            .filter(|release| release.platform == ObsidianPlatform::Desktop)
+            .map(|release| (release.version.get_patch(), release.date))
+            .collect::<Vec<_>>();
+        releases.sort_by(|(a_version, a_date), (b_version, b_date)| {
+            a_version
+                .cmp(b_version)
+                .then_with(|| a_date.days_since_epoch().cmp(&b_date.days_since_epoch()))
+        });
+        Self { releases }
+    }
+
+    /// The date of the first desktop release at or after `version`, insider
+    /// builds included.
+    pub(super) fn first_release_date(&self, version: &Version) -> Option<&Date> {
+        let version = version.get_patch();
+        let index = self
+            .releases
+            .partition_point(|(release, _)| *release < version);
+        self.releases.get(index).map(|(_, date)| date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::{
+        date::Date,
+        release::{ObsidianPlatform, ObsidianReleaseInfo},
+        version::Version,
+    };
+
+    use super::ObsidianReleaseTimeline;
+
+    fn release(version: &str, platform: ObsidianPlatform, date: &str) -> ObsidianReleaseInfo {
+        ObsidianReleaseInfo {
+            version: Version::parse(version).unwrap(),
+            platform,
+            insider: false,
+            date: Date::from_string(date).unwrap(),
+            info: String::new(),
+            major_release: false,
+        }
+    }
+
+    #[test]
+    fn dates_versions_by_the_first_desktop_release_at_or_after_them() {
+        let timeline = ObsidianReleaseTimeline::from_changelog(vec![
+            release("1.1.9", ObsidianPlatform::Desktop, "2023-01-09"),
+            release("1.1.0", ObsidianPlatform::Mobile, "2022-12-01"),
+            release("1.1.1", ObsidianPlatform::Desktop, "2022-12-13"),
+            release("1.0.3", ObsidianPlatform::Desktop, "2022-10-25"),
+        ]);
+
+        let date_of = |version: &str| {
+            timeline
+                .first_release_date(&Version::parse(version).unwrap())
+                .map(Date::to_fancy_string)
+        };
+
+        assert_eq!(date_of("1.1.1"), Some("2022-12-13".to_string()));
+        assert_eq!(date_of("1.1.0"), Some("2022-12-13".to_string()));
+        assert_eq!(date_of("1.1.5"), Some("2023-01-09".to_string()));
+        assert_eq!(date_of("1.2.0"), None);
+    }
+}
@@ -1,10 +1,12 @@
 use std::collections::HashSet;
 
 use data_lib::plugin::{
-    MainJsApiCapability, MainJsApiDisclosure, MainJsApiEvidence, PluginRepoData,
+    ApiVersionRequirement, DeprecatedApiUsage, MainJsApiCapability, MainJsApiDisclosure,
+    MainJsApiEvidence, PluginRepoData,
 };
 
 use super::{
+    mainjs::ReproducibilityResult,
     mainjs::api_classifier::{ApiClassificationResult, ApiSeverity, Confidence},
     types::MainJsResult,
 };
@@ -13,6 +15,7 @@ use crate::plugins::release_acquisition::PluginReleaseStateEntry;
 pub(super) trait PluginRepoDataExt {
     fn apply_main_js_analysis(&mut self, result: &MainJsResult);
     fn apply_release_state(&mut self, state_entry: &PluginReleaseStateEntry);
+    fn apply_reproducibility(&mut self, result: &ReproducibilityResult);
 }
 
 impl PluginRepoDataExt for PluginRepoData {
@@ -55,6 +58,13 @@ impl PluginRepoDataExt for PluginRepoData {
         self.latest_release_tag = state_entry.latest_release_tag.clone();
         self.latest_release_published_at = state_entry.latest_release_published_at.clone();
         self.latest_release_fetch_status = state_entry.latest_release_fetch_status.clone();
+        self.latest_release_styles_css_size_bytes =
+            state_entry.latest_release_styles_css_size_bytes;
+    }
+
+    fn apply_reproducibility(&mut self, result: &ReproducibilityResult) {
+        self.main_js_reproducibility_score = result.score;
+        self.main_js_reproducibility_mismatches = result.mismatches.clone();
     }
 }
 
@@ -64,36 +74,12 @@ trait MainJsApiOutputExt {
 
 impl MainJsApiOutputExt for PluginRepoData {
     fn apply_main_js_api_usage(&mut self, api_usage: &ApiClassificationResult) {
-        let public_capability_ids = api_usage
-            .capabilities()
+        self.main_js_api_capabilities = public_api_capabilities(api_usage);
+        let public_capability_ids = self
+            .main_js_api_capabilities
             .iter()
-            .filter(|capability| {
-                is_public_capability(capability.severity(), capability.confidence())
-            })
-            .map(|capability| capability.id().to_string())
+            .map(|capability| capability.id.as_str())
             .collect::<HashSet<_>>();
-
-        self.main_js_api_capabilities = api_usage
-            .capabilities()
-            .iter()
-            .filter(|capability| public_capability_ids.contains(capability.id()))
-            .map(|capability| MainJsApiCapability {
-                id: capability.id().to_string(),
-                label: capability.label().to_string(),
-                category: capability.category().as_str().to_string(),
-                severity: capability.severity().as_str().to_string(),
-                confidence: capability.confidence().as_str().to_string(),
-                evidence: capability
-                    .evidence()
-                    .iter()
-                    .map(|evidence| MainJsApiEvidence {
-                        kind: evidence.kind().as_str().to_string(),
-                        symbol: evidence.symbol().to_string(),
-                        count: evidence.count(),
-                    })
-                    .collect(),
-            })
-            .collect();
         self.main_js_api_disclosures = api_usage
             .disclosures()
             .iter()
@@ -103,9 +89,66 @@ impl MainJsApiOutputExt for PluginRepoData {
                 from_capability: disclosure.source_capability().to_string(),
             })
             .collect();
+        self.deprecated_api_usages = api_usage
+            .capabilities()
+            .iter()
+            .filter(|capability| public_capability_ids.contains(capability.id()))
+            .filter_map(|capability| {
+                let deprecation = capability.deprecation()?;
+                Some(DeprecatedApiUsage {
+                    id: capability.id().to_string(),
+                    label: capability.label().to_string(),
+                    replacement: deprecation.replacement().to_string(),
+                    replacement_since: deprecation.replacement_since().to_string(),
+                })
+            })
+            .collect();
+        self.api_version_requirements = api_usage
+            .capabilities()
+            .iter()
+            .flat_map(|capability| {
+                capability
+                    .introductions()
+                    .iter()
+                    .map(|introduction| ApiVersionRequirement {
+                        id: capability.id().to_string(),
+                        label: capability.label().to_string(),
+                        symbol: introduction.symbol().to_string(),
+                        since: introduction.since().to_string(),
+                        since_date: None,
+                    })
+            })
+            .collect();
     }
 }
 
+/// Capabilities that are confident enough to be shown publicly.
+pub(super) fn public_api_capabilities(
+    api_usage: &ApiClassificationResult,
+) -> Vec<MainJsApiCapability> {
+    api_usage
+        .capabilities()
+        .iter()
+        .filter(|capability| is_public_capability(capability.severity(), capability.confidence()))
+        .map(|capability| MainJsApiCapability {
+            id: capability.id().to_string(),
+            label: capability.label().to_string(),
+            category: capability.category().as_str().to_string(),
+            severity: capability.severity().as_str().to_string(),
+            confidence: capability.confidence().as_str().to_string(),
+            evidence: capability
+                .evidence()
+                .iter()
+                .map(|evidence| MainJsApiEvidence {
+                    kind: evidence.kind().as_str().to_string(),
+                    symbol: evidence.symbol().to_string(),
+                    count: evidence.count(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 fn is_public_capability(severity: ApiSeverity, confidence: Confidence) -> bool {
     if confidence == Confidence::Low {
         return false;
@@ -1,31 +1,57 @@
-use std::fs;
+use std::{fs, path::Path};
 
-use data_lib::plugin::{PluginData, PluginRepoAnalysisError, PluginRepoData};
+use data_lib::{
+    date::Date,
+    plugin::{
+        PluginData, PluginManifest, PluginRepoAnalysisError, PluginRepoData, ReleaseAssetReupload,
+    },
+    version::Version,
+};
 
 use super::{
-    mainjs::analyze_main_js, output::PluginRepoDataExt, repo::analyze_repo,
+    advisories::AdvisoryDatabase,
+    mainjs::{SourceFingerprint, analyze_main_js, compare_release_with_source},
+    obsidian_releases::ObsidianReleaseTimeline,
+    output::PluginRepoDataExt,
+    release_manifest::{compare_release_manifest, read_cached_release_manifest},
+    repo::{analyze_repo, analyze_repo_at},
     run_stats::ExtraRunStats,
+    timeline::read_release_events,
+    versions_json::check_versions_json,
 };
 use crate::plugins::{
     license::license_compare::LicenseComparer,
     release_acquisition::{
-        PluginReleaseState, PluginReleaseStateEntry, release_main_js_cache_path,
+        PluginReleaseState, PluginReleaseStateEntry, ReleaseAssetIndex, ReleaseAssetKind,
+        release_main_js_cache_path,
     },
-    stats_helper::HelperPluginStore,
+    stats_helper::{HelperPluginStore, HelperRelease},
 };
 
-const MAX_MAIN_JS_ANALYSIS_BYTES: u64 = 10 * 1024 * 1024;
+pub(super) const MAX_MAIN_JS_ANALYSIS_BYTES: u64 = 10 * 1024 * 1024;
 
 pub(crate) fn analyze_plugin(
     plugin: &PluginData,
     license_comparer: &LicenseComparer,
     release_state: &PluginReleaseState,
     helper_store: &HelperPluginStore,
+    advisory_database: &AdvisoryDatabase,
+    obsidian_releases: &ObsidianReleaseTimeline,
     run_stats: &mut ExtraRunStats,
 ) -> Result<PluginRepoData, String> {
     let repo_result = analyze_repo(plugin, license_comparer).map_err(|error| error.to_string())?;
-    let mut output = repo_result.into_plugin_repo_data();
-    output.manifest = helper_store.helper_manifest_for_plugin(plugin);
+    let (mut output, source_fingerprint) = repo_result.into_plugin_repo_data();
+    apply_dependency_advisories(&mut output, advisory_database, run_stats);
+    let repo_manifest = std::mem::replace(
+        &mut output.manifest,
+        helper_store.helper_manifest_for_plugin(plugin),
+    );
+    output.main_js_release_events = read_release_events(&plugin.id);
+    apply_versions_json(
+        &mut output,
+        helper_store.helper_releases_for_plugin(plugin),
+        run_stats,
+    );
 
     let Some(state_entry) = matching_release_state_entry(plugin, release_state) else {
         run_stats.release_state_missing += 1;
@@ -34,27 +60,134 @@ pub(crate) fn analyze_plugin(
 
     output.apply_release_state(state_entry);
     increment_release_status_count(run_stats, state_entry);
+    apply_release_asset_index(
+        &mut output,
+        &plugin.id,
+        state_entry.latest_release_tag.as_deref(),
+        run_stats,
+    );
 
     if let Some(tag) = state_entry.latest_release_tag.as_deref() {
-        let path = release_main_js_cache_path(&plugin.id, tag);
-        if let Ok(path) = path {
-            let too_large = fs::metadata(&path)
-                .map(|metadata| metadata.len() > MAX_MAIN_JS_ANALYSIS_BYTES)
-                .unwrap_or(false);
-            if too_large {
-                output
-                    .analysis_errors
-                    .push(PluginRepoAnalysisError::MainJsAnalysisTooLarge);
-                run_stats.release_main_js_scan_failed += 1;
-            } else if let Ok(bytes) = fs::read(path) {
-                if let Ok(source) = std::str::from_utf8(&bytes) {
-                    let mainjs = analyze_main_js(source);
-                    output.apply_main_js_analysis(&mainjs);
-                    run_stats.release_main_js_scanned += 1;
-                } else {
-                    run_stats.release_main_js_scan_failed += 1;
-                }
-            } else if output.estimated_target_es_version.is_none() {
+        apply_cached_main_js(&mut output, &plugin.id, tag, &source_fingerprint, run_stats);
+        apply_release_manifest(&mut output, plugin, tag, repo_manifest.as_ref(), run_stats);
+    } else if output.estimated_target_es_version.is_none() {
+        run_stats.release_main_js_scan_failed += 1;
+    }
+    apply_api_version_requirements(&mut output, obsidian_releases, run_stats);
+
+    Ok(output)
+}
+
+/// Analyzes a materialized repo snapshot of `release_tag`. The manifest is the
+/// one committed at that tag, and main.js is only analyzed if the release
+/// asset of the same tag is still cached. `versions.json` is not compared to
+/// releases, since the release list is only known for the current state.
+pub(crate) fn analyze_plugin_snapshot(
+    plugin: &PluginData,
+    snapshot_path: &Path,
+    release_tag: &str,
+    license_comparer: &LicenseComparer,
+    advisory_database: &AdvisoryDatabase,
+    obsidian_releases: &ObsidianReleaseTimeline,
+    run_stats: &mut ExtraRunStats,
+) -> Result<PluginRepoData, String> {
+    let repo_result = analyze_repo_at(plugin, snapshot_path, license_comparer)
+        .map_err(|error| error.to_string())?;
+    let (mut output, source_fingerprint) = repo_result.into_plugin_repo_data();
+    apply_dependency_advisories(&mut output, advisory_database, run_stats);
+    output.latest_release_tag = Some(release_tag.to_string());
+    apply_cached_main_js(
+        &mut output,
+        &plugin.id,
+        release_tag,
+        &source_fingerprint,
+        run_stats,
+    );
+    let repo_manifest = output.manifest.clone();
+    apply_release_manifest(
+        &mut output,
+        plugin,
+        release_tag,
+        repo_manifest.as_ref(),
+        run_stats,
+    );
+    apply_api_version_requirements(&mut output, obsidian_releases, run_stats);
+    apply_versions_json(&mut output, &[], run_stats);
+
+    Ok(output)
+}
+
+fn apply_dependency_advisories(
+    output: &mut PluginRepoData,
+    advisory_database: &AdvisoryDatabase,
+    run_stats: &mut ExtraRunStats,
+) {
+    output.dependency_advisories = advisory_database.find_advisories(&output.resolved_dependencies);
+    if !output.dependency_advisories.is_empty() {
+        run_stats.dependency_advisory_matched += 1;
+    }
+}
+
+/// Dates the API versions found in main.js and counts plugins whose declared
+/// `minAppVersion` predates them.
+fn apply_api_version_requirements(
+    output: &mut PluginRepoData,
+    obsidian_releases: &ObsidianReleaseTimeline,
+    run_stats: &mut ExtraRunStats,
+) {
+    for requirement in &mut output.api_version_requirements {
+        requirement.since_date = Version::parse(&requirement.since)
+            .and_then(|since| obsidian_releases.first_release_date(&since))
+            .map(Date::to_fancy_string);
+    }
+
+    let declared = output
+        .manifest
+        .as_ref()
+        .and_then(|manifest| manifest.min_app_version.as_deref())
+        .and_then(Version::parse);
+    if let Some(declared) = declared
+        && !output.api_requirements_newer_than(&declared).is_empty()
+    {
+        run_stats.min_app_version_older_than_api_usage += 1;
+    }
+}
+
+fn apply_cached_main_js(
+    output: &mut PluginRepoData,
+    plugin_id: &str,
+    release_tag: &str,
+    source_fingerprint: &SourceFingerprint,
+    run_stats: &mut ExtraRunStats,
+) {
+    let path = release_main_js_cache_path(plugin_id, release_tag);
+    if let Ok(path) = path {
+        let too_large = fs::metadata(&path)
+            .map(|metadata| metadata.len() > MAX_MAIN_JS_ANALYSIS_BYTES)
+            .unwrap_or(false);
+        if too_large {
+            output
+                .analysis_errors
+                .push(PluginRepoAnalysisError::MainJsAnalysisTooLarge);
+            run_stats.release_main_js_scan_failed += 1;
+        } else if let Ok(bytes) = fs::read(path) {
+            if let Ok(source) = std::str::from_utf8(&bytes) {
+                let mainjs = analyze_main_js(source);
+                output.apply_main_js_analysis(&mainjs);
+                let declared_dependencies = output
+                    .dependencies
+                    .iter()
+                    .chain(output.dev_dependencies.iter())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                output.apply_reproducibility(&compare_release_with_source(
+                    source,
+                    &mainjs,
+                    source_fingerprint,
+                    &declared_dependencies,
+                ));
+                run_stats.release_main_js_scanned += 1;
+            } else {
                 run_stats.release_main_js_scan_failed += 1;
             }
         } else if output.estimated_target_es_version.is_none() {
@@ -63,8 +196,72 @@ pub(crate) fn analyze_plugin(
     } else if output.estimated_target_es_version.is_none() {
         run_stats.release_main_js_scan_failed += 1;
     }
+}
 
-    Ok(output)
+/// Checks `versions.json` against `releases` and the manifest in `output`,
+/// which is the released one unless analyzing a snapshot.
+fn apply_versions_json(
+    output: &mut PluginRepoData,
+    releases: &[HelperRelease],
+    run_stats: &mut ExtraRunStats,
+) {
+    let Some(entries) = &output.ci.versions_json else {
+        return;
+    };
+
+    output.versions_json_issues = check_versions_json(entries, releases, output.manifest.as_ref());
+    if !output.versions_json_issues.is_empty() {
+        run_stats.versions_json_inconsistent += 1;
+    }
+}
+
+fn apply_release_manifest(
+    output: &mut PluginRepoData,
+    plugin: &PluginData,
+    release_tag: &str,
+    repo_manifest: Option<&PluginManifest>,
+    run_stats: &mut ExtraRunStats,
+) {
+    let Some(release_manifest) = read_cached_release_manifest(&plugin.id, release_tag) else {
+        return;
+    };
+
+    output.release_manifest_mismatches =
+        compare_release_manifest(&release_manifest, repo_manifest, &plugin.current_entry);
+    if !output.release_manifest_mismatches.is_empty() {
+        run_stats.release_manifest_mismatched += 1;
+    }
+}
+
+fn apply_release_asset_index(
+    output: &mut PluginRepoData,
+    plugin_id: &str,
+    latest_release_tag: Option<&str>,
+    run_stats: &mut ExtraRunStats,
+) {
+    let Ok(index) = ReleaseAssetIndex::read(plugin_id) else {
+        return;
+    };
+
+    output.latest_release_main_js_sha256 = latest_release_tag
+        .and_then(|tag| index.get(tag, ReleaseAssetKind::MainJs))
+        .map(|entry| entry.sha256.clone());
+    output.release_asset_reuploads = index
+        .assets
+        .iter()
+        .filter_map(|entry| {
+            let previous = entry.replaced.last()?;
+            Some(ReleaseAssetReupload {
+                tag: entry.tag.clone(),
+                asset: entry.asset.clone(),
+                previous_sha256: previous.sha256.clone(),
+                sha256: entry.sha256.clone(),
+            })
+        })
+        .collect();
+    if !output.release_asset_reuploads.is_empty() {
+        run_stats.release_asset_reuploaded += 1;
+    }
 }
 
 fn matching_release_state_entry<'a>(
@@ -87,3 +284,72 @@ fn increment_release_status_count(
         *run_stats.status_counts.entry(status.clone()).or_insert(0) += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use data_lib::{
+        commit::Commit, common::DownloadHistory, date::Date, input_data::ObsCommunityPlugin,
+        plugin::PluginData,
+    };
+    use hashbrown::HashMap;
+
+    use super::analyze_plugin_snapshot;
+    use crate::plugins::{
+        analysis::{
+            advisories::AdvisoryDatabase, obsidian_releases::ObsidianReleaseTimeline,
+            run_stats::ExtraRunStats,
+        },
+        license::license_compare::LicenseComparer,
+    };
+
+    #[test]
+    fn committed_main_js_is_reported() {
+        let repo_path = std::env::temp_dir().join(format!(
+            "obsidian-stats-pipeline-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::fs::write(
+            repo_path.join("manifest.json"),
+            r#"{ "id": "sample-plugin", "version": "1.0.0" }"#,
+        )
+        .unwrap();
+        std::fs::write(repo_path.join("main.js"), "module.exports = {};\n").unwrap();
+
+        let entry = ObsCommunityPlugin {
+            id: "sample-plugin".to_string(),
+            name: "Sample".to_string(),
+            author: "Author".to_string(),
+            description: String::new(),
+            repo: "owner/sample-plugin".to_string(),
+        };
+        let plugin = PluginData {
+            id: entry.id.clone(),
+            added_commit: Commit {
+                date: Date::from_string("2024-01-01").unwrap(),
+                hash: "0".repeat(40),
+            },
+            removed_commit: None,
+            initial_entry: entry.clone(),
+            current_entry: entry,
+            change_history: Vec::new(),
+            download_history: DownloadHistory(HashMap::new()),
+            download_count: 0,
+            version_history: Vec::new(),
+        };
+
+        let result = analyze_plugin_snapshot(
+            &plugin,
+            &repo_path,
+            "1.0.0",
+            &LicenseComparer::new(),
+            &AdvisoryDatabase::default(),
+            &ObsidianReleaseTimeline::default(),
+            &mut ExtraRunStats::default(),
+        );
+        let _ = std::fs::remove_dir_all(&repo_path);
+
+        assert!(result.unwrap().repo_has_committed_main_js);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeSet;
+
+use data_lib::plugin::{
+    MainJsApiCapability,
+    timeline::{MainJsReleaseAnalysis, MainJsReleaseEvent},
+};
+use hashbrown::HashMap;
+
+/// Diffs every release in `releases` (oldest first) against its predecessor.
+pub(super) fn release_events(releases: &[MainJsReleaseAnalysis]) -> Vec<MainJsReleaseEvent> {
+    releases
+        .windows(2)
+        .map(|pair| diff_releases(&pair[0], &pair[1]))
+        .collect()
+}
+
+fn diff_releases(
+    previous: &MainJsReleaseAnalysis,
+    current: &MainJsReleaseAnalysis,
+) -> MainJsReleaseEvent {
+    let (added_capabilities, removed_capabilities) =
+        diff_capabilities(&previous.api_capabilities, &current.api_capabilities);
+    let (added_hosts, removed_hosts) = diff_hosts(
+        &previous.known_api_host_counts,
+        &current.known_api_host_counts,
+    );
+
+    MainJsReleaseEvent {
+        tag: current.tag.clone(),
+        previous_tag: previous.tag.clone(),
+        published_at: current.published_at.clone(),
+        size_delta_bytes: i64::try_from(current.size_bytes).unwrap_or(i64::MAX)
+            - i64::try_from(previous.size_bytes).unwrap_or(i64::MAX),
+        added_capabilities,
+        removed_capabilities,
+        added_hosts,
+        removed_hosts,
+    }
+}
+
+/// Returns the capability ids that were added and removed, each sorted.
+fn diff_capabilities(
+    previous: &[MainJsApiCapability],
+    current: &[MainJsApiCapability],
+) -> (Vec<String>, Vec<String>) {
+    diff_sets(
+        previous
+            .iter()
+            .map(|capability| capability.id.as_str())
+            .collect(),
+        current
+            .iter()
+            .map(|capability| capability.id.as_str())
+            .collect(),
+    )
+}
+
+/// Returns the hosts that appear and disappear between two string-signal
+/// maps. Count changes of hosts present in both are not reported.
+fn diff_hosts(
+    previous: &HashMap<String, u32>,
+    current: &HashMap<String, u32>,
+) -> (Vec<String>, Vec<String>) {
+    diff_sets(
+        previous.keys().map(String::as_str).collect(),
+        current.keys().map(String::as_str).collect(),
+    )
+}
+
+fn diff_sets(previous: BTreeSet<&str>, current: BTreeSet<&str>) -> (Vec<String>, Vec<String>) {
+    let added = current
+        .difference(&previous)
+        .map(|value| value.to_string())
+        .collect();
+    let removed = previous
+        .difference(&current)
+        .map(|value| value.to_string())
+        .collect();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::plugin::{MainJsApiCapability, timeline::MainJsReleaseAnalysis};
+    use hashbrown::HashMap;
+
+    use super::release_events;
+
+    fn capability(id: &str) -> MainJsApiCapability {
+        MainJsApiCapability {
+            id: id.to_string(),
+            label: id.to_string(),
+            category: "test".to_string(),
+            severity: "warning".to_string(),
+            confidence: "high".to_string(),
+            evidence: Vec::new(),
+        }
+    }
+
+    fn release(
+        tag: &str,
+        size_bytes: u64,
+        capabilities: &[&str],
+        hosts: &[&str],
+    ) -> MainJsReleaseAnalysis {
+        MainJsReleaseAnalysis {
+            tag: tag.to_string(),
+            published_at: None,
+            size_bytes,
+            sha256: None,
+            estimated_target_es_version: None,
+            is_probably_minified: None,
+            minification_score: None,
+            bundler_fingerprints: Vec::new(),
+            api_capabilities: capabilities.iter().map(|id| capability(id)).collect(),
+            known_api_host_counts: hosts
+                .iter()
+                .map(|host| (host.to_string(), 1))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn reports_capability_host_and_size_changes_per_release() {
+        let releases = [
+            release("1.0.0", 1000, &["vault.read"], &[]),
+            release(
+                "1.1.0",
+                1500,
+                &["vault.read", "network.browser"],
+                &["api.example.com"],
+            ),
+            release("1.2.0", 1200, &["network.browser"], &["api.example.com"]),
+        ];
+
+        let events = release_events(&releases);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].tag, "1.1.0");
+        assert_eq!(events[0].previous_tag, "1.0.0");
+        assert_eq!(events[0].size_delta_bytes, 500);
+        assert_eq!(events[0].added_capabilities, ["network.browser"]);
+        assert_eq!(events[0].added_hosts, ["api.example.com"]);
+        assert_eq!(events[1].size_delta_bytes, -300);
+        assert_eq!(events[1].removed_capabilities, ["vault.read"]);
+        assert!(events[1].added_hosts.is_empty());
+        assert!(events[1].has_changes());
+    }
+}
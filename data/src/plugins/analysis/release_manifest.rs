@@ -0,0 +1,140 @@
+//! Compares the `manifest.json` release asset, which is what Obsidian
+//! installs, with the manifest committed at the release tag and with the
+//! community list entry.
+
+use std::fs;
+
+use data_lib::{
+    input_data::ObsCommunityPlugin,
+    plugin::{PluginManifest, ReleaseManifestMismatch},
+};
+
+use crate::plugins::release_acquisition::{ReleaseAssetKind, release_asset_cache_path};
+
+pub(super) fn read_cached_release_manifest(
+    plugin_id: &str,
+    release_tag: &str,
+) -> Option<PluginManifest> {
+    let path =
+        release_asset_cache_path(plugin_id, release_tag, ReleaseAssetKind::ManifestJson).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()
+}
+
+pub(super) fn compare_release_manifest(
+    release: &PluginManifest,
+    repo: Option<&PluginManifest>,
+    community_entry: &ObsCommunityPlugin,
+) -> Vec<ReleaseManifestMismatch> {
+    let repo_field = |field: fn(&PluginManifest) -> Option<String>| repo.and_then(field);
+
+    let fields_to_check = [
+        (
+            "id",
+            release.id.clone(),
+            repo_field(|manifest| manifest.id.clone()),
+            Some(community_entry.id.as_str()),
+        ),
+        (
+            "name",
+            release.name.clone(),
+            repo_field(|manifest| manifest.name.clone()),
+            Some(community_entry.name.as_str()),
+        ),
+        (
+            "version",
+            release.version.clone(),
+            repo_field(|manifest| manifest.version.clone()),
+            None,
+        ),
+        (
+            "minAppVersion",
+            release.min_app_version.clone(),
+            repo_field(|manifest| manifest.min_app_version.clone()),
+            None,
+        ),
+        (
+            "author",
+            release.author.clone(),
+            repo_field(|manifest| manifest.author.clone()),
+            Some(community_entry.author.as_str()),
+        ),
+        (
+            "description",
+            release.description.clone(),
+            repo_field(|manifest| manifest.description.clone()),
+            Some(community_entry.description.as_str()),
+        ),
+        (
+            "isDesktopOnly",
+            release.is_desktop_only.map(|value| value.to_string()),
+            repo_field(|manifest| manifest.is_desktop_only.map(|value| value.to_string())),
+            None,
+        ),
+    ];
+
+    fields_to_check
+        .into_iter()
+        .filter_map(|(field, release_value, repo_value, community_value)| {
+            let release_value = release_value?;
+            let differs_from_repo = repo_value
+                .as_deref()
+                .is_some_and(|value| value != release_value);
+            let differs_from_community =
+                community_value.is_some_and(|value| value != release_value);
+
+            (differs_from_repo || differs_from_community).then(|| ReleaseManifestMismatch {
+                field: field.to_string(),
+                release_value,
+                repo_value,
+                community_list_value: community_value.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::{input_data::ObsCommunityPlugin, plugin::PluginManifest};
+
+    use super::compare_release_manifest;
+
+    #[test]
+    fn reports_fields_that_differ_from_repo_or_community_list() {
+        let community_entry = ObsCommunityPlugin {
+            id: "plugin".to_string(),
+            name: "Plugin".to_string(),
+            author: "Author".to_string(),
+            description: "Does things".to_string(),
+            repo: "owner/plugin".to_string(),
+        };
+        let repo = PluginManifest {
+            id: Some("plugin".to_string()),
+            name: Some("Plugin".to_string()),
+            version: Some("1.0.0".to_string()),
+            author: Some("Author".to_string()),
+            description: Some("Does things".to_string()),
+            ..PluginManifest::default()
+        };
+        let release = PluginManifest {
+            version: Some("1.0.1".to_string()),
+            description: Some("Does other things".to_string()),
+            ..repo.clone()
+        };
+
+        let mismatches = compare_release_manifest(&release, Some(&repo), &community_entry);
+        let fields = mismatches
+            .iter()
+            .map(|mismatch| mismatch.field.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields, ["version", "description"]);
+        assert_eq!(mismatches[0].repo_value.as_deref(), Some("1.0.0"));
+        assert_eq!(mismatches[0].community_list_value, None);
+        assert_eq!(
+            mismatches[1].community_list_value.as_deref(),
+            Some("Does things")
+        );
+        assert!(compare_release_manifest(&repo, Some(&repo), &community_entry).is_empty());
+    }
+}
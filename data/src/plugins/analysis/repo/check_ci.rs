@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use data_lib::{
+    plugin::ci::{CiWorkflow, CiWorkflowPurpose, PluginCiData, RepoTool, VersionsJsonEntry},
+    version::Version,
+};
+
+use super::check_source::read_limited;
+
+const MAX_CONFIG_FILE_BYTES: u64 = 1024 * 1024;
+const WORKFLOW_DIR: &str = ".github/workflows/";
+/// Directories GitHub reads community health files from.
+const COMMUNITY_FILE_DIRS: &[&str] = &["", ".github/", "docs/"];
+const RELEASE_ACTIONS: &[&str] = &[
+    "softprops/action-gh-release",
+    "ncipollo/release-action",
+    "actions/create-release",
+    "marvinpinto/action-automatic-releases",
+];
+const TEST_COMMANDS: &[&str] = &["test", "vitest", "jest", "mocha"];
+const LINT_COMMANDS: &[&str] = &["lint", "eslint", "biome", "prettier"];
+const BUILD_COMMANDS: &[&str] = &["build", "esbuild"];
+
+pub(super) fn run(repo_path: &str, files: &[String]) -> PluginCiData {
+    PluginCiData {
+        workflows: files
+            .iter()
+            .filter(|file| is_workflow_file(file))
+            .map(|file| CiWorkflow {
+                file: file.clone(),
+                purposes: read_limited(repo_path, file, MAX_CONFIG_FILE_BYTES).map_or_else(
+                    || vec![CiWorkflowPurpose::Other],
+                    |content| workflow_purposes(&content),
+                ),
+            })
+            .collect(),
+        versions_json: files
+            .iter()
+            .any(|file| file == "versions.json")
+            .then(|| read_limited(repo_path, "versions.json", MAX_CONFIG_FILE_BYTES))
+            .flatten()
+            .and_then(|content| parse_versions_json(&content)),
+        has_version_bump_script: files.iter().any(|file| is_version_bump_script(file)),
+        tools: repo_tools(files),
+        has_contributing_guide: has_community_file(files, "CONTRIBUTING"),
+        has_security_policy: has_community_file(files, "SECURITY"),
+    }
+}
+
+fn is_workflow_file(file: &str) -> bool {
+    file.strip_prefix(WORKFLOW_DIR).is_some_and(|name| {
+        !name.contains('/') && (name.ends_with(".yml") || name.ends_with(".yaml"))
+    })
+}
+
+/// Classifies a workflow by its triggers, the actions it uses and the
+/// commands it runs. Workflows matching none of them are `Other`.
+fn workflow_purposes(content: &str) -> Vec<CiWorkflowPurpose> {
+    let Ok(workflow) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return vec![CiWorkflowPurpose::Other];
+    };
+
+    let mut purposes = BTreeSet::new();
+    if is_release_trigger(workflow.get("on")) {
+        purposes.insert(CiWorkflowPurpose::Release);
+    }
+
+    let steps = workflow
+        .get("jobs")
+        .and_then(serde_yaml::Value::as_mapping)
+        .into_iter()
+        .flat_map(|jobs| jobs.values())
+        .filter_map(|job| job.get("steps")?.as_sequence())
+        .flatten();
+    for step in steps {
+        if let Some(uses) = step.get("uses").and_then(serde_yaml::Value::as_str)
+            && RELEASE_ACTIONS
+                .iter()
+                .any(|action| uses.starts_with(action))
+        {
+            purposes.insert(CiWorkflowPurpose::Release);
+        }
+        let Some(run) = step.get("run").and_then(serde_yaml::Value::as_str) else {
+            continue;
+        };
+        if run.contains("gh release") {
+            purposes.insert(CiWorkflowPurpose::Release);
+        }
+        for token in run.split(|c: char| c.is_whitespace() || c == ';' || c == '&') {
+            let command = token.split(':').next().unwrap_or(token);
+            if TEST_COMMANDS.contains(&command) {
+                purposes.insert(CiWorkflowPurpose::Test);
+            } else if LINT_COMMANDS.contains(&command) {
+                purposes.insert(CiWorkflowPurpose::Lint);
+            } else if BUILD_COMMANDS.contains(&command) {
+                purposes.insert(CiWorkflowPurpose::Build);
+            }
+        }
+    }
+
+    if purposes.is_empty() {
+        purposes.insert(CiWorkflowPurpose::Other);
+    }
+    purposes.into_iter().collect()
+}
+
+/// `on: release` or `on: push: tags: [...]`, in any of the trigger forms.
+fn is_release_trigger(on: Option<&serde_yaml::Value>) -> bool {
+    match on {
+        Some(serde_yaml::Value::String(event)) => event == "release",
+        Some(serde_yaml::Value::Sequence(events)) => {
+            events.iter().any(|event| event.as_str() == Some("release"))
+        }
+        Some(serde_yaml::Value::Mapping(events)) => {
+            events.contains_key("release")
+                || events
+                    .get("push")
+                    .is_some_and(|push| push.get("tags").is_some())
+        }
+        _ => false,
+    }
+}
+
+/// `versions.json` maps plugin versions to the minimum app version. Entries
+/// are sorted by version, unparsable versions last.
+fn parse_versions_json(content: &str) -> Option<Vec<VersionsJsonEntry>> {
+    let versions = serde_json::from_str::<BTreeMap<String, String>>(content).ok()?;
+    let mut entries = versions
+        .into_iter()
+        .map(|(version, min_app_version)| VersionsJsonEntry {
+            version,
+            min_app_version,
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_cached_key(|entry| {
+        let version = Version::parse(&entry.version);
+        (version.is_none(), version)
+    });
+    Some(entries)
+}
+
+fn is_version_bump_script(file: &str) -> bool {
+    let file_name = file.rsplit('/').next().unwrap_or(file);
+    ["version-bump.", "version_bump.", "bump-version."]
+        .iter()
+        .any(|prefix| file_name.starts_with(prefix))
+        && [".js", ".mjs", ".cjs", ".ts", ".mts"]
+            .iter()
+            .any(|ending| file_name.ends_with(ending))
+}
+
+fn repo_tools(files: &[String]) -> Vec<RepoTool> {
+    let mut tools = BTreeSet::new();
+    for file in files {
+        let tool = match file.as_str() {
+            name if name.starts_with(".eslintrc") || name.starts_with("eslint.config.") => {
+                RepoTool::Eslint
+            }
+            name if name.starts_with(".prettierrc") || name.starts_with("prettier.config.") => {
+                RepoTool::Prettier
+            }
+            "biome.json" | "biome.jsonc" => RepoTool::Biome,
+            ".github/dependabot.yml" | ".github/dependabot.yaml" => RepoTool::Dependabot,
+            "renovate.json"
+            | "renovate.json5"
+            | ".renovaterc"
+            | ".renovaterc.json"
+            | ".github/renovate.json"
+            | ".github/renovate.json5" => RepoTool::Renovate,
+            _ => continue,
+        };
+        tools.insert(tool);
+    }
+    tools.into_iter().collect()
+}
+
+/// `CONTRIBUTING.md`, `.github/SECURITY.md` and similar, in any case and with
+/// any extension.
+fn has_community_file(files: &[String], name: &str) -> bool {
+    files.iter().any(|file| {
+        COMMUNITY_FILE_DIRS.iter().any(|dir| {
+            file.strip_prefix(dir).is_some_and(|file_name| {
+                !file_name.contains('/')
+                    && file_name
+                        .split('.')
+                        .next()
+                        .is_some_and(|stem| stem.eq_ignore_ascii_case(name))
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::plugin::ci::{CiWorkflowPurpose, RepoTool};
+
+    use super::{has_community_file, parse_versions_json, repo_tools, workflow_purposes};
+
+    #[test]
+    fn classifies_workflows_by_triggers_actions_and_commands() {
+        let release = r#"
+name: Release
+on:
+  push:
+    tags: ["*"]
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: |
+          npm ci
+          npm run build
+      - uses: softprops/action-gh-release@v2
+"#;
+        assert_eq!(
+            workflow_purposes(release),
+            vec![CiWorkflowPurpose::Build, CiWorkflowPurpose::Release]
+        );
+
+        let checks = r#"
+on: [push, pull_request]
+jobs:
+  check:
+    runs-on: ubuntu-latest
+    steps:
+      - run: npm run lint:ci && npx vitest run
+"#;
+        assert_eq!(
+            workflow_purposes(checks),
+            vec![CiWorkflowPurpose::Lint, CiWorkflowPurpose::Test]
+        );
+
+        let stale = "on: schedule\njobs:\n  stale:\n    steps:\n      - uses: actions/stale@v9\n";
+        assert_eq!(workflow_purposes(stale), vec![CiWorkflowPurpose::Other]);
+    }
+
+    #[test]
+    fn detects_hygiene_files_and_sorts_versions_json_entries() {
+        let files = [
+            ".github/dependabot.yml",
+            ".github/SECURITY.md",
+            "eslint.config.mjs",
+            "docs/contributing.md",
+            "src/biome.json",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            repo_tools(&files),
+            vec![RepoTool::Eslint, RepoTool::Dependabot]
+        );
+        assert!(has_community_file(&files, "CONTRIBUTING"));
+        assert!(has_community_file(&files, "SECURITY"));
+
+        let versions =
+            parse_versions_json(r#"{"1.10.0": "1.4.0", "1.9.0": "0.15.0", "beta": "1.0.0"}"#)
+                .unwrap();
+        let versions = versions
+            .iter()
+            .map(|entry| entry.version.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec!["1.9.0", "1.10.0", "beta"]);
+        assert!(parse_versions_json(r#"{"1.0.0": 15}"#).is_none());
+    }
+}
@@ -4,9 +4,7 @@ use hashbrown::HashMap;
 
 use data_lib::plugin::PluginRepoAnalysisError;
 
-use super::LOC_EXCLUDED;
-
-const TEST_FILE_SUFFIXES: &[&str] = &[".test.ts", ".test.js", ".spec.ts", ".spec.js"];
+use super::{LOC_EXCLUDED, check_tests::is_test_file};
 
 pub(super) struct FilesResult {
     pub(super) files: Vec<String>,
@@ -53,7 +51,7 @@ fn count_file_types(files: &[String]) -> HashMap<String, usize> {
     file_types
 }
 
-fn count_lines_of_code(repo_path: &str, files: &[String]) -> HashMap<String, usize> {
+pub(super) fn count_lines_of_code(repo_path: &str, files: &[String]) -> HashMap<String, usize> {
     let config = tokei::Config::default();
     let mut languages = tokei::Languages::new();
     let paths = files
@@ -70,12 +68,10 @@ fn count_lines_of_code(repo_path: &str, files: &[String]) -> HashMap<String, usi
         .collect()
 }
 
+/// Uses the same detection as the test metrics, so `has_test_files` agrees
+/// with `tests.test_file_count`.
 fn has_test_files(files: &[String]) -> bool {
-    files.iter().any(|file| {
-        TEST_FILE_SUFFIXES
-            .iter()
-            .any(|suffix| file.ends_with(suffix))
-    })
+    files.iter().any(|file| is_test_file(file))
 }
 
 fn list_files_in_repo(repo_path: &str) -> (Vec<String>, bool) {
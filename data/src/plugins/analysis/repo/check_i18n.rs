@@ -1,4 +1,115 @@
-use data_lib::common::{I18N_FILE_ENDINGS, I18N_LOCALE_CODES};
+use std::{collections::BTreeSet, path::Path};
+
+use data_lib::{
+    common::{I18N_FILE_ENDINGS, I18N_LOCALE_CODES},
+    plugin::LocaleCoverage,
+};
+use hashbrown::HashMap;
+use swc_ecma_ast::{
+    AssignTarget, Decl, Expr, ModuleDecl, ModuleItem, ObjectLit, Program, Prop, PropName,
+    PropOrSpread, SimpleAssignTarget, Stmt,
+};
+
+use super::{
+    check_source::{is_bundled_source_file, read_limited},
+    parse_source_file,
+};
+
+const MAX_LOCALE_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_LOCALE_FILES: usize = 200;
+/// Directory names that mark `<dir>/<locale>/<file>` layouts as translations.
+const LOCALE_DIRS: &[&str] = &[
+    "i18n",
+    "l10n",
+    "lang",
+    "langs",
+    "language",
+    "languages",
+    "locale",
+    "locales",
+    "translation",
+    "translations",
+];
+/// Ways a plugin picks up the language Obsidian runs in, as
+/// `(label, source marker)`.
+const LANGUAGE_DETECTION_MARKERS: &[(&str, &str)] = &[
+    ("moment_locale", "moment.locale("),
+    ("get_language", "getLanguage("),
+    (
+        "local_storage_language",
+        "localStorage.getItem(\"language\")",
+    ),
+    ("local_storage_language", "localStorage.getItem('language')"),
+];
+
+/// Keys of one locale, split by layout. Keys of `<locale>/<file>` layouts are
+/// prefixed with the file name and never match keys of `<locale>.<ext>`
+/// files, so the two are only compared with their own kind.
+#[derive(Debug, Default)]
+struct LocaleKeys {
+    from_locale_files: BTreeSet<String>,
+    from_locale_dirs: BTreeSet<String>,
+}
+
+impl LocaleKeys {
+    fn len(&self) -> usize {
+        self.from_locale_files.len() + self.from_locale_dirs.len()
+    }
+
+    fn layouts(&self) -> [&BTreeSet<String>; 2] {
+        [&self.from_locale_files, &self.from_locale_dirs]
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct I18nResult {
+    pub(super) has_i18n_files: bool,
+    pub(super) locales: Vec<LocaleCoverage>,
+    pub(super) base_locale: Option<String>,
+    pub(super) language_detection: Vec<String>,
+}
+
+pub(super) fn run(repo_path: &str, files: &[String]) -> I18nResult {
+    let mut keys_by_locale: HashMap<&'static str, LocaleKeys> = HashMap::new();
+    for (file, locale, prefix) in files
+        .iter()
+        .filter_map(|file| {
+            let (locale, prefix) = locale_of_file(file)?;
+            Some((file, locale, prefix))
+        })
+        .take(MAX_LOCALE_FILES)
+    {
+        let keys = keys_by_locale.entry(locale).or_default();
+        let Some(content) = read_limited(repo_path, file, MAX_LOCALE_FILE_BYTES) else {
+            continue;
+        };
+        for key in locale_file_keys(file, &content) {
+            match &prefix {
+                Some(prefix) => keys.from_locale_dirs.insert(format!("{prefix}:{key}")),
+                None => keys.from_locale_files.insert(key),
+            };
+        }
+    }
+
+    let base_locale = base_locale(&keys_by_locale);
+    let base_keys = base_locale.and_then(|locale| keys_by_locale.get(locale));
+    let mut locales = keys_by_locale
+        .iter()
+        .map(|(locale, keys)| LocaleCoverage {
+            locale: locale.to_string(),
+            key_count: keys.len() as u32,
+            completeness: base_keys.and_then(|base_keys| completeness(base_keys, keys)),
+        })
+        .collect::<Vec<_>>();
+    locales.sort_by(|a, b| a.locale.cmp(&b.locale));
+
+    I18nResult {
+        has_i18n_files: has_i18n_files(files),
+        locales,
+        base_locale: base_locale.map(str::to_string),
+        language_detection: language_detection(repo_path, files),
+    }
+}
 
 pub(super) fn has_i18n_files(files: &[String]) -> bool {
     files.iter().any(|file| {
@@ -10,3 +121,330 @@ pub(super) fn has_i18n_files(files: &[String]) -> bool {
         })
     })
 }
+
+/// Share of the base locale's keys that `keys` translates. Only layouts that
+/// both locales use are compared; `None` if they share none. A locale without
+/// any readable keys translates nothing.
+fn completeness(base_keys: &LocaleKeys, keys: &LocaleKeys) -> Option<f32> {
+    if keys.len() == 0 {
+        return (base_keys.len() > 0).then_some(0.0);
+    }
+    let (translated, total) = base_keys
+        .layouts()
+        .into_iter()
+        .zip(keys.layouts())
+        .filter(|(base_keys, keys)| !base_keys.is_empty() && !keys.is_empty())
+        .fold((0, 0), |(translated, total), (base_keys, keys)| {
+            (
+                translated + base_keys.intersection(keys).count(),
+                total + base_keys.len(),
+            )
+        });
+    (total > 0).then(|| translated as f32 / total as f32)
+}
+
+/// The locale a translation file inside a locale directory belongs to, either
+/// from its name (`locales/de.json`) or from its directory
+/// (`locales/de/common.json`). The second value prefixes keys of per-directory
+/// files so that files of the same locale do not collide.
+fn locale_of_file(file: &str) -> Option<(&'static str, Option<String>)> {
+    let path = Path::new(file);
+    let file_name = path.file_name()?.to_str()?;
+    if file_name.ends_with(".d.ts") {
+        return None;
+    }
+    let ending = I18N_FILE_ENDINGS
+        .iter()
+        .find(|ending| file_name.ends_with(**ending))?;
+    let stem = &file_name[..file_name.len() - ending.len()];
+    let mut ancestors = path.parent()?.iter().rev().filter_map(|dir| dir.to_str());
+    let is_locale_dir = |dir: &str| LOCALE_DIRS.contains(&dir.to_ascii_lowercase().as_str());
+
+    if let Some(locale) = locale_code(stem) {
+        return ancestors.any(is_locale_dir).then_some((locale, None));
+    }
+
+    let locale = locale_code(ancestors.next()?)?;
+    ancestors
+        .any(is_locale_dir)
+        .then(|| (locale, Some(stem.to_string())))
+}
+
+/// Matches `zh_cn` or `zh-CN` against the known locale codes.
+fn locale_code(value: &str) -> Option<&'static str> {
+    let value = value.replace('_', "-");
+    I18N_LOCALE_CODES
+        .iter()
+        .find(|code| code.eq_ignore_ascii_case(&value))
+        .copied()
+}
+
+/// English when present, otherwise the locale with the most keys.
+fn base_locale(keys_by_locale: &HashMap<&'static str, LocaleKeys>) -> Option<&'static str> {
+    keys_by_locale
+        .keys()
+        .filter(|locale| locale.eq_ignore_ascii_case("en") || locale.starts_with("en-"))
+        .min_by_key(|locale| locale.len())
+        .or_else(|| {
+            keys_by_locale
+                .iter()
+                .max_by(|(a_locale, a_keys), (b_locale, b_keys)| {
+                    a_keys
+                        .len()
+                        .cmp(&b_keys.len())
+                        .then_with(|| b_locale.cmp(a_locale))
+                })
+                .map(|(locale, _)| locale)
+        })
+        .copied()
+}
+
+fn language_detection(repo_path: &str, files: &[String]) -> Vec<String> {
+    let mut detected = BTreeSet::new();
+    for file in files.iter().filter(|file| is_bundled_source_file(file)) {
+        let Some(source) = read_limited(repo_path, file, MAX_LOCALE_FILE_BYTES) else {
+            continue;
+        };
+        for (label, marker) in LANGUAGE_DETECTION_MARKERS {
+            if source.contains(marker) {
+                detected.insert(label.to_string());
+            }
+        }
+    }
+    detected.into_iter().collect()
+}
+
+/// Dotted paths of the translated strings in a locale file.
+fn locale_file_keys(file: &str, content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    if file.ends_with(".json") {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(content) {
+            collect_json_keys(&value, "", &mut keys);
+        }
+    } else if file.ends_with(".yaml") || file.ends_with(".yml") {
+        if let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(content) {
+            collect_yaml_keys(&value, "", &mut keys);
+        }
+    } else if let Some(program) = parse_source_file(file, content)
+        && let Some(object) = largest_exported_object(&program)
+    {
+        collect_object_keys(object, "", &mut keys);
+    }
+    keys
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn collect_json_keys(value: &serde_json::Value, prefix: &str, keys: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                collect_json_keys(value, &join_key(prefix, key), keys);
+            }
+        }
+        _ if !prefix.is_empty() => keys.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+fn collect_yaml_keys(value: &serde_yaml::Value, prefix: &str, keys: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                collect_yaml_keys(value, &join_key(prefix, key), keys);
+            }
+        }
+        _ if !prefix.is_empty() => keys.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+/// The object literal with the most keys among `export default`, exported or
+/// top-level `const` declarations and `module.exports` assignments.
+fn largest_exported_object(program: &Program) -> Option<&ObjectLit> {
+    let mut candidates = Vec::new();
+
+    match program {
+        Program::Module(module) => {
+            for item in &module.body {
+                match item {
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+                        candidates.push(&*export.expr);
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                        collect_decl_candidates(&export.decl, &mut candidates);
+                    }
+                    ModuleItem::Stmt(stmt) => collect_stmt_candidate(stmt, &mut candidates),
+                    _ => {}
+                }
+            }
+        }
+        Program::Script(script) => {
+            for stmt in &script.body {
+                collect_stmt_candidate(stmt, &mut candidates);
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(object_literal)
+        .max_by_key(|object| count_object_keys(object))
+}
+
+fn collect_decl_candidates<'a>(decl: &'a Decl, candidates: &mut Vec<&'a Expr>) {
+    if let Decl::Var(var) = decl {
+        candidates.extend(
+            var.decls
+                .iter()
+                .filter_map(|declarator| declarator.init.as_deref()),
+        );
+    }
+}
+
+fn collect_stmt_candidate<'a>(stmt: &'a Stmt, candidates: &mut Vec<&'a Expr>) {
+    match stmt {
+        Stmt::Decl(decl) => collect_decl_candidates(decl, candidates),
+        Stmt::Expr(expr) => {
+            if let Expr::Assign(assign) = &*expr.expr
+                && let AssignTarget::Simple(SimpleAssignTarget::Member(_)) = &assign.left
+            {
+                candidates.push(&assign.right);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Unwraps `as const`, `satisfies` and parentheses around an object literal.
+fn object_literal(expr: &Expr) -> Option<&ObjectLit> {
+    match expr {
+        Expr::Object(object) => Some(object),
+        Expr::Paren(paren) => object_literal(&paren.expr),
+        Expr::TsAs(ts_as) => object_literal(&ts_as.expr),
+        Expr::TsConstAssertion(assertion) => object_literal(&assertion.expr),
+        Expr::TsSatisfies(satisfies) => object_literal(&satisfies.expr),
+        _ => None,
+    }
+}
+
+fn count_object_keys(object: &ObjectLit) -> usize {
+    let mut keys = Vec::new();
+    collect_object_keys(object, "", &mut keys);
+    keys.len()
+}
+
+fn collect_object_keys(object: &ObjectLit, prefix: &str, keys: &mut Vec<String>) {
+    for prop in &object.props {
+        let PropOrSpread::Prop(prop) = prop else {
+            continue;
+        };
+        match &**prop {
+            Prop::KeyValue(key_value) => {
+                let Some(key) = prop_name(&key_value.key) else {
+                    continue;
+                };
+                let key = join_key(prefix, &key);
+                match object_literal(&key_value.value) {
+                    Some(nested) => collect_object_keys(nested, &key, keys),
+                    None => keys.push(key),
+                }
+            }
+            Prop::Shorthand(ident) => keys.push(join_key(prefix, &ident.sym)),
+            Prop::Method(method) => {
+                if let Some(key) = prop_name(&method.key) {
+                    keys.push(join_key(prefix, &key));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn prop_name(name: &PropName) -> Option<String> {
+    match name {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(value) => Some(value.value.to_string_lossy().into_owned()),
+        PropName::Num(value) => Some(value.value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocaleKeys, completeness, locale_file_keys, locale_of_file};
+
+    #[test]
+    fn finds_locales_by_file_name_or_locale_directory() {
+        assert_eq!(locale_of_file("src/lang/locale/de.ts"), Some(("de", None)));
+        assert_eq!(locale_of_file("locales/zh_cn.json"), Some(("zh-CN", None)));
+        assert_eq!(
+            locale_of_file("public/locales/fr/settings.json"),
+            Some(("fr", Some("settings".to_string())))
+        );
+        assert_eq!(locale_of_file("src/de/settings.json"), None);
+        assert_eq!(locale_of_file("src/it.ts"), None);
+        assert_eq!(locale_of_file("id.json"), None);
+        assert_eq!(locale_of_file("src/main.ts"), None);
+        assert_eq!(locale_of_file("types/en.d.ts"), None);
+    }
+
+    #[test]
+    fn compares_completeness_within_the_same_layout() {
+        let keys = |from_locale_files: &[&str], from_locale_dirs: &[&str]| LocaleKeys {
+            from_locale_files: from_locale_files
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            from_locale_dirs: from_locale_dirs.iter().map(|key| key.to_string()).collect(),
+        };
+        let base = keys(&["ok", "cancel"], &[]);
+
+        assert_eq!(
+            completeness(&base, &keys(&[], &["common:ok", "common:cancel"])),
+            None
+        );
+        assert_eq!(
+            completeness(&base, &keys(&["ok"], &["common:ok"])),
+            Some(0.5)
+        );
+        assert_eq!(completeness(&base, &LocaleKeys::default()), Some(0.0));
+    }
+
+    #[test]
+    fn counts_nested_keys_of_json_yaml_and_ts_locales() {
+        let json = r#"{ "settings": { "title": "Title", "desc": "Desc" }, "ok": "OK" }"#;
+        assert_eq!(
+            locale_file_keys("en.json", json),
+            vec!["ok", "settings.desc", "settings.title"]
+        );
+
+        let yaml = "settings:\n  title: Titel\nok: OK\n";
+        assert_eq!(
+            locale_file_keys("de.yml", yaml),
+            vec!["settings.title", "ok"]
+        );
+
+        let ts = r#"
+            import type { Locale } from "./types";
+            const helper = { unrelated: 1 };
+            export default {
+                settings: { title: "Titre", "desc": `Desc` },
+                greet: (name: string) => `Bonjour ${name}`,
+            } satisfies Locale;
+        "#;
+        assert_eq!(
+            locale_file_keys("fr.ts", ts),
+            vec!["settings.title", "settings.desc", "greet"]
+        );
+    }
+}
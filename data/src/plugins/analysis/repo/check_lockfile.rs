@@ -0,0 +1,383 @@
+//! Reads resolved package versions from the lockfile at the repository root.
+//! `bun.lockb` is a binary format and `lock.json` belongs to Deno, so neither
+//! is parsed.
+
+use std::{collections::BTreeSet, fs};
+
+use data_lib::plugin::{PluginRepoAnalysisDetailError, ResolvedDependency};
+
+use super::safe_repo_file_path;
+
+/// Lockfiles in the order they are tried. Only the first one present is read.
+const LOCK_FILES: &[(&str, LockFileFormat)] = &[
+    ("package-lock.json", LockFileFormat::Npm),
+    ("pnpm-lock.yaml", LockFileFormat::Pnpm),
+    ("yarn.lock", LockFileFormat::Yarn),
+    ("bun.lock", LockFileFormat::Bun),
+];
+
+/// Lockfiles of large monorepos exceed this, plugin repositories do not.
+const MAX_LOCK_FILE_BYTES: u64 = 32 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+enum LockFileFormat {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+pub(super) fn run(
+    repo_path: &str,
+    plugin_id: &str,
+    files: &[String],
+    direct_dependencies: &[&String],
+) -> Result<Vec<ResolvedDependency>, PluginRepoAnalysisDetailError> {
+    let Some((lock_file, format)) = LOCK_FILES
+        .iter()
+        .find(|(name, _)| files.iter().any(|file| file == name))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let parse_error = |message: String| PluginRepoAnalysisDetailError::LockfileParse {
+        plugin_id: plugin_id.to_string(),
+        lock_file: lock_file.to_string(),
+        message,
+    };
+
+    let path = safe_repo_file_path(repo_path, lock_file).map_err(|e| parse_error(e.to_string()))?;
+    let size = fs::metadata(&path)
+        .map_err(|e| parse_error(e.to_string()))?
+        .len();
+    if size > MAX_LOCK_FILE_BYTES {
+        return Err(parse_error(format!("file is {size} bytes")));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| parse_error(e.to_string()))?;
+    let content = content.trim_start_matches('\u{feff}');
+
+    let packages = match format {
+        LockFileFormat::Npm => parse_package_lock(content),
+        LockFileFormat::Pnpm => parse_pnpm_lock(content),
+        LockFileFormat::Yarn => Ok(parse_yarn_lock(content)),
+        LockFileFormat::Bun => parse_bun_lock(content),
+    }
+    .map_err(parse_error)?;
+
+    Ok(packages
+        .into_iter()
+        .map(|(name, version)| ResolvedDependency {
+            direct: direct_dependencies.iter().any(|direct| **direct == name),
+            name,
+            version,
+        })
+        .collect())
+}
+
+/// Reads `packages` (lockfile v2 and v3) or the nested `dependencies` tree
+/// (lockfile v1).
+fn parse_package_lock(content: &str) -> Result<BTreeSet<(String, String)>, String> {
+    let lock: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut packages = BTreeSet::new();
+
+    if let Some(entries) = lock.get("packages").and_then(|value| value.as_object()) {
+        for (path, entry) in entries {
+            // The empty path is the project itself, paths outside
+            // node_modules are workspace members.
+            let Some((_, installed_name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if entry.get("link").and_then(|link| link.as_bool()) == Some(true) {
+                continue;
+            }
+            let name = entry
+                .get("name")
+                .and_then(|name| name.as_str())
+                .unwrap_or(installed_name);
+            if let Some(version) = entry.get("version").and_then(|version| version.as_str()) {
+                packages.insert((name.to_string(), version.to_string()));
+            }
+        }
+    } else if let Some(dependencies) = lock.get("dependencies") {
+        collect_package_lock_v1(dependencies, &mut packages);
+    }
+
+    Ok(packages)
+}
+
+fn collect_package_lock_v1(dependencies: &serde_json::Value, out: &mut BTreeSet<(String, String)>) {
+    let Some(dependencies) = dependencies.as_object() else {
+        return;
+    };
+    for (name, entry) in dependencies {
+        if let Some(version) = entry.get("version").and_then(|version| version.as_str())
+            && !version.starts_with("file:")
+        {
+            out.insert((name.clone(), version.to_string()));
+        }
+        if let Some(nested) = entry.get("dependencies") {
+            collect_package_lock_v1(nested, out);
+        }
+    }
+}
+
+/// Reads the keys of `packages`, which look like `/name/1.0.0` (v5),
+/// `/name@1.0.0` (v6) or `name@1.0.0` (v9), optionally followed by a peer
+/// dependency suffix.
+fn parse_pnpm_lock(content: &str) -> Result<BTreeSet<(String, String)>, String> {
+    let lock: serde_yaml::Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+    let mut packages = BTreeSet::new();
+
+    let Some(entries) = lock.get("packages").and_then(|value| value.as_mapping()) else {
+        return Ok(packages);
+    };
+    for (key, entry) in entries {
+        let field = |name: &str| entry.get(name).and_then(|value| value.as_str());
+        let parsed = match (field("name"), field("version")) {
+            (Some(name), Some(version)) => Some((name.to_string(), version.to_string())),
+            _ => key.as_str().and_then(parse_pnpm_package_key),
+        };
+        if let Some(package) = parsed {
+            packages.insert(package);
+        }
+    }
+
+    Ok(packages)
+}
+
+fn parse_pnpm_package_key(key: &str) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let key = key.split_once('(').map_or(key, |(key, _)| key);
+
+    let (name, version) = match split_package_spec(key).filter(|(name, _)| is_package_name(name)) {
+        Some(split) => split,
+        None => {
+            let (name, version) = key.rsplit_once('/')?;
+            (name, version.split_once('_').map_or(version, |(v, _)| v))
+        }
+    };
+    is_registry_version(version).then(|| (name.to_string(), version.to_string()))
+}
+
+/// Reads both the classic format (`version "1.0.0"`) and the Berry format
+/// (`version: 1.0.0`). Workspace, link and portal entries are skipped.
+fn parse_yarn_lock(content: &str) -> BTreeSet<(String, String)> {
+    let mut packages = BTreeSet::new();
+    let mut names: Vec<&str> = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            names = line
+                .strip_suffix(':')
+                .filter(|header| *header != "__metadata")
+                .map(|header| {
+                    header
+                        .split(", ")
+                        .map(|spec| spec.trim().trim_matches('"'))
+                        .filter(|spec| {
+                            !["@workspace:", "@link:", "@portal:", "@file:"]
+                                .iter()
+                                .any(|protocol| spec.contains(protocol))
+                        })
+                        .filter_map(|spec| split_package_spec(spec).map(|(name, _)| name))
+                        .collect()
+                })
+                .unwrap_or_default();
+            names.dedup();
+            continue;
+        }
+
+        let Some(version) = line.trim().strip_prefix("version") else {
+            continue;
+        };
+        let version = version.trim_start_matches(':').trim().trim_matches('"');
+        if !version.is_empty() {
+            for name in names.drain(..) {
+                packages.insert((name.to_string(), version.to_string()));
+            }
+        }
+    }
+
+    packages
+}
+
+/// Reads `packages`, whose values start with the resolved `name@version`.
+fn parse_bun_lock(content: &str) -> Result<BTreeSet<(String, String)>, String> {
+    let lock: serde_json::Value =
+        serde_json::from_str(&strip_trailing_commas(content)).map_err(|e| e.to_string())?;
+    let mut packages = BTreeSet::new();
+
+    let Some(entries) = lock.get("packages").and_then(|value| value.as_object()) else {
+        return Ok(packages);
+    };
+    for entry in entries.values() {
+        if let Some(spec) = entry
+            .get(0)
+            .and_then(|spec| spec.as_str())
+            .and_then(split_package_spec)
+            .filter(|(_, version)| is_registry_version(version))
+        {
+            packages.insert((spec.0.to_string(), spec.1.to_string()));
+        }
+    }
+
+    Ok(packages)
+}
+
+/// bun.lock is JSON with trailing commas.
+fn strip_trailing_commas(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, ch) in content.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if ch == ',' {
+            let next = content[index + 1..].trim_start().chars().next();
+            if matches!(next, Some('}' | ']')) {
+                continue;
+            }
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+/// Splits `name@range` or `@scope/name@range` at the `@` after the name.
+fn split_package_spec(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    let (name, version) = (&spec[..at], &spec[at + 1..]);
+    (!name.is_empty() && !version.is_empty()).then_some((name, version))
+}
+
+fn is_package_name(name: &str) -> bool {
+    let slashes = name.matches('/').count();
+    if name.starts_with('@') {
+        slashes == 1
+    } else {
+        slashes == 0
+    }
+}
+
+/// Whether `version` is a plain semver version rather than a git, tarball or
+/// workspace reference.
+fn is_registry_version(version: &str) -> bool {
+    version.starts_with(|ch: char| ch.is_ascii_digit()) && !version.contains(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{parse_bun_lock, parse_package_lock, parse_pnpm_lock, parse_yarn_lock};
+
+    fn set(entries: &[(&str, &str)]) -> BTreeSet<(String, String)> {
+        entries
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_package_lock_v3_and_v1() {
+        let v3 = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "name": "plugin", "version": "1.0.0" },
+                "node_modules/obsidian": { "version": "1.4.11" },
+                "node_modules/@codemirror/state": { "version": "6.2.0" },
+                "node_modules/a/node_modules/lodash": { "version": "4.17.20" },
+                "node_modules/alias": { "name": "lodash", "version": "4.17.21" },
+                "node_modules/local": { "resolved": "packages/local", "link": true },
+                "packages/local": { "version": "0.0.1" }
+            }
+        }"#;
+        assert_eq!(
+            parse_package_lock(v3).unwrap(),
+            set(&[
+                ("@codemirror/state", "6.2.0"),
+                ("lodash", "4.17.20"),
+                ("lodash", "4.17.21"),
+                ("obsidian", "1.4.11"),
+            ])
+        );
+
+        let v1 = r#"{
+            "lockfileVersion": 1,
+            "dependencies": {
+                "a": { "version": "1.0.0", "dependencies": { "b": { "version": "2.0.0" } } }
+            }
+        }"#;
+        assert_eq!(
+            parse_package_lock(v1).unwrap(),
+            set(&[("a", "1.0.0"), ("b", "2.0.0")])
+        );
+        assert!(parse_package_lock("{").is_err());
+    }
+
+    #[test]
+    fn parses_pnpm_lock_key_formats() {
+        let lock = "lockfileVersion: '9.0'\n\
+            packages:\n  \
+              /moment/2.29.4:\n    resolution: {integrity: x}\n  \
+              /@types/node/18.0.0_typescript@5.0.0:\n    resolution: {integrity: x}\n  \
+              /tslib@2.6.0:\n    resolution: {integrity: x}\n  \
+              obsidian@1.5.7(@codemirror/state@6.4.0):\n    resolution: {integrity: x}\n  \
+              github.com/user/repo/abc:\n    name: forked\n    version: 0.1.0\n";
+        assert_eq!(
+            parse_pnpm_lock(lock).unwrap(),
+            set(&[
+                ("@types/node", "18.0.0"),
+                ("forked", "0.1.0"),
+                ("moment", "2.29.4"),
+                ("obsidian", "1.5.7"),
+                ("tslib", "2.6.0"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_classic_and_berry_yarn_locks() {
+        let classic = "# yarn lockfile v1\n\n\
+            \"@types/estree@*\", \"@types/estree@^1.0.0\":\n  version \"1.0.1\"\n  resolved \"x\"\n\n\
+            lodash@^4.17.0:\n  version \"4.17.21\"\n";
+        assert_eq!(
+            parse_yarn_lock(classic),
+            set(&[("@types/estree", "1.0.1"), ("lodash", "4.17.21")])
+        );
+
+        let berry = "__metadata:\n  version: 6\n\n\
+            \"obsidian@npm:latest\":\n  version: 1.5.7\n  dependencies:\n    moment: 2.29.4\n\n\
+            \"plugin@workspace:.\":\n  version: 0.0.0-use.local\n";
+        assert_eq!(parse_yarn_lock(berry), set(&[("obsidian", "1.5.7")]));
+    }
+
+    #[test]
+    fn parses_bun_lock_with_trailing_commas() {
+        let lock = r#"{
+  "lockfileVersion": 1,
+  "workspaces": { "": { "name": "plugin", }, },
+  "packages": {
+    "obsidian": ["obsidian@1.5.7", "", { "peerDependencies": { "x": "1" } }, "sha512-a,"],
+    "@types/node": ["@types/node@20.1.0", "", {}, "sha512-b"],
+    "forked": ["forked@github:user/repo#abc", {}],
+  },
+}"#;
+        assert_eq!(
+            parse_bun_lock(lock).unwrap(),
+            set(&[("@types/node", "20.1.0"), ("obsidian", "1.5.7")])
+        );
+    }
+}
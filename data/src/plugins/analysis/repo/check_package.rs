@@ -1,9 +1,12 @@
-use std::fs;
+use std::{collections::BTreeMap, fs};
 
 use data_lib::{
     common::I18N_DEPENDENCIES,
     plugin::{
-        PluginRepoAnalysisDetailError, bundlers::Bundler, packages::PackageManager,
+        PluginRepoAnalysisDetailError,
+        bundlers::Bundler,
+        detectors::{DetectedTool, DetectionInput, ToolDetection, detect_tools},
+        packages::PackageManager,
         testing::TestingFramework,
     },
 };
@@ -16,20 +19,31 @@ pub(super) struct PackageResult {
     pub(super) dev_dependencies: Vec<String>,
     pub(super) testing_frameworks: Vec<TestingFramework>,
     pub(super) bundlers: Vec<Bundler>,
+    pub(super) tool_detections: Vec<ToolDetection>,
     pub(super) package_json_license: Option<String>,
     pub(super) has_i18n_dependencies: bool,
+    pub(super) obsidian_typings_range: Option<String>,
+    pub(super) scripts: BTreeMap<String, String>,
 }
 
 impl PackageResult {
     pub(super) fn empty(files: &[String]) -> Self {
+        let tool_detections = detect_tools(&DetectionInput {
+            files,
+            dependencies: &[],
+            scripts: &BTreeMap::new(),
+        });
         Self {
-            package_managers: PackageManager::find_package_managers(files),
+            package_managers: PackageManager::from_detections(&tool_detections),
             dependencies: Vec::new(),
             dev_dependencies: Vec::new(),
-            testing_frameworks: Vec::new(),
-            bundlers: Vec::new(),
+            testing_frameworks: TestingFramework::from_detections(&tool_detections),
+            bundlers: Bundler::from_detections(&tool_detections),
+            tool_detections,
             package_json_license: None,
             has_i18n_dependencies: false,
+            obsidian_typings_range: None,
+            scripts: BTreeMap::new(),
         }
     }
 }
@@ -57,16 +71,45 @@ pub(super) fn run(
         .and_then(|l| l.as_str())
         .map(|s| s.to_string());
 
+    let obsidian_typings_range = ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .find_map(|key| {
+            package_json
+                .get(key)
+                .and_then(|dependencies| dependencies.get("obsidian"))
+                .and_then(|range| range.as_str())
+        })
+        .map(|range| range.trim().to_string());
+
+    let scripts = package_json
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .map_or_else(BTreeMap::new, |scripts| {
+            scripts
+                .iter()
+                .filter_map(|(name, command)| Some((name.clone(), command.as_str()?.to_string())))
+                .collect()
+        });
+
+    let tool_detections = detect_tools(&DetectionInput {
+        files,
+        dependencies: &all_dependencies,
+        scripts: &scripts,
+    });
+
     Ok(PackageResult {
-        package_managers: PackageManager::find_package_managers(files),
-        testing_frameworks: TestingFramework::find_testing_frameworks(&all_dependencies),
-        bundlers: Bundler::find_bundlers(&all_dependencies),
+        package_managers: PackageManager::from_detections(&tool_detections),
+        testing_frameworks: TestingFramework::from_detections(&tool_detections),
+        bundlers: Bundler::from_detections(&tool_detections),
+        tool_detections,
         has_i18n_dependencies: all_dependencies
             .iter()
             .any(|dep| I18N_DEPENDENCIES.contains(&dep.as_str())),
         dependencies,
         dev_dependencies,
         package_json_license,
+        obsidian_typings_range,
+        scripts,
     })
 }
 
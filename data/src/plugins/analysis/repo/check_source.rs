@@ -0,0 +1,79 @@
+use std::{fs, path::Path};
+
+use super::{
+    super::mainjs::{SourceFingerprint, fingerprint_source},
+    safe_repo_file_path,
+};
+
+const MAX_SOURCE_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_COMMITTED_MAIN_JS_BYTES: u64 = 10 * 1024 * 1024;
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "svelte", "vue"];
+const BUILD_OUTPUT_DIRS: &[&str] = &["dist/", "build/", "out/", "coverage/"];
+
+pub(super) fn run(repo_path: &str, files: &[String]) -> SourceFingerprint {
+    let committed_main_js = files
+        .iter()
+        .any(|file| file == "main.js")
+        .then(|| read_limited(repo_path, "main.js", MAX_COMMITTED_MAIN_JS_BYTES))
+        .flatten();
+    let sources = files
+        .iter()
+        .filter(|file| is_bundled_source_file(file))
+        .filter_map(|file| read_limited(repo_path, file, MAX_SOURCE_FILE_BYTES))
+        .collect::<Vec<_>>();
+
+    fingerprint_source(committed_main_js, &sources)
+}
+
+/// Source files that plausibly end up in the bundle. Build output, tests and
+/// build scripts are skipped because their strings never reach main.js.
+pub(super) fn is_bundled_source_file(file: &str) -> bool {
+    let path = Path::new(file);
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let has_source_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+
+    has_source_extension
+        && file != "main.js"
+        && !file_name.ends_with(".d.ts")
+        && !file_name.contains(".config.")
+        && !file_name.contains(".test.")
+        && !file_name.contains(".spec.")
+        && !file_name.starts_with("version-bump")
+        && !BUILD_OUTPUT_DIRS.iter().any(|dir| file.starts_with(dir))
+}
+
+pub(super) fn read_limited(repo_path: &str, relative_path: &str, max_bytes: u64) -> Option<String> {
+    let path = safe_repo_file_path(repo_path, relative_path).ok()?;
+    if fs::metadata(&path).ok()?.len() > max_bytes {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_bundled_source_file;
+
+    #[test]
+    fn skips_build_output_tests_and_scripts() {
+        for file in ["main.ts", "src/view.tsx", "src/ui/Board.svelte"] {
+            assert!(is_bundled_source_file(file), "{file}");
+        }
+        for file in [
+            "main.js",
+            "dist/main.js",
+            "esbuild.config.mjs",
+            "version-bump.mjs",
+            "src/view.test.ts",
+            "src/types.d.ts",
+            "README.md",
+        ] {
+            assert!(!is_bundled_source_file(file), "{file}");
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::BTreeMap, path::Path};
+
+use data_lib::plugin::testing::PluginTestData;
+use swc_ecma_ast::{CallExpr, Callee, Expr, MemberProp, Program};
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::{
+    check_files::count_lines_of_code,
+    check_source::{is_bundled_source_file, read_limited},
+    parse_source_file,
+};
+
+const MAX_TEST_FILE_BYTES: u64 = 1024 * 1024;
+const TEST_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+/// Top-level directories whose scripts are all treated as tests, including
+/// their helpers and mocks.
+const TEST_DIRS: &[&str] = &["test/", "tests/"];
+const TEST_FUNCTIONS: &[&str] = &["it", "test"];
+/// Modifiers like `it.only(...)` that still declare a single test case.
+const TEST_MODIFIERS: &[&str] = &["only", "skip", "todo", "concurrent", "failing", "fails"];
+const COVERAGE_FILES: &[&str] = &[
+    ".nycrc",
+    ".nycrc.json",
+    ".nycrc.yml",
+    ".nycrc.yaml",
+    ".c8rc",
+    ".c8rc.json",
+    "codecov.yml",
+    "codecov.yaml",
+    ".codecov.yml",
+    ".github/codecov.yml",
+];
+const TEST_RUNNER_CONFIG_PREFIXES: &[&str] = &["jest.config.", "vitest.config.", "vite.config."];
+const COVERAGE_COMMANDS: &[&str] = &["c8", "nyc"];
+/// What `npm init` puts into `scripts.test`.
+const NPM_PLACEHOLDER_TEST_SCRIPT: &str = "echo \"Error: no test specified\" && exit 1";
+
+pub(super) fn run(
+    repo_path: &str,
+    files: &[String],
+    scripts: &BTreeMap<String, String>,
+) -> PluginTestData {
+    let test_files = files
+        .iter()
+        .filter(|file| is_test_file(file))
+        .cloned()
+        .collect::<Vec<_>>();
+    let source_files = files
+        .iter()
+        .filter(|file| is_bundled_source_file(file) && !is_test_file(file))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let test_case_count = test_files
+        .iter()
+        .filter_map(|file| {
+            let source = read_limited(repo_path, file, MAX_TEST_FILE_BYTES)?;
+            Some(count_test_cases(&parse_source_file(file, &source)?))
+        })
+        .sum();
+
+    PluginTestData {
+        test_file_count: test_files.len() as u32,
+        test_case_count,
+        test_lines_of_code: total_lines_of_code(repo_path, &test_files),
+        source_lines_of_code: total_lines_of_code(repo_path, &source_files),
+        has_coverage_config: has_coverage_config(repo_path, files, scripts),
+        has_test_script: scripts.iter().any(|(name, command)| {
+            (name == "test" || name.starts_with("test:"))
+                && command.trim() != NPM_PLACEHOLDER_TEST_SCRIPT
+        }),
+    }
+}
+
+/// `*.test.*` and `*.spec.*` scripts, and scripts in `__tests__` or top-level
+/// test directories.
+pub(super) fn is_test_file(file: &str) -> bool {
+    let path = Path::new(file);
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let has_test_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| TEST_EXTENSIONS.contains(&ext));
+
+    has_test_extension
+        && !file_name.ends_with(".d.ts")
+        && (file_name.contains(".test.")
+            || file_name.contains(".spec.")
+            || file.starts_with("__tests__/")
+            || file.contains("/__tests__/")
+            || TEST_DIRS.iter().any(|dir| file.starts_with(dir)))
+}
+
+fn total_lines_of_code(repo_path: &str, files: &[String]) -> u32 {
+    if files.is_empty() {
+        return 0;
+    }
+    count_lines_of_code(repo_path, files)
+        .values()
+        .sum::<usize>() as u32
+}
+
+fn count_test_cases(program: &Program) -> u32 {
+    let mut visitor = TestCaseVisitor::default();
+    program.visit_with(&mut visitor);
+    visitor.count
+}
+
+#[derive(Default)]
+struct TestCaseVisitor {
+    count: u32,
+}
+
+impl Visit for TestCaseVisitor {
+    fn visit_call_expr(&mut self, expr: &CallExpr) {
+        if let Callee::Expr(callee) = &expr.callee
+            && is_test_case_callee(callee)
+        {
+            self.count += 1;
+        }
+
+        expr.visit_children_with(self);
+    }
+}
+
+/// `it`, `test`, modified forms like `test.skip` and the function returned by
+/// `it.each(table)`.
+fn is_test_case_callee(callee: &Expr) -> bool {
+    match callee {
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(each),
+            ..
+        }) => matches!(
+            &**each,
+            Expr::Member(member)
+                if matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == *"each")
+                    && is_test_function(&member.obj)
+        ),
+        _ => is_test_function(callee),
+    }
+}
+
+fn is_test_function(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(ident) => TEST_FUNCTIONS.iter().any(|name| ident.sym == **name),
+        Expr::Member(member) => {
+            matches!(
+                &member.prop,
+                MemberProp::Ident(prop)
+                    if TEST_MODIFIERS.iter().any(|modifier| prop.sym == **modifier)
+            ) && is_test_function(&member.obj)
+        }
+        _ => false,
+    }
+}
+
+/// A coverage tool config, a test runner config mentioning coverage, or a
+/// script collecting coverage.
+fn has_coverage_config(
+    repo_path: &str,
+    files: &[String],
+    scripts: &BTreeMap<String, String>,
+) -> bool {
+    files
+        .iter()
+        .any(|file| COVERAGE_FILES.contains(&file.as_str()))
+        || files
+            .iter()
+            .filter(|file| {
+                TEST_RUNNER_CONFIG_PREFIXES
+                    .iter()
+                    .any(|prefix| file.starts_with(prefix))
+            })
+            .filter_map(|file| read_limited(repo_path, file, MAX_TEST_FILE_BYTES))
+            .any(|content| content.contains("coverage"))
+        || scripts.values().any(|command| {
+            command
+                .split_whitespace()
+                .any(|token| token.starts_with("--coverage") || COVERAGE_COMMANDS.contains(&token))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_test_cases, is_test_file, parse_source_file};
+
+    #[test]
+    fn detects_test_files() {
+        for file in [
+            "src/main.test.ts",
+            "src/view.spec.tsx",
+            "src/__tests__/parser.ts",
+            "tests/mocks/obsidian.ts",
+        ] {
+            assert!(is_test_file(file), "{file}");
+        }
+        for file in [
+            "src/main.ts",
+            "src/test/helpers.ts",
+            "tests/types.d.ts",
+            "test/fixture.md",
+        ] {
+            assert!(!is_test_file(file), "{file}");
+        }
+    }
+
+    #[test]
+    fn counts_test_cases_including_modifiers_and_tables() {
+        let source = r#"
+            import { describe, it, test, expect } from "vitest";
+
+            describe("parser", () => {
+                it("parses", () => expect(parse("a")).toBe("a"));
+                it.skip("handles errors", () => {});
+                test.each([[1], [2]])("case %i", (value: number) => {
+                    expect(value).toBeGreaterThan(0);
+                });
+                test.todo("streams");
+            });
+            helper.it("not a test");
+        "#;
+        let program = parse_source_file("src/parser.test.ts", source).unwrap();
+
+        assert_eq!(count_test_cases(&program), 4);
+    }
+}
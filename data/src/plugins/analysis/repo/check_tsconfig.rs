@@ -0,0 +1,224 @@
+use data_lib::plugin::tsconfig::TsConfigData;
+use serde_json::{Map, Value};
+
+use super::check_source::read_limited;
+
+const MAX_CONFIG_FILE_BYTES: u64 = 1024 * 1024;
+const ROOT_TSCONFIG: &str = "tsconfig.json";
+/// Guards against long or cyclic `extends` chains.
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+pub(super) fn run(repo_path: &str, files: &[String]) -> Option<TsConfigData> {
+    if !files.iter().any(|file| file == ROOT_TSCONFIG) {
+        return None;
+    }
+
+    resolve_tsconfig(|file| {
+        files
+            .iter()
+            .any(|known| known == file)
+            .then(|| read_limited(repo_path, file, MAX_CONFIG_FILE_BYTES))
+            .flatten()
+    })
+}
+
+/// Reads `tsconfig.json` through `read_file` and merges the compiler options
+/// of the in-repo configs it extends. Later `extends` entries override earlier
+/// ones and the extending config overrides all of them.
+fn resolve_tsconfig(read_file: impl Fn(&str) -> Option<String>) -> Option<TsConfigData> {
+    let mut tsconfig = TsConfigData::default();
+    let options = collect_compiler_options(ROOT_TSCONFIG, &read_file, &mut tsconfig, 0)?;
+
+    let bool_option = |name: &str| options.get(name).and_then(Value::as_bool);
+    let string_option = |name: &str| {
+        options
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::to_ascii_lowercase)
+    };
+
+    tsconfig.strict = bool_option("strict").unwrap_or(false);
+    tsconfig.no_implicit_any = bool_option("noImplicitAny").unwrap_or(tsconfig.strict);
+    tsconfig.target = string_option("target");
+    tsconfig.module = string_option("module");
+    tsconfig.module_resolution = string_option("moduleResolution");
+    tsconfig.source_map = bool_option("sourceMap").unwrap_or(false)
+        || bool_option("inlineSourceMap").unwrap_or(false);
+    tsconfig.isolated_modules = bool_option("isolatedModules").unwrap_or(false);
+    Some(tsconfig)
+}
+
+fn collect_compiler_options(
+    file: &str,
+    read_file: &impl Fn(&str) -> Option<String>,
+    tsconfig: &mut TsConfigData,
+    depth: usize,
+) -> Option<Map<String, Value>> {
+    if depth > MAX_EXTENDS_DEPTH || tsconfig.files.iter().any(|read| read == file) {
+        return None;
+    }
+    let config = serde_json::from_str::<Value>(&strip_jsonc(&read_file(file)?)).ok()?;
+    tsconfig.files.push(file.to_string());
+
+    let extends = match config.get("extends") {
+        Some(Value::String(extends)) => vec![extends.as_str()],
+        Some(Value::Array(extends)) => extends.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+
+    let mut options = Map::new();
+    for extends in extends {
+        let base = resolve_extends(file, extends).and_then(|base| {
+            [base.clone(), format!("{base}.json")]
+                .into_iter()
+                .find(|candidate| read_file(candidate).is_some())
+        });
+        match base {
+            Some(base) => {
+                if let Some(base_options) =
+                    collect_compiler_options(&base, read_file, tsconfig, depth + 1)
+                {
+                    options.extend(base_options);
+                }
+            }
+            None => tsconfig.external_extends.push(extends.to_string()),
+        }
+    }
+    if let Some(Value::Object(own_options)) = config.get("compilerOptions") {
+        options.extend(own_options.clone());
+    }
+    Some(options)
+}
+
+/// Resolves a relative `extends` path against the directory of `file`.
+/// Package specifiers and paths leaving the repo resolve to `None`.
+fn resolve_extends(file: &str, extends: &str) -> Option<String> {
+    if !extends.starts_with("./") && !extends.starts_with("../") {
+        return None;
+    }
+
+    let mut parts = file.split('/').collect::<Vec<_>>();
+    parts.pop();
+    for part in extends.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Turns the JSON with comments and trailing commas that TypeScript accepts
+/// into plain JSON.
+fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|next| *next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ('}' | ']', _) => {
+                if output.trim_end().ends_with(',') {
+                    let comma = output.trim_end().len() - 1;
+                    output.remove(comma);
+                }
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{resolve_extends, resolve_tsconfig, strip_jsonc};
+
+    #[test]
+    fn strips_comments_and_trailing_commas_outside_strings() {
+        let content = r#"{
+            // comment
+            "a": "http://x/*y*/", /* block */
+            "b": [1, 2,],
+        }"#;
+        let value = serde_json::from_str::<serde_json::Value>(&strip_jsonc(content)).unwrap();
+
+        assert_eq!(value["a"], "http://x/*y*/");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn merges_options_along_in_repo_extends_chain() {
+        let files = HashMap::from([
+            (
+                "tsconfig.json",
+                r#"{
+                    "extends": ["@tsconfig/svelte/tsconfig.json", "./config/tsconfig.base"],
+                    "compilerOptions": { "target": "ES2018", "noImplicitAny": false },
+                }"#,
+            ),
+            (
+                "config/tsconfig.base.json",
+                r#"{
+                    "extends": "../tsconfig.json",
+                    "compilerOptions": {
+                        "strict": true,
+                        "target": "ES6",
+                        "module": "ESNext",
+                        "inlineSourceMap": true,
+                    },
+                }"#,
+            ),
+        ]);
+
+        let tsconfig =
+            resolve_tsconfig(|file| files.get(file).map(|content| content.to_string())).unwrap();
+
+        assert_eq!(
+            tsconfig.files,
+            ["tsconfig.json", "config/tsconfig.base.json"]
+        );
+        assert_eq!(
+            tsconfig.external_extends,
+            ["@tsconfig/svelte/tsconfig.json"]
+        );
+        assert!(tsconfig.strict);
+        assert!(!tsconfig.no_implicit_any);
+        assert_eq!(tsconfig.target.as_deref(), Some("es2018"));
+        assert_eq!(tsconfig.module.as_deref(), Some("esnext"));
+        assert!(tsconfig.source_map);
+        assert!(!tsconfig.isolated_modules);
+
+        assert_eq!(resolve_extends("tsconfig.json", "../outside.json"), None);
+    }
+}
@@ -1,6 +1,9 @@
 use std::path::{Path, PathBuf};
 
 use data_lib::plugin::{PluginData, PluginRepoAnalysisDetailError};
+use swc_common::{FileName, SourceMap, sync::Lrc};
+use swc_ecma_ast::{EsVersion, Program};
+use swc_ecma_parser::{EsSyntax, Parser, StringInput, Syntax, TsSyntax, lexer::Lexer};
 
 use super::types::RepoResult;
 use crate::{
@@ -9,11 +12,16 @@ use crate::{
     security::{validate_existing_path_under, validate_relative_repo_path, validated_plugin_path},
 };
 
+mod check_ci;
 mod check_files;
 mod check_i18n;
 mod check_license;
+mod check_lockfile;
 mod check_manifest;
 mod check_package;
+mod check_source;
+mod check_tests;
+mod check_tsconfig;
 
 pub(super) const LOC_EXCLUDED: &[&str] = &[
     "package-lock.json",
@@ -36,10 +44,20 @@ pub(super) fn analyze_repo(
                 path: PathBuf::from(error),
             }
         })?;
+    analyze_repo_at(plugin, &repo_path, license_comparer)
+}
+
+/// Runs the repo checks against a checkout at `repo_path`, which may be the
+/// current clone or a materialized snapshot.
+pub(super) fn analyze_repo_at(
+    plugin: &PluginData,
+    repo_path: &Path,
+    license_comparer: &LicenseComparer,
+) -> Result<RepoResult, PluginRepoAnalysisDetailError> {
     if !repo_path.exists() {
         return Err(PluginRepoAnalysisDetailError::RepositoryMissing {
             plugin_id: plugin.id.clone(),
-            path: repo_path.clone(),
+            path: repo_path.to_path_buf(),
         });
     }
     let repo_path = repo_path.to_string_lossy().to_string();
@@ -61,6 +79,23 @@ pub(super) fn analyze_repo(
             check_package::PackageResult::empty(&file_data.files)
         }
     };
+    let direct_dependencies = package_data
+        .dependencies
+        .iter()
+        .chain(package_data.dev_dependencies.iter())
+        .collect::<Vec<_>>();
+    let resolved_dependencies = match check_lockfile::run(
+        &repo_path,
+        &plugin.id,
+        &file_data.files,
+        &direct_dependencies,
+    ) {
+        Ok(resolved_dependencies) => resolved_dependencies,
+        Err(error) => {
+            analysis_errors.push(error.code());
+            Vec::new()
+        }
+    };
 
     let (package_json_license, file_license) = check_license::run(
         &plugin.id,
@@ -70,23 +105,38 @@ pub(super) fn analyze_repo(
         license_comparer,
     );
 
+    let i18n = check_i18n::run(&repo_path, &file_data.files);
+    let ci = check_ci::run(&repo_path, &file_data.files);
+    let tsconfig = check_tsconfig::run(&repo_path, &file_data.files);
+    let tests = check_tests::run(&repo_path, &file_data.files, &package_data.scripts);
+
     Ok(RepoResult {
         uses_typescript: file_data.uses_typescript,
         has_package_json: file_data.has_package_json,
         package_managers: package_data.package_managers,
         dependencies: package_data.dependencies,
         dev_dependencies: package_data.dev_dependencies,
+        resolved_dependencies,
         testing_frameworks: package_data.testing_frameworks,
         bundlers: package_data.bundlers,
+        tool_detections: package_data.tool_detections,
         has_test_files: file_data.has_test_files,
+        tests,
         has_beta_manifest: file_data.has_beta_manifest,
+        ci,
+        tsconfig,
         file_type_counts: file_data.file_type_counts,
         package_json_license,
         file_license,
         manifest,
         lines_of_code: file_data.lines_of_code,
         has_i18n_dependencies: package_data.has_i18n_dependencies,
-        has_i18n_files: check_i18n::has_i18n_files(&file_data.files),
+        obsidian_typings_range: package_data.obsidian_typings_range,
+        has_i18n_files: i18n.has_i18n_files,
+        i18n_locales: i18n.locales,
+        i18n_base_locale: i18n.base_locale,
+        i18n_language_detection: i18n.language_detection,
+        source_fingerprint: check_source::run(&repo_path, &file_data.files),
         analysis_errors,
     })
 }
@@ -110,3 +160,38 @@ pub(super) fn safe_repo_file_path(
 
     Ok(path)
 }
+
+/// Parses a repository source file, with TypeScript syntax for `.ts`, `.tsx`
+/// and `.mts` files.
+pub(super) fn parse_source_file(relative_path: &str, source: &str) -> Option<Program> {
+    let cm = Lrc::new(SourceMap::default());
+    let fm = cm.new_source_file(
+        FileName::Custom(relative_path.to_string()).into(),
+        source.to_string(),
+    );
+    let syntax = if [".ts", ".tsx", ".mts", ".cts"]
+        .iter()
+        .any(|ending| relative_path.ends_with(ending))
+    {
+        Syntax::Typescript(TsSyntax {
+            tsx: relative_path.ends_with(".tsx"),
+            decorators: true,
+            ..Default::default()
+        })
+    } else {
+        Syntax::Es(EsSyntax {
+            jsx: true,
+            decorators: true,
+            ..Default::default()
+        })
+    };
+
+    Parser::new_from(Lexer::new(
+        syntax,
+        EsVersion::EsNext,
+        StringInput::from(&*fm),
+        None,
+    ))
+    .parse_program()
+    .ok()
+}
@@ -8,6 +8,11 @@ pub(super) struct ExtraRunStats {
     pub(super) release_state_missing: usize,
     pub(super) release_main_js_scanned: usize,
     pub(super) release_main_js_scan_failed: usize,
+    pub(super) release_manifest_mismatched: usize,
+    pub(super) release_asset_reuploaded: usize,
+    pub(super) dependency_advisory_matched: usize,
+    pub(super) min_app_version_older_than_api_usage: usize,
+    pub(super) versions_json_inconsistent: usize,
     pub(super) status_counts: HashMap<String, usize>,
 }
 
@@ -18,6 +23,11 @@ impl ExtraRunStats {
         self.release_state_missing += other.release_state_missing;
         self.release_main_js_scanned += other.release_main_js_scanned;
         self.release_main_js_scan_failed += other.release_main_js_scan_failed;
+        self.release_manifest_mismatched += other.release_manifest_mismatched;
+        self.release_asset_reuploaded += other.release_asset_reuploaded;
+        self.dependency_advisory_matched += other.dependency_advisory_matched;
+        self.min_app_version_older_than_api_usage += other.min_app_version_older_than_api_usage;
+        self.versions_json_inconsistent += other.versions_json_inconsistent;
 
         for (status, count) in other.status_counts {
             *self.status_counts.entry(status).or_insert(0) += count;
@@ -0,0 +1,216 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use data_lib::plugin::{
+    PluginData,
+    timeline::{MainJsReleaseAnalysis, MainJsReleaseEvent, PluginReleaseTimeline},
+};
+
+use super::{
+    mainjs::analyze_main_js, output::public_api_capabilities, pipeline::MAX_MAIN_JS_ANALYSIS_BYTES,
+    release_diff::release_events,
+};
+use crate::{
+    constants::PLUGIN_RELEASE_TIMELINE_PATH,
+    file_utils::ensure_dir,
+    plugins::{
+        release_acquisition::{
+            ReleaseAssetIndex, ReleaseAssetKind, ReleaseBackfillLimits, backfill_releases,
+            release_main_js_cache_path,
+        },
+        stats_helper::{HelperPluginStore, HelperRelease},
+    },
+    progress::should_log_progress,
+    security::validate_plugin_id,
+    state::{read_json_or_default, write_json_atomic},
+};
+
+/// Analyzes every cached `main.js` of the backfilled releases and writes one
+/// timeline per plugin to `PLUGIN_RELEASE_TIMELINE_PATH/{plugin_id}.json`.
+/// Releases whose cached bundle still has the content hash recorded in the
+/// release asset index keep their previous analysis; bundles without a hash
+/// are analyzed again.
+pub fn build_release_timelines(
+    plugins: &[PluginData],
+    limits: &ReleaseBackfillLimits,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let helper_store = HelperPluginStore::read()?;
+    let cutoff = limits.cutoff_date();
+    ensure_dir(Path::new(PLUGIN_RELEASE_TIMELINE_PATH))?;
+
+    let mut written = 0;
+    let mut analyzed = 0;
+    for (idx, plugin) in plugins.iter().enumerate() {
+        if should_log_progress(idx + 1, plugins.len()) {
+            println!(
+                "  Release timeline progress: {} / {}",
+                idx + 1,
+                plugins.len()
+            );
+        }
+        let Some(helper_plugin) = helper_store
+            .get(&plugin.id)
+            .filter(|helper_plugin| helper_plugin.repo == plugin.current_entry.repo)
+        else {
+            continue;
+        };
+        if validate_plugin_id(&plugin.id).is_err() {
+            continue;
+        }
+
+        let path = timeline_path(&plugin.id);
+        let previous: PluginReleaseTimeline = read_json_or_default(&path);
+        let asset_index = ReleaseAssetIndex::read(&plugin.id).unwrap_or_default();
+        let mut timeline = PluginReleaseTimeline {
+            id: plugin.id.clone(),
+            ..PluginReleaseTimeline::default()
+        };
+
+        for release in backfill_releases(helper_plugin, limits, &cutoff) {
+            let Ok(cache_path) = release_main_js_cache_path(&plugin.id, &release.tag) else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(&cache_path) else {
+                continue;
+            };
+
+            let sha256 = asset_index
+                .get(&release.tag, ReleaseAssetKind::MainJs)
+                .map(|entry| entry.sha256.clone());
+            if let Some(entry) = reusable_analysis(&previous, &release.tag, sha256.as_deref()) {
+                timeline.releases.push(entry.clone());
+            } else if metadata.len() <= MAX_MAIN_JS_ANALYSIS_BYTES
+                && let Ok(source) = fs::read_to_string(&cache_path)
+            {
+                timeline
+                    .releases
+                    .push(analyze_release(release, metadata.len(), sha256, &source));
+                analyzed += 1;
+            }
+        }
+
+        if !timeline.releases.is_empty() {
+            timeline.events = release_events(&timeline.releases);
+            write_json_atomic(&path, &timeline)?;
+            written += 1;
+        }
+    }
+
+    println!("Release timelines written: {written} ({analyzed} releases analyzed)");
+
+    Ok(())
+}
+
+/// Release events of the last timeline built for `plugin_id` that changed
+/// anything besides the bundle size.
+pub(super) fn read_release_events(plugin_id: &str) -> Vec<MainJsReleaseEvent> {
+    if validate_plugin_id(plugin_id).is_err() {
+        return Vec::new();
+    }
+    read_json_or_default::<PluginReleaseTimeline>(&timeline_path(plugin_id))
+        .events
+        .into_iter()
+        .filter(MainJsReleaseEvent::has_changes)
+        .collect()
+}
+
+/// The previous analysis of `tag`, if it was made from the same content.
+/// Without a hash a re-upload of the same size cannot be told apart.
+fn reusable_analysis<'a>(
+    previous: &'a PluginReleaseTimeline,
+    tag: &str,
+    sha256: Option<&str>,
+) -> Option<&'a MainJsReleaseAnalysis> {
+    let sha256 = sha256?;
+    previous
+        .releases
+        .iter()
+        .find(|entry| entry.tag == tag && entry.sha256.as_deref() == Some(sha256))
+}
+
+fn timeline_path(plugin_id: &str) -> PathBuf {
+    Path::new(PLUGIN_RELEASE_TIMELINE_PATH).join(format!("{plugin_id}.json"))
+}
+
+fn analyze_release(
+    release: &HelperRelease,
+    size_bytes: u64,
+    sha256: Option<String>,
+    source: &str,
+) -> MainJsReleaseAnalysis {
+    let result = analyze_main_js(source);
+
+    MainJsReleaseAnalysis {
+        tag: release.tag.clone(),
+        published_at: release.published_at.clone(),
+        size_bytes,
+        sha256,
+        estimated_target_es_version: result.estimated_target_es_version,
+        is_probably_minified: result.is_probably_minified,
+        minification_score: result.minification_score,
+        bundler_fingerprints: result.bundler_fingerprints,
+        api_capabilities: public_api_capabilities(&result.api_usage),
+        known_api_host_counts: result.known_api_host_counts.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::plugin::timeline::PluginReleaseTimeline;
+
+    use super::{analyze_release, reusable_analysis};
+    use crate::plugins::stats_helper::HelperRelease;
+
+    #[test]
+    fn records_release_metadata_and_signals() {
+        let release = HelperRelease {
+            tag: "1.0.0".to_string(),
+            published_at: Some("2024-01-01T00:00:00Z".to_string()),
+            download_count: None,
+            prerelease: false,
+            draft: false,
+        };
+        let source = r#"fetch("https://api.openai.com/v1/models");"#;
+
+        let entry = analyze_release(
+            &release,
+            source.len() as u64,
+            Some("abc".to_string()),
+            source,
+        );
+
+        assert_eq!(entry.tag, "1.0.0");
+        assert_eq!(entry.size_bytes, source.len() as u64);
+        assert_eq!(entry.sha256.as_deref(), Some("abc"));
+        assert_eq!(entry.known_api_host_counts.get("api.openai.com"), Some(&1));
+        assert!(
+            entry
+                .api_capabilities
+                .iter()
+                .any(|capability| capability.id == "network.browser")
+        );
+    }
+
+    #[test]
+    fn reuses_analysis_only_for_the_same_content_hash() {
+        let release = HelperRelease {
+            tag: "1.0.0".to_string(),
+            published_at: None,
+            download_count: None,
+            prerelease: false,
+            draft: false,
+        };
+        let previous = PluginReleaseTimeline {
+            id: "plugin".to_string(),
+            releases: vec![analyze_release(&release, 3, Some("aaa".to_string()), "1;2")],
+            events: Vec::new(),
+        };
+
+        assert!(reusable_analysis(&previous, "1.0.0", Some("aaa")).is_some());
+        assert!(reusable_analysis(&previous, "1.0.0", Some("bbb")).is_none());
+        assert!(reusable_analysis(&previous, "1.0.0", None).is_none());
+        assert!(reusable_analysis(&previous, "1.1.0", Some("aaa")).is_none());
+    }
+}
@@ -1,11 +1,17 @@
 use data_lib::plugin::{
-    LicenseInfo, PluginManifest, PluginRepoAnalysisError, PluginRepoData, bundlers::Bundler,
-    packages::PackageManager, testing::TestingFramework,
+    LicenseInfo, LocaleCoverage, PluginManifest, PluginRepoAnalysisError, PluginRepoData,
+    ResolvedDependency,
+    bundlers::Bundler,
+    ci::PluginCiData,
+    detectors::ToolDetection,
+    packages::PackageManager,
+    testing::{PluginTestData, TestingFramework},
+    tsconfig::TsConfigData,
 };
 use hashbrown::HashMap;
 use std::collections::BTreeMap;
 
-use super::mainjs::api_classifier::ApiClassificationResult;
+use super::mainjs::{SourceFingerprint, api_classifier::ApiClassificationResult};
 
 #[derive(Debug)]
 pub(super) struct RepoResult {
@@ -14,32 +20,51 @@ pub(super) struct RepoResult {
     pub(super) package_managers: Vec<PackageManager>,
     pub(super) testing_frameworks: Vec<TestingFramework>,
     pub(super) bundlers: Vec<Bundler>,
+    pub(super) tool_detections: Vec<ToolDetection>,
     pub(super) dependencies: Vec<String>,
     pub(super) dev_dependencies: Vec<String>,
+    pub(super) resolved_dependencies: Vec<ResolvedDependency>,
     pub(super) has_test_files: bool,
+    pub(super) tests: PluginTestData,
     pub(super) has_beta_manifest: bool,
+    pub(super) ci: PluginCiData,
+    pub(super) tsconfig: Option<TsConfigData>,
     pub(super) file_type_counts: HashMap<String, usize>,
     pub(super) package_json_license: LicenseInfo,
     pub(super) file_license: LicenseInfo,
     pub(super) manifest: Option<PluginManifest>,
     pub(super) lines_of_code: HashMap<String, usize>,
     pub(super) has_i18n_dependencies: bool,
+    pub(super) obsidian_typings_range: Option<String>,
     pub(super) has_i18n_files: bool,
+    pub(super) i18n_locales: Vec<LocaleCoverage>,
+    pub(super) i18n_base_locale: Option<String>,
+    pub(super) i18n_language_detection: Vec<String>,
+    pub(super) source_fingerprint: SourceFingerprint,
     pub(super) analysis_errors: Vec<PluginRepoAnalysisError>,
 }
 
 impl RepoResult {
-    pub(super) fn into_plugin_repo_data(self) -> PluginRepoData {
-        PluginRepoData {
+    /// Converts into the stored repo data, handing back the source fingerprint
+    /// that the release main.js is compared against later.
+    pub(super) fn into_plugin_repo_data(self) -> (PluginRepoData, SourceFingerprint) {
+        let repo_data = PluginRepoData {
             uses_typescript: self.uses_typescript,
             has_package_json: self.has_package_json,
             package_managers: self.package_managers,
             testing_frameworks: self.testing_frameworks,
             bundlers: self.bundlers,
+            tool_detections: self.tool_detections,
             dependencies: self.dependencies,
             dev_dependencies: self.dev_dependencies,
+            resolved_dependencies: self.resolved_dependencies,
+            dependency_advisories: Vec::new(),
+            obsidian_typings_range: self.obsidian_typings_range,
             has_test_files: self.has_test_files,
+            tests: self.tests,
             has_beta_manifest: self.has_beta_manifest,
+            ci: self.ci,
+            tsconfig: self.tsconfig,
             file_type_counts: self.file_type_counts,
             package_json_license: self.package_json_license,
             file_license: self.file_license,
@@ -47,6 +72,10 @@ impl RepoResult {
             lines_of_code: self.lines_of_code,
             has_i18n_dependencies: self.has_i18n_dependencies,
             has_i18n_files: self.has_i18n_files,
+            i18n_locales: self.i18n_locales,
+            i18n_base_locale: self.i18n_base_locale,
+            i18n_language_detection: self.i18n_language_detection,
+            repo_has_committed_main_js: self.source_fingerprint.committed_main_js.is_some(),
             latest_release_main_js_size_bytes: None,
             main_js_parse_succeeded: None,
             main_js_tolerant_parse_required: None,
@@ -68,11 +97,22 @@ impl RepoResult {
             main_js_credential_literal_count: None,
             main_js_api_capabilities: Vec::new(),
             main_js_api_disclosures: Vec::new(),
+            deprecated_api_usages: Vec::new(),
+            api_version_requirements: Vec::new(),
             latest_release_tag: None,
             latest_release_published_at: None,
             latest_release_fetch_status: None,
+            latest_release_styles_css_size_bytes: None,
+            release_manifest_mismatches: Vec::new(),
+            versions_json_issues: Vec::new(),
+            latest_release_main_js_sha256: None,
+            release_asset_reuploads: Vec::new(),
+            main_js_reproducibility_score: None,
+            main_js_reproducibility_mismatches: Vec::new(),
+            main_js_release_events: Vec::new(),
             analysis_errors: self.analysis_errors,
-        }
+        };
+        (repo_data, self.source_fingerprint)
     }
 }
 
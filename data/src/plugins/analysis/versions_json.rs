@@ -0,0 +1,214 @@
+//! Checks `versions.json`, which Obsidian reads to pick the newest plugin
+//! version an older app can install, against the published releases and the
+//! manifest.
+
+use data_lib::{
+    plugin::{
+        PluginManifest,
+        ci::{VersionsJsonEntry, VersionsJsonIssue, VersionsJsonIssueKind},
+    },
+    version::Version,
+};
+
+use crate::plugins::stats_helper::HelperRelease;
+
+/// Releases older than the first `versions.json` entry are not reported as
+/// missing, since most plugins only add the file once they raise
+/// `minAppVersion`. Releases newer than the analysed manifest version were
+/// published after the cloned tag, so they are not expected in its
+/// `versions.json` either; without a parsable manifest version the check is
+/// skipped.
+pub(super) fn check_versions_json(
+    entries: &[VersionsJsonEntry],
+    releases: &[HelperRelease],
+    manifest: Option<&PluginManifest>,
+) -> Vec<VersionsJsonIssue> {
+    let mut issues = Vec::new();
+    let issue = |kind, version: &str, detail: Option<&str>| VersionsJsonIssue {
+        kind,
+        version: version.to_string(),
+        detail: detail.map(str::to_string),
+    };
+
+    let mut parsed_entries = Vec::new();
+    for entry in entries {
+        match (
+            Version::parse(&entry.version),
+            Version::parse(&entry.min_app_version),
+        ) {
+            (Some(version), Some(min_app_version)) => {
+                parsed_entries.push((entry, version, min_app_version));
+            }
+            (None, _) => {
+                issues.push(issue(
+                    VersionsJsonIssueKind::UnparsableVersion,
+                    &entry.version,
+                    None,
+                ));
+            }
+            (Some(_), None) => issues.push(issue(
+                VersionsJsonIssueKind::UnparsableVersion,
+                &entry.version,
+                Some(&entry.min_app_version),
+            )),
+        }
+    }
+
+    // Entries are sorted by version, so the minimum app version must not
+    // drop below any earlier one.
+    let mut highest_min_app_version: Option<&Version> = None;
+    for (entry, _, min_app_version) in &parsed_entries {
+        if highest_min_app_version.is_some_and(|highest| min_app_version < highest) {
+            issues.push(issue(
+                VersionsJsonIssueKind::NonMonotoneMinAppVersion,
+                &entry.version,
+                Some(&entry.min_app_version),
+            ));
+        } else {
+            highest_min_app_version = Some(min_app_version);
+        }
+    }
+
+    let manifest_version = manifest
+        .and_then(|manifest| manifest.version.as_deref())
+        .and_then(Version::parse);
+    if let Some((_, oldest, _)) = parsed_entries.first()
+        && let Some(newest) = &manifest_version
+    {
+        for release in releases {
+            if release.draft || release.prerelease {
+                continue;
+            }
+            let tag = release.tag.strip_prefix('v').unwrap_or(&release.tag);
+            let Some(version) = Version::parse(tag) else {
+                continue;
+            };
+            let listed = parsed_entries
+                .iter()
+                .any(|(_, entry_version, _)| *entry_version == version);
+            if &version >= oldest && &version <= newest && !listed {
+                issues.push(issue(VersionsJsonIssueKind::MissingRelease, tag, None));
+            }
+        }
+    }
+
+    if let Some(manifest) = manifest
+        && let (Some(version), Some(min_app_version)) = (
+            manifest.version.as_deref(),
+            manifest.min_app_version.as_deref(),
+        )
+        && let Some(entry) = entries.iter().find(|entry| entry.version == version)
+        && entry.min_app_version != min_app_version
+        && Version::parse(&entry.min_app_version) != Version::parse(min_app_version)
+    {
+        issues.push(issue(
+            VersionsJsonIssueKind::ManifestMinAppVersionMismatch,
+            version,
+            Some(&entry.min_app_version),
+        ));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use data_lib::plugin::{
+        PluginManifest,
+        ci::{VersionsJsonEntry, VersionsJsonIssueKind},
+    };
+
+    use super::check_versions_json;
+    use crate::plugins::stats_helper::HelperRelease;
+
+    fn entry(version: &str, min_app_version: &str) -> VersionsJsonEntry {
+        VersionsJsonEntry {
+            version: version.to_string(),
+            min_app_version: min_app_version.to_string(),
+        }
+    }
+
+    fn release(tag: &str, prerelease: bool) -> HelperRelease {
+        HelperRelease {
+            tag: tag.to_string(),
+            published_at: None,
+            download_count: None,
+            prerelease,
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn reports_missing_releases_decreasing_versions_and_manifest_mismatch() {
+        let entries = [
+            entry("1.1.0", "0.15.0"),
+            entry("1.2.0", "1.4.0"),
+            entry("1.3.0", "1.0.0"),
+            entry("1.5.0", "1.4.0"),
+            entry("next", "1.4.0"),
+        ];
+        let releases = [
+            release("1.0.0", false),
+            release("v1.3.0", false),
+            release("1.4.0", false),
+            release("1.6.0-beta.1", true),
+            release("1.7.0", false),
+        ];
+        let manifest = PluginManifest {
+            version: Some("1.5.0".to_string()),
+            min_app_version: Some("1.5.0".to_string()),
+            ..PluginManifest::default()
+        };
+
+        let issues = check_versions_json(&entries, &releases, Some(&manifest));
+        let issues = issues
+            .iter()
+            .map(|issue| (issue.kind, issue.version.as_str(), issue.detail.as_deref()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            issues,
+            [
+                (VersionsJsonIssueKind::UnparsableVersion, "next", None),
+                (
+                    VersionsJsonIssueKind::NonMonotoneMinAppVersion,
+                    "1.3.0",
+                    Some("1.0.0")
+                ),
+                (VersionsJsonIssueKind::MissingRelease, "1.4.0", None),
+                (
+                    VersionsJsonIssueKind::ManifestMinAppVersionMismatch,
+                    "1.5.0",
+                    Some("1.4.0")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_consistent_versions_json() {
+        let entries = [entry("1.0.0", "0.15.0"), entry("1.1.0", "0.15.0")];
+        let releases = [release("1.0.0", false), release("1.1.0", false)];
+        let manifest = PluginManifest {
+            version: Some("1.1.0".to_string()),
+            min_app_version: Some("0.15.0".to_string()),
+            ..PluginManifest::default()
+        };
+
+        assert!(check_versions_json(&entries, &releases, Some(&manifest)).is_empty());
+    }
+
+    #[test]
+    fn ignores_releases_newer_than_the_manifest() {
+        let entries = [entry("1.0.0", "0.15.0")];
+        let releases = [release("1.0.0", false), release("1.1.0", false)];
+        let manifest = PluginManifest {
+            version: Some("1.0.0".to_string()),
+            min_app_version: Some("0.15.0".to_string()),
+            ..PluginManifest::default()
+        };
+
+        assert!(check_versions_json(&entries, &releases, Some(&manifest)).is_empty());
+        assert!(check_versions_json(&entries, &releases, None).is_empty());
+    }
+}
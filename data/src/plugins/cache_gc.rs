@@ -0,0 +1,615 @@
+//! Garbage collection for the repository clones and the release asset cache.
+//! Entries of plugins that are gone from the plugin list (or were removed from
+//! it) are deleted, including quarantined downloads. Release assets and
+//! quarantined downloads are only kept for the current target tag, the last
+//! tag with a `main.js`, and the releases within the backfill limits. Blobs
+//! that no index references anymore are deleted afterwards.
+//!
+//! If `CACHE_QUOTA_MB` is set, whole entries are evicted afterwards, least
+//! recently analysed plugin first, until the caches fit. Evicted clones and
+//! release assets are fetched again by the next pipeline run.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use data_lib::plugin::PluginData;
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        CACHE_ACCESS_STATE_PATH, PLUGIN_RELEASE_ASSET_BLOB_PATH,
+        PLUGIN_RELEASE_ENRICHMENT_STATE_PATH, PLUGIN_RELEASE_MAIN_JS_PATH,
+        PLUGIN_RELEASE_QUARANTINE_PATH, PLUGIN_REPO_PATH,
+    },
+    plugins::{
+        release_acquisition::{
+            PluginReleaseState, ReleaseAssetIndex, ReleaseAssetKind, ReleaseBackfillLimits,
+            backfill_releases, legacy_release_asset_path, prune_release_assets,
+            quarantined_release_asset_path, release_asset_blob_path, release_asset_index_path,
+        },
+        stats_helper::HelperPluginStore,
+    },
+    security::{validate_plugin_id, validated_plugin_path},
+    state::{now_unix_seconds, read_json_or_default, write_json_atomic},
+};
+
+const CACHE_QUOTA_MB_ENV: &str = "CACHE_QUOTA_MB";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct CacheAccessState {
+    #[serde(default)]
+    last_analyzed_unix: HashMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CacheEntryKind {
+    ReleaseAssets,
+    Repo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheEntry {
+    plugin_id: String,
+    kind: CacheEntryKind,
+    last_analyzed_unix: i64,
+    /// Bytes that deleting the entry frees. Blobs shared with other plugins
+    /// are not counted.
+    size_bytes: u64,
+}
+
+#[derive(Debug, Default)]
+struct GcReport {
+    removed_plugins: usize,
+    removed_tags: usize,
+    removed_blobs: usize,
+    evicted_entries: usize,
+    reclaimed_bytes: u64,
+}
+
+/// Which plugins reference each blob of the release asset store.
+#[derive(Debug, Default)]
+struct BlobReferences {
+    referrers: HashMap<String, HashSet<String>>,
+    by_plugin: HashMap<String, HashSet<String>>,
+}
+
+impl BlobReferences {
+    fn read(plugin_ids: &HashSet<&str>) -> Result<Self, String> {
+        let mut references = Self::default();
+        for plugin_id in plugin_ids {
+            for entry in ReleaseAssetIndex::read(plugin_id)?.assets {
+                references
+                    .referrers
+                    .entry(entry.sha256.clone())
+                    .or_default()
+                    .insert(plugin_id.to_string());
+                references
+                    .by_plugin
+                    .entry(plugin_id.to_string())
+                    .or_default()
+                    .insert(entry.sha256);
+            }
+        }
+        Ok(references)
+    }
+
+    /// Blobs that only `plugin_id` references.
+    fn exclusive_blobs(&self, plugin_id: &str) -> Vec<&str> {
+        self.by_plugin
+            .get(plugin_id)
+            .map_or_else(Vec::new, |blobs| {
+                blobs
+                    .iter()
+                    .filter(|sha256| {
+                        self.referrers
+                            .get(*sha256)
+                            .is_some_and(|ids| ids.len() == 1)
+                    })
+                    .map(String::as_str)
+                    .collect()
+            })
+    }
+
+    /// Forgets the references of `plugin_id` and returns the blobs that are no
+    /// longer referenced at all.
+    fn release_plugin(&mut self, plugin_id: &str) -> Vec<String> {
+        let mut unreferenced = Vec::new();
+        for sha256 in self.by_plugin.remove(plugin_id).unwrap_or_default() {
+            if let Some(referrers) = self.referrers.get_mut(&sha256) {
+                referrers.remove(plugin_id);
+                if referrers.is_empty() {
+                    self.referrers.remove(&sha256);
+                    unreferenced.push(sha256);
+                }
+            }
+        }
+        unreferenced
+    }
+}
+
+/// Remembers that the caches of `plugin_ids` were used by the analysis, so
+/// that the quota evicts other plugins first.
+pub fn record_analyzed_plugins<'a>(
+    plugin_ids: impl IntoIterator<Item = &'a str>,
+) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(CACHE_ACCESS_STATE_PATH);
+    let mut state: CacheAccessState = read_json_or_default(path);
+    let now = now_unix_seconds();
+    for plugin_id in plugin_ids {
+        state.last_analyzed_unix.insert(plugin_id.to_string(), now);
+    }
+    write_json_atomic(path, &state)
+}
+
+pub fn collect_cache_garbage(plugins: &[PluginData]) -> Result<(), Box<dyn Error>> {
+    let active_ids = plugins
+        .iter()
+        .filter(|plugin| plugin.removed_commit.is_none())
+        .map(|plugin| plugin.id.as_str())
+        .collect::<HashSet<_>>();
+    let kept_tags = kept_release_tags(plugins)?;
+    let mut report = GcReport::default();
+
+    for root in [
+        PLUGIN_REPO_PATH,
+        PLUGIN_RELEASE_MAIN_JS_PATH,
+        PLUGIN_RELEASE_QUARANTINE_PATH,
+    ] {
+        remove_inactive_plugin_dirs(Path::new(root), &active_ids, &mut report)?;
+    }
+    for plugin_id in &active_ids {
+        let no_tags = HashSet::new();
+        let keep = kept_tags.get(*plugin_id).unwrap_or(&no_tags);
+        remove_stale_release_tags(plugin_id, keep, &mut report)?;
+        remove_stale_quarantined_assets(plugin_id, keep, &mut report)?;
+    }
+
+    let mut references = BlobReferences::read(&active_ids)?;
+    remove_unreferenced_blobs(&references, &mut report)?;
+
+    if let Some(quota_bytes) = quota_bytes_from_env() {
+        enforce_quota(&active_ids, &mut references, quota_bytes, &mut report)?;
+    }
+
+    println!(
+        "Cache GC: removed_plugins={}, removed_tags={}, removed_blobs={}, evicted={}, reclaimed_bytes={}",
+        report.removed_plugins,
+        report.removed_tags,
+        report.removed_blobs,
+        report.evicted_entries,
+        report.reclaimed_bytes
+    );
+
+    Ok(())
+}
+
+fn quota_bytes_from_env() -> Option<u64> {
+    parse_quota_mb(&std::env::var(CACHE_QUOTA_MB_ENV).ok()?)
+}
+
+/// Unset, zero and unparsable quotas disable eviction.
+fn parse_quota_mb(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb.saturating_mul(1024 * 1024))
+}
+
+/// Release tags per plugin whose assets are still used by the analysis or the
+/// release timelines.
+fn kept_release_tags(
+    plugins: &[PluginData],
+) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+    let release_state: PluginReleaseState =
+        read_json_or_default(Path::new(PLUGIN_RELEASE_ENRICHMENT_STATE_PATH));
+    let helper_store = HelperPluginStore::read()?;
+    let limits = ReleaseBackfillLimits::from_env();
+    let cutoff = limits.cutoff_date();
+
+    let mut kept = HashMap::<String, HashSet<String>>::new();
+    for plugin in plugins {
+        let tags = kept.entry(plugin.id.clone()).or_default();
+        if let Some(entry) = release_state.entries.get(&plugin.id) {
+            tags.extend(entry.latest_release_tag.iter().cloned());
+            tags.extend(entry.last_successful_main_js_release_tag.iter().cloned());
+        }
+        if let Some(helper_plugin) = helper_store
+            .get(&plugin.id)
+            .filter(|helper_plugin| helper_plugin.repo == plugin.current_entry.repo)
+        {
+            tags.extend(
+                backfill_releases(helper_plugin, &limits, &cutoff)
+                    .into_iter()
+                    .map(|release| release.tag.clone()),
+            );
+        }
+    }
+    Ok(kept)
+}
+
+/// Deletes `root/{plugin_id}` of every plugin that is not active. Dot entries
+/// are staging directories of clones and downloads and are left alone.
+fn remove_inactive_plugin_dirs(
+    root: &Path,
+    active_ids: &HashSet<&str>,
+    report: &mut GcReport,
+) -> Result<(), Box<dyn Error>> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(());
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name.starts_with('.') || active_ids.contains(name.as_str()) {
+            continue;
+        }
+
+        report.reclaimed_bytes += remove_path(&entry.path())?;
+        report.removed_plugins += 1;
+    }
+    Ok(())
+}
+
+fn remove_stale_release_tags(
+    plugin_id: &str,
+    keep: &HashSet<String>,
+    report: &mut GcReport,
+) -> Result<(), Box<dyn Error>> {
+    let Ok(plugin_dir) = validated_plugin_path(Path::new(PLUGIN_RELEASE_MAIN_JS_PATH), plugin_id)
+    else {
+        return Ok(());
+    };
+    if !plugin_dir.is_dir() {
+        return Ok(());
+    }
+
+    let removed = prune_release_assets(plugin_id, |entry| keep.contains(&entry.tag))?;
+    report.removed_tags += removed
+        .iter()
+        .map(|entry| entry.tag.as_str())
+        .collect::<HashSet<_>>()
+        .len();
+
+    let mut kept_files = HashSet::new();
+    for tag in keep {
+        for kind in [
+            ReleaseAssetKind::MainJs,
+            ReleaseAssetKind::ManifestJson,
+            ReleaseAssetKind::StylesCss,
+        ] {
+            kept_files.insert(legacy_release_asset_path(plugin_id, tag, kind)?);
+        }
+    }
+    let index_path = release_asset_index_path(plugin_id)?;
+    for entry in fs::read_dir(&plugin_dir)? {
+        let path = entry?.path();
+        let is_staging = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        if is_staging || path == index_path || kept_files.contains(&path) {
+            continue;
+        }
+        report.reclaimed_bytes += remove_path(&path)?;
+    }
+    Ok(())
+}
+
+fn remove_stale_quarantined_assets(
+    plugin_id: &str,
+    keep: &HashSet<String>,
+    report: &mut GcReport,
+) -> Result<(), Box<dyn Error>> {
+    let Ok(quarantine_dir) =
+        validated_plugin_path(Path::new(PLUGIN_RELEASE_QUARANTINE_PATH), plugin_id)
+    else {
+        return Ok(());
+    };
+    if !quarantine_dir.is_dir() {
+        return Ok(());
+    }
+
+    let mut kept_files = HashSet::new();
+    for tag in keep {
+        for kind in [
+            ReleaseAssetKind::MainJs,
+            ReleaseAssetKind::ManifestJson,
+            ReleaseAssetKind::StylesCss,
+        ] {
+            kept_files.insert(quarantined_release_asset_path(plugin_id, tag, kind)?);
+        }
+    }
+    for entry in fs::read_dir(&quarantine_dir)? {
+        let path = entry?.path();
+        if !kept_files.contains(&path) {
+            report.reclaimed_bytes += remove_path(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_unreferenced_blobs(
+    references: &BlobReferences,
+    report: &mut GcReport,
+) -> Result<(), Box<dyn Error>> {
+    for blob in stored_blobs()? {
+        let referenced = blob
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|sha256| references.referrers.contains_key(sha256));
+        if !referenced {
+            report.reclaimed_bytes += remove_path(&blob)?;
+            report.removed_blobs += 1;
+        }
+    }
+    Ok(())
+}
+
+fn stored_blobs() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let Ok(prefixes) = fs::read_dir(Path::new(PLUGIN_RELEASE_ASSET_BLOB_PATH).join("sha256"))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut blobs = Vec::new();
+    for prefix in prefixes {
+        let prefix = prefix?.path();
+        if !prefix.is_dir() {
+            continue;
+        }
+        for blob in fs::read_dir(prefix)? {
+            blobs.push(blob?.path());
+        }
+    }
+    Ok(blobs)
+}
+
+fn enforce_quota(
+    active_ids: &HashSet<&str>,
+    references: &mut BlobReferences,
+    quota_bytes: u64,
+    report: &mut GcReport,
+) -> Result<(), Box<dyn Error>> {
+    let total_bytes = [
+        PLUGIN_REPO_PATH,
+        PLUGIN_RELEASE_MAIN_JS_PATH,
+        PLUGIN_RELEASE_ASSET_BLOB_PATH,
+    ]
+    .into_iter()
+    .map(|root| dir_size(Path::new(root)))
+    .sum::<u64>();
+    if total_bytes <= quota_bytes {
+        return Ok(());
+    }
+
+    let access_state: CacheAccessState = read_json_or_default(Path::new(CACHE_ACCESS_STATE_PATH));
+    let mut candidates = Vec::new();
+    for plugin_id in active_ids {
+        if validate_plugin_id(plugin_id).is_err() {
+            continue;
+        }
+        let last_analyzed_unix = access_state
+            .last_analyzed_unix
+            .get(*plugin_id)
+            .copied()
+            .unwrap_or(0);
+
+        let repo_size = dir_size(&Path::new(PLUGIN_REPO_PATH).join(plugin_id));
+        if repo_size > 0 {
+            candidates.push(CacheEntry {
+                plugin_id: plugin_id.to_string(),
+                kind: CacheEntryKind::Repo,
+                last_analyzed_unix,
+                size_bytes: repo_size,
+            });
+        }
+
+        let release_size = dir_size(&Path::new(PLUGIN_RELEASE_MAIN_JS_PATH).join(plugin_id))
+            + references
+                .exclusive_blobs(plugin_id)
+                .into_iter()
+                .map(|sha256| file_size(&release_asset_blob_path(sha256)))
+                .sum::<u64>();
+        if release_size > 0 {
+            candidates.push(CacheEntry {
+                plugin_id: plugin_id.to_string(),
+                kind: CacheEntryKind::ReleaseAssets,
+                last_analyzed_unix,
+                size_bytes: release_size,
+            });
+        }
+    }
+
+    let evictions = select_evictions(candidates, total_bytes, quota_bytes);
+    if evictions.is_empty() {
+        return Ok(());
+    }
+
+    let state_path = Path::new(PLUGIN_RELEASE_ENRICHMENT_STATE_PATH);
+    let mut release_state: PluginReleaseState = read_json_or_default(state_path);
+    for entry in &evictions {
+        match entry.kind {
+            CacheEntryKind::Repo => {
+                report.reclaimed_bytes +=
+                    remove_path(&Path::new(PLUGIN_REPO_PATH).join(&entry.plugin_id))?;
+            }
+            CacheEntryKind::ReleaseAssets => {
+                report.reclaimed_bytes +=
+                    remove_path(&Path::new(PLUGIN_RELEASE_MAIN_JS_PATH).join(&entry.plugin_id))?;
+                for sha256 in references.release_plugin(&entry.plugin_id) {
+                    report.reclaimed_bytes += remove_path(&release_asset_blob_path(&sha256))?;
+                    report.removed_blobs += 1;
+                }
+                // Without its state entry the next run downloads the assets
+                // again instead of treating the release as current.
+                release_state.entries.remove(&entry.plugin_id);
+            }
+        }
+        report.evicted_entries += 1;
+    }
+    write_json_atomic(state_path, &release_state)?;
+
+    println!(
+        "Cache quota of {} MB exceeded by {} bytes; evicted {} entries",
+        quota_bytes / (1024 * 1024),
+        total_bytes - quota_bytes,
+        evictions.len()
+    );
+    Ok(())
+}
+
+/// Picks entries to delete, least recently analysed first, until the caches
+/// fit into `quota_bytes`. Repos go before release assets of the same plugin
+/// because they are cheaper to fetch again than a rate limited download.
+fn select_evictions(
+    mut candidates: Vec<CacheEntry>,
+    total_bytes: u64,
+    quota_bytes: u64,
+) -> Vec<CacheEntry> {
+    candidates.sort_by(|left, right| {
+        left.last_analyzed_unix
+            .cmp(&right.last_analyzed_unix)
+            .then_with(|| right.kind.cmp(&left.kind))
+            .then_with(|| left.plugin_id.cmp(&right.plugin_id))
+    });
+
+    let mut remaining = total_bytes;
+    candidates
+        .into_iter()
+        .take_while(|entry| {
+            let evict = remaining > quota_bytes;
+            remaining = remaining.saturating_sub(entry.size_bytes);
+            evict
+        })
+        .collect()
+}
+
+/// Removes a file or directory and returns the number of bytes it occupied.
+fn remove_path(path: &Path) -> Result<u64, Box<dyn Error>> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(0);
+    };
+    if metadata.is_dir() {
+        let size = dir_size(path);
+        fs::remove_dir_all(path)?;
+        Ok(size)
+    } else {
+        fs::remove_file(path)?;
+        Ok(metadata.len())
+    }
+}
+
+/// Total size of the files below `path`. Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BlobReferences, CacheEntry, CacheEntryKind, dir_size, parse_quota_mb, remove_path,
+        select_evictions,
+    };
+
+    fn entry(plugin_id: &str, kind: CacheEntryKind, last_analyzed_unix: i64) -> CacheEntry {
+        CacheEntry {
+            plugin_id: plugin_id.to_string(),
+            kind,
+            last_analyzed_unix,
+            size_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn parses_quota_in_megabytes() {
+        assert_eq!(parse_quota_mb("2"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_quota_mb("0"), None);
+        assert_eq!(parse_quota_mb("lots"), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_analysed_entries_until_within_quota() {
+        let candidates = vec![
+            entry("recent", CacheEntryKind::Repo, 300),
+            entry("old", CacheEntryKind::ReleaseAssets, 100),
+            entry("old", CacheEntryKind::Repo, 100),
+            entry("middle", CacheEntryKind::Repo, 200),
+        ];
+
+        let evicted = select_evictions(candidates.clone(), 400, 250)
+            .into_iter()
+            .map(|entry| (entry.plugin_id, entry.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            evicted,
+            vec![
+                ("old".to_string(), CacheEntryKind::Repo),
+                ("old".to_string(), CacheEntryKind::ReleaseAssets),
+            ]
+        );
+        assert!(select_evictions(candidates, 400, 400).is_empty());
+    }
+
+    #[test]
+    fn shared_blobs_survive_until_the_last_plugin_releases_them() {
+        let mut references = BlobReferences::default();
+        for (plugin_id, sha256) in [("a", "shared"), ("b", "shared"), ("a", "own")] {
+            references
+                .referrers
+                .entry(sha256.to_string())
+                .or_default()
+                .insert(plugin_id.to_string());
+            references
+                .by_plugin
+                .entry(plugin_id.to_string())
+                .or_default()
+                .insert(sha256.to_string());
+        }
+
+        assert_eq!(references.exclusive_blobs("a"), vec!["own"]);
+        assert_eq!(references.release_plugin("a"), vec!["own".to_string()]);
+        assert_eq!(references.exclusive_blobs("b"), vec!["shared"]);
+        assert_eq!(references.release_plugin("b"), vec!["shared".to_string()]);
+        assert!(references.referrers.is_empty());
+        assert!(references.by_plugin.is_empty());
+    }
+
+    #[test]
+    fn removing_a_directory_reports_its_size() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-cache-gc-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("a.txt"), b"12345").unwrap();
+        std::fs::write(root.join("nested").join("b.txt"), b"123").unwrap();
+
+        assert_eq!(dir_size(&root), 8);
+        assert_eq!(remove_path(&root).unwrap(), 8);
+        assert!(!root.exists());
+        assert_eq!(remove_path(&root).unwrap(), 0);
+    }
+}
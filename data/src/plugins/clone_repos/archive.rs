@@ -0,0 +1,233 @@
+//! Optional archive of cloned repositories.
+//!
+//! Every archived clone becomes a ref `refs/snapshots/{plugin_id}/{tag}` in a
+//! single bare repository. Git stores objects zlib-compressed and addressed by
+//! their content hash, so files that are identical across releases or plugins
+//! are only stored once.
+
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use super::git_clone::{CloneError, git_command, run_clone_command};
+use crate::{constants::PLUGIN_REPO_ARCHIVE_PATH, security::validate_plugin_id};
+
+pub(super) fn archive_snapshot(
+    plugin_id: &str,
+    release_tag: &str,
+    repo_path: &Path,
+    timeout: Duration,
+) -> Result<(), CloneError> {
+    archive_snapshot_in(
+        Path::new(PLUGIN_REPO_ARCHIVE_PATH),
+        plugin_id,
+        release_tag,
+        repo_path,
+        timeout,
+    )
+}
+
+fn archive_snapshot_in(
+    archive_path: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    repo_path: &Path,
+    timeout: Duration,
+) -> Result<(), CloneError> {
+    let snapshot_ref = snapshot_ref(plugin_id, release_tag).map_err(CloneError::GitFailed)?;
+    ensure_archive_repo(archive_path, timeout)?;
+    let repo_path = repo_path
+        .canonicalize()
+        .map_err(|error| CloneError::GitFailed(format!("failed to resolve clone path: {error}")))?;
+
+    // Clones are shallow, so the archive has to accept the shallow boundary.
+    let mut fetch = git_command();
+    fetch
+        .arg("--git-dir")
+        .arg(archive_path)
+        .args(["fetch", "--quiet", "--no-tags", "--update-shallow"])
+        .arg(&repo_path)
+        .arg(format!("+HEAD:{snapshot_ref}"));
+    run_clone_command(&mut fetch, timeout)
+}
+
+/// Repacks loose objects once enough snapshots have accumulated.
+pub(super) fn pack_archive(timeout: Duration) -> Result<(), CloneError> {
+    let mut gc = git_command();
+    gc.arg("--git-dir")
+        .arg(PLUGIN_REPO_ARCHIVE_PATH)
+        .args(["gc", "--auto", "--quiet"]);
+    run_clone_command(&mut gc, timeout)
+}
+
+/// Writes the archived tree of `(plugin_id, release_tag)` into `destination`,
+/// creating the directory if needed.
+pub fn materialize_snapshot(
+    plugin_id: &str,
+    release_tag: &str,
+    destination: &Path,
+) -> Result<(), String> {
+    materialize_snapshot_from(
+        Path::new(PLUGIN_REPO_ARCHIVE_PATH),
+        plugin_id,
+        release_tag,
+        destination,
+    )
+}
+
+fn materialize_snapshot_from(
+    archive_path: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    destination: &Path,
+) -> Result<(), String> {
+    let snapshot_ref = snapshot_ref(plugin_id, release_tag)?;
+    if !archive_path.is_dir() {
+        return Err(format!(
+            "snapshot archive does not exist: {}",
+            archive_path.display()
+        ));
+    }
+    std::fs::create_dir_all(destination)
+        .map_err(|error| format!("failed to create snapshot directory: {error}"))?;
+
+    let mut archive = Command::new("git")
+        .arg("--git-dir")
+        .arg(archive_path)
+        .args(["archive", "--format=tar", &snapshot_ref])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to start git archive: {error}"))?;
+    let archive_stdout = archive
+        .stdout
+        .take()
+        .ok_or_else(|| "git archive stdout was not piped".to_string())?;
+    let extract = Command::new("tar")
+        .arg("-x")
+        .arg("-C")
+        .arg(destination)
+        .stdin(archive_stdout)
+        .output()
+        .map_err(|error| format!("failed to run tar: {error}"))?;
+    let archive_output = archive
+        .wait_with_output()
+        .map_err(|error| format!("failed while waiting for git archive: {error}"))?;
+
+    if !archive_output.status.success() {
+        return Err(format!(
+            "snapshot {snapshot_ref} could not be read: {}",
+            String::from_utf8_lossy(&archive_output.stderr).trim()
+        ));
+    }
+    if !extract.status.success() {
+        return Err(format!(
+            "failed to extract snapshot {snapshot_ref}: {}",
+            String::from_utf8_lossy(&extract.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+fn ensure_archive_repo(archive_path: &Path, timeout: Duration) -> Result<(), CloneError> {
+    if archive_path.join("HEAD").is_file() {
+        return Ok(());
+    }
+
+    let mut init = git_command();
+    init.args(["init", "--bare", "--quiet"]).arg(archive_path);
+    run_clone_command(&mut init, timeout)
+}
+
+fn snapshot_ref(plugin_id: &str, release_tag: &str) -> Result<String, String> {
+    validate_plugin_id(plugin_id)?;
+    if release_tag.is_empty()
+        || release_tag.starts_with(['-', '/', '.'])
+        || release_tag.ends_with(['/', '.'])
+        || release_tag.contains("..")
+        || release_tag.contains("//")
+        || release_tag.contains("@{")
+        || release_tag.ends_with(".lock")
+        || release_tag
+            .chars()
+            .any(|ch| ch.is_ascii_control() || " ~^:?*[\\".contains(ch))
+    {
+        return Err(format!(
+            "release tag is not a safe ref name: {release_tag:?}"
+        ));
+    }
+
+    Ok(format!("refs/snapshots/{plugin_id}/{release_tag}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        super::git_clone::{git_command, run_clone_command},
+        archive_snapshot_in, materialize_snapshot_from, snapshot_ref,
+    };
+
+    #[test]
+    fn snapshot_refs_are_keyed_by_plugin_and_tag() {
+        assert_eq!(
+            snapshot_ref("calendar", "v1.2.0").unwrap(),
+            "refs/snapshots/calendar/v1.2.0"
+        );
+        assert!(snapshot_ref("../calendar", "1.0.0").is_err());
+        assert!(snapshot_ref("calendar", "../1.0.0").is_err());
+        assert!(snapshot_ref("calendar", "1.0.0:evil").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn archived_shallow_clone_can_be_materialized() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-archive-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let clone = root.join("clone");
+        let archive = root.join("archive.git");
+        let restored = root.join("restored");
+        std::fs::create_dir_all(&source).unwrap();
+        let git = |args: &[&str]| {
+            let mut command = git_command();
+            command
+                .arg("-C")
+                .arg(&source)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args);
+            run_clone_command(&mut command, Duration::from_secs(30)).unwrap();
+        };
+        git(&["init", "--quiet"]);
+        std::fs::write(source.join("main.ts"), "first").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "first"]);
+        std::fs::write(source.join("main.ts"), "second").unwrap();
+        git(&["commit", "--quiet", "-am", "second"]);
+        git(&["tag", "1.0.0"]);
+
+        let mut clone_command = git_command();
+        clone_command
+            .args(["clone", "--depth", "1", "--branch", "1.0.0", "--quiet"])
+            .arg(format!("file://{}", source.display()))
+            .arg(&clone);
+        run_clone_command(&mut clone_command, Duration::from_secs(30)).unwrap();
+
+        let archived =
+            archive_snapshot_in(&archive, "plugin", "1.0.0", &clone, Duration::from_secs(30));
+        let materialized = materialize_snapshot_from(&archive, "plugin", "1.0.0", &restored);
+        let content = std::fs::read_to_string(restored.join("main.ts"));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(archived.is_ok(), "{archived:?}");
+        assert!(materialized.is_ok(), "{materialized:?}");
+        assert_eq!(content.unwrap(), "second");
+    }
+}
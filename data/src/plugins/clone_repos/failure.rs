@@ -0,0 +1,177 @@
+/// Coarse reason a clone failed, derived from the error kind and git's stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum CloneFailureCategory {
+    /// The repository was deleted or renamed (GitHub answers 404).
+    RepoGone,
+    /// The repository exists but the target release tag does not.
+    TagMissing,
+    /// The remote asked for credentials, usually because the repo is private.
+    AuthRequired,
+    /// Git LFS objects or oversized pushes that the server refused to send.
+    LfsOrSize,
+    /// DNS, TLS or connection problems.
+    Network,
+    Timeout,
+    /// The plugin id or repo slug failed validation.
+    Invalid,
+    /// Local process or filesystem problems, including a local mirror that
+    /// lacks the repo.
+    Local,
+    Unknown,
+}
+
+impl CloneFailureCategory {
+    pub(super) fn as_state_value(self) -> &'static str {
+        match self {
+            Self::RepoGone => "repo_gone",
+            Self::TagMissing => "tag_missing",
+            Self::AuthRequired => "auth_required",
+            Self::LfsOrSize => "lfs_or_size",
+            Self::Network => "network",
+            Self::Timeout => "timeout",
+            Self::Invalid => "invalid",
+            Self::Local => "local",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    pub(super) fn from_state_value(value: &str) -> Self {
+        match value {
+            "repo_gone" => Self::RepoGone,
+            "tag_missing" => Self::TagMissing,
+            "auth_required" => Self::AuthRequired,
+            "lfs_or_size" => Self::LfsOrSize,
+            "network" => Self::Network,
+            "timeout" => Self::Timeout,
+            "invalid" => Self::Invalid,
+            "local" => Self::Local,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub(super) fn from_git_stderr(stderr: &str) -> Self {
+        let stderr = stderr.to_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|needle| stderr.contains(needle));
+
+        if contains_any(&[
+            "repository not found",
+            "returned error: 404",
+            "does not appear to be a git repository",
+        ]) {
+            Self::RepoGone
+        } else if contains_any(&["not found in upstream", "couldn't find remote ref"]) {
+            Self::TagMissing
+        } else if contains_any(&[
+            "could not read username",
+            "could not read password",
+            "authentication failed",
+            "terminal prompts disabled",
+            "returned error: 401",
+            "returned error: 403",
+        ]) {
+            Self::AuthRequired
+        } else if contains_any(&[
+            "git-lfs",
+            "http 413",
+            "this exceeds github's file size limit",
+        ]) {
+            Self::LfsOrSize
+        } else if contains_any(&[
+            "could not resolve host",
+            "failed to connect",
+            "connection timed out",
+            "connection reset",
+            "connection refused",
+            "unable to access",
+            "rpc failed",
+            "early eof",
+            "remote end hung up",
+            "ssl",
+            "gnutls",
+            "returned error: 5",
+        ]) {
+            Self::Network
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Transient failures get one more attempt within the same run.
+    pub(super) fn is_retryable_in_run(self) -> bool {
+        matches!(self, Self::Network | Self::Timeout)
+    }
+
+    /// Days to wait before cloning the same repo and tag again. Failures that
+    /// rarely fix themselves back off longer; a new target tag always triggers
+    /// a fresh attempt.
+    pub(super) fn backoff_days(self) -> i64 {
+        match self {
+            Self::RepoGone | Self::AuthRequired | Self::Invalid => 14,
+            Self::LfsOrSize => 7,
+            Self::TagMissing => 3,
+            Self::Timeout | Self::Unknown => 1,
+            Self::Network | Self::Local => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CloneFailureCategory;
+
+    #[test]
+    fn classifies_common_git_failures() {
+        let cases = [
+            (
+                "remote: Repository not found.\nfatal: repository 'https://github.com/a/b.git/' not found",
+                CloneFailureCategory::RepoGone,
+            ),
+            (
+                "warning: Could not find remote branch 1.2.3 to clone.\nfatal: Remote branch 1.2.3 not found in upstream origin",
+                CloneFailureCategory::TagMissing,
+            ),
+            (
+                "fatal: could not read Username for 'https://github.com': terminal prompts disabled",
+                CloneFailureCategory::AuthRequired,
+            ),
+            (
+                "fatal: unable to access 'https://github.com/a/b.git/': Could not resolve host: github.com",
+                CloneFailureCategory::Network,
+            ),
+            (
+                "error: RPC failed; HTTP 413 curl 22 The requested URL returned error: 413",
+                CloneFailureCategory::LfsOrSize,
+            ),
+            (
+                "fatal: pack exceeds maximum allowed size",
+                CloneFailureCategory::Unknown,
+            ),
+            ("fatal: something odd", CloneFailureCategory::Unknown),
+        ];
+
+        for (stderr, expected) in cases {
+            assert_eq!(CloneFailureCategory::from_git_stderr(stderr), expected);
+        }
+    }
+
+    #[test]
+    fn category_labels_round_trip() {
+        for category in [
+            CloneFailureCategory::RepoGone,
+            CloneFailureCategory::TagMissing,
+            CloneFailureCategory::AuthRequired,
+            CloneFailureCategory::LfsOrSize,
+            CloneFailureCategory::Network,
+            CloneFailureCategory::Timeout,
+            CloneFailureCategory::Invalid,
+            CloneFailureCategory::Local,
+            CloneFailureCategory::Unknown,
+        ] {
+            assert_eq!(
+                CloneFailureCategory::from_state_value(category.as_state_value()),
+                category
+            );
+        }
+        assert_eq!(CloneFailureCategory::RepoGone.backoff_days(), 14);
+    }
+}
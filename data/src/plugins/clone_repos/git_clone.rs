@@ -13,9 +13,11 @@ use std::os::unix::process::CommandExt;
 
 use data_lib::plugin::PluginData;
 
+use super::failure::CloneFailureCategory;
 use crate::{
     constants::PLUGIN_REPO_PATH,
-    security::{github_repo_url, validated_plugin_path},
+    forge::{ForgeConfig, RepoUrlError},
+    security::validated_plugin_path,
     state::now_unix_seconds,
 };
 
@@ -24,6 +26,14 @@ pub(super) enum CloneResult {
         id: String,
         repo: String,
         target_release_tag: String,
+        strategy: CloneStrategy,
+    },
+    /// The clone exceeded the configured size limit and was discarded.
+    Oversized {
+        id: String,
+        repo: String,
+        target_release_tag: String,
+        size_bytes: u64,
     },
     Failed {
         id: String,
@@ -33,10 +43,98 @@ pub(super) enum CloneResult {
     },
 }
 
+/// Which parts of a repository are downloaded and checked out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CloneMode {
+    /// The whole tree at the target tag.
+    Full,
+    /// A blob-filtered clone whose sparse checkout only contains the files
+    /// repo analysis reads.
+    Partial,
+}
+
+impl CloneMode {
+    pub(super) fn from_env_value(value: Option<&str>) -> Self {
+        match value.map(str::to_lowercase).as_deref() {
+            Some("partial") => Self::Partial,
+            _ => Self::Full,
+        }
+    }
+
+    pub(super) fn as_state_value(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Partial => "partial",
+        }
+    }
+}
+
+/// Sparse checkout patterns (gitignore syntax) used in partial mode. They
+/// cover sources, manifests, package and lock files, configs and license
+/// files, and leave out binary assets such as images and fonts.
+const PARTIAL_CLONE_SPARSE_PATTERNS: &[&str] = &[
+    "*.ts",
+    "*.tsx",
+    "*.mts",
+    "*.cts",
+    "*.js",
+    "*.jsx",
+    "*.mjs",
+    "*.cjs",
+    "*.svelte",
+    "*.vue",
+    "*.css",
+    "*.scss",
+    "*.less",
+    "*.json",
+    "*.jsonc",
+    "*.json5",
+    "*.yaml",
+    "*.yml",
+    "*.toml",
+    "*.lock",
+    "*.md",
+    "*.txt",
+    ".*rc",
+    "LICENSE*",
+    "LICENCE*",
+    "COPYING*",
+    "UNLICENSE*",
+];
+
+#[derive(Debug, Clone)]
+pub(super) struct CloneOptions {
+    pub(super) timeout: Duration,
+    pub(super) forge: ForgeConfig,
+    pub(super) mode: CloneMode,
+    pub(super) blob_limit_bytes: u64,
+    pub(super) max_repo_bytes: u64,
+}
+
+/// How a successful clone reached the target release tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CloneStrategy {
+    /// Fresh shallow clone into a temp dir that replaced the previous clone.
+    FullClone,
+    /// Only the target tag was fetched into the existing shallow clone.
+    IncrementalFetch,
+}
+
+impl CloneStrategy {
+    pub(super) fn as_state_value(self) -> &'static str {
+        match self {
+            Self::FullClone => "full_clone",
+            Self::IncrementalFetch => "incremental_fetch",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) enum CloneError {
     InvalidPluginId(String),
     InvalidRepoSlug(String),
+    /// The configured local mirror has no repository for the slug.
+    MirrorMissing(String),
     GitStart(String),
     GitOutput(String),
     GitFailed(String),
@@ -50,12 +148,39 @@ pub(super) enum CloneError {
         error: String,
         restore_error: Option<String>,
     },
+    /// The clone exceeds `CloneOptions::max_repo_bytes`, either in the
+    /// downloaded objects before checkout or on disk after it.
+    Oversized {
+        size_bytes: u64,
+    },
 }
 
 impl CloneError {
+    fn from_repo_url_error(error: RepoUrlError) -> Self {
+        match error {
+            RepoUrlError::Invalid(error) => Self::InvalidRepoSlug(error),
+            RepoUrlError::MirrorMissing(_) => Self::MirrorMissing(error.to_string()),
+        }
+    }
+
     pub(super) fn as_state_value(&self) -> String {
         format!("failed:{self}")
     }
+
+    pub(super) fn category(&self) -> CloneFailureCategory {
+        match self {
+            Self::InvalidPluginId(_) | Self::InvalidRepoSlug(_) => CloneFailureCategory::Invalid,
+            Self::GitFailed(stderr) => CloneFailureCategory::from_git_stderr(stderr),
+            Self::GitTimeout { .. } => CloneFailureCategory::Timeout,
+            Self::Oversized { .. } => CloneFailureCategory::LfsOrSize,
+            Self::GitStart(_)
+            | Self::GitOutput(_)
+            | Self::GitWait(_)
+            | Self::MirrorMissing(_)
+            | Self::MoveExistingToBackup(_)
+            | Self::MoveCloneIntoPlace { .. } => CloneFailureCategory::Local,
+        }
+    }
 }
 
 impl fmt::Display for CloneError {
@@ -63,6 +188,7 @@ impl fmt::Display for CloneError {
         match self {
             Self::InvalidPluginId(error) => write!(f, "{error}"),
             Self::InvalidRepoSlug(error) => write!(f, "{error}"),
+            Self::MirrorMissing(error) => write!(f, "{error}"),
             Self::GitStart(error) => write!(f, "failed to start git clone: {error}"),
             Self::GitOutput(error) => write!(f, "failed to read git clone output: {error}"),
             Self::GitFailed(error) => f.write_str(error),
@@ -89,6 +215,9 @@ impl fmt::Display for CloneError {
                 }
                 Ok(())
             }
+            Self::Oversized { size_bytes } => {
+                write!(f, "clone exceeds size limit: {size_bytes} bytes")
+            }
         }
     }
 }
@@ -96,7 +225,8 @@ impl fmt::Display for CloneError {
 pub(super) fn clone_repo_preserving_previous(
     plugin: &PluginData,
     target_release_tag: &str,
-    clone_timeout: Duration,
+    options: &CloneOptions,
+    try_incremental: bool,
 ) -> CloneResult {
     let target_path = match validated_plugin_path(Path::new(PLUGIN_REPO_PATH), &plugin.id) {
         Ok(path) => path,
@@ -109,6 +239,36 @@ pub(super) fn clone_repo_preserving_previous(
             };
         }
     };
+
+    let mut fetch_failed = false;
+    if try_incremental && target_path.join(".git").is_dir() {
+        let fetch_result = options
+            .forge
+            .repo_url(&plugin.current_entry.repo)
+            .map_err(CloneError::from_repo_url_error)
+            .and_then(|repo_url| {
+                fetch_tag_into_existing_clone(&target_path, &repo_url, target_release_tag, options)
+            });
+        match fetch_result {
+            Ok(()) => {
+                return CloneResult::Success {
+                    id: plugin.id.clone(),
+                    repo: plugin.current_entry.repo.clone(),
+                    target_release_tag: target_release_tag.to_string(),
+                    strategy: CloneStrategy::IncrementalFetch,
+                };
+            }
+            // An existing clone keeps the objects of every earlier tag, so
+            // an oversized result here is left to the fresh clone to confirm.
+            Err(error) => {
+                println!(
+                    "  Clone fetch fallback: {} {error}; falling back to full clone",
+                    plugin.id
+                );
+                fetch_failed = true;
+            }
+        }
+    }
     let timestamp = now_unix_seconds();
     let tmp_path = Path::new(PLUGIN_REPO_PATH).join(format!(".tmp-{}-{timestamp}", plugin.id));
     let backup_path = Path::new(PLUGIN_REPO_PATH).join(format!(".bak-{}-{timestamp}", plugin.id));
@@ -120,16 +280,33 @@ pub(super) fn clone_repo_preserving_previous(
         let _ = std::fs::remove_dir_all(&backup_path);
     }
 
-    let clone_result = run_with_timeout_retry(
-        || run_git_clone(plugin, target_release_tag, &tmp_path, clone_timeout),
+    let clone_result = run_with_transient_retry(
+        || run_git_clone(plugin, target_release_tag, &tmp_path, options),
         |error| {
             println!("  Clone retry: {} {error}; retrying once", plugin.id);
             let _ = std::fs::remove_dir_all(&tmp_path);
         },
     );
 
+    if let Err(CloneError::Oversized { size_bytes }) = clone_result {
+        let _ = std::fs::remove_dir_all(&tmp_path);
+        // A clone of an older tag would still be analyzed otherwise, so the
+        // repo is dropped entirely once it grows past the limit.
+        let _ = std::fs::remove_dir_all(&target_path);
+        return CloneResult::Oversized {
+            id: plugin.id.clone(),
+            repo: plugin.current_entry.repo.clone(),
+            target_release_tag: target_release_tag.to_string(),
+            size_bytes,
+        };
+    }
     if let Err(error) = clone_result {
         let _ = std::fs::remove_dir_all(&tmp_path);
+        // The failed fetch may have stopped between fetching and checking out,
+        // so the previous clone can no longer be trusted to match any tag.
+        if fetch_failed {
+            let _ = std::fs::remove_dir_all(&target_path);
+        }
         return CloneResult::Failed {
             id: plugin.id.clone(),
             repo: plugin.current_entry.repo.clone(),
@@ -158,6 +335,7 @@ pub(super) fn clone_repo_preserving_previous(
                 id: plugin.id.clone(),
                 repo: plugin.current_entry.repo.clone(),
                 target_release_tag: target_release_tag.to_string(),
+                strategy: CloneStrategy::FullClone,
             }
         }
         Err(error) => {
@@ -185,13 +363,15 @@ pub(super) fn clone_repo_preserving_previous(
     }
 }
 
-fn run_with_timeout_retry<T, F, R>(mut operation: F, mut before_retry: R) -> Result<T, CloneError>
+/// Retries once if the first attempt failed for a transient reason such as a
+/// timeout or a network problem.
+fn run_with_transient_retry<T, F, R>(mut operation: F, mut before_retry: R) -> Result<T, CloneError>
 where
     F: FnMut() -> Result<T, CloneError>,
     R: FnMut(&CloneError),
 {
     match operation() {
-        Err(error @ CloneError::GitTimeout { .. }) => {
+        Err(error) if error.category().is_retryable_in_run() => {
             before_retry(&error);
             operation()
         }
@@ -203,32 +383,154 @@ fn run_git_clone(
     plugin: &PluginData,
     target_release_tag: &str,
     tmp_path: &Path,
-    clone_timeout: Duration,
+    options: &CloneOptions,
+) -> Result<(), CloneError> {
+    let repo_url = options
+        .forge
+        .repo_url(&plugin.current_entry.repo)
+        .map_err(CloneError::from_repo_url_error)?;
+    clone_tag(&repo_url, target_release_tag, tmp_path, options)
+}
+
+fn clone_tag(
+    repo_url: &str,
+    target_release_tag: &str,
+    tmp_path: &Path,
+    options: &CloneOptions,
+) -> Result<(), CloneError> {
+    let started = Instant::now();
+    let mut command = git_command();
+    command.args([
+        "clone",
+        "--depth",
+        "1",
+        "--branch",
+        target_release_tag,
+        "--single-branch",
+        "--no-checkout",
+        "--quiet",
+    ]);
+    if options.mode == CloneMode::Partial {
+        command.arg(blob_filter_arg(options.blob_limit_bytes));
+    }
+    command.arg(repo_url).arg(tmp_path);
+    run_clone_command(&mut command, options.timeout)?;
+    ensure_within_size_limit(&tmp_path.join(".git"), options.max_repo_bytes)?;
+
+    if options.mode == CloneMode::Partial {
+        set_sparse_checkout(tmp_path, remaining_timeout(options.timeout, started))?;
+    }
+    let mut checkout = git_command();
+    checkout
+        .arg("-C")
+        .arg(tmp_path)
+        .args(["checkout", "--quiet", "--force", "--detach", "HEAD"]);
+    run_clone_command(&mut checkout, remaining_timeout(options.timeout, started))?;
+    ensure_within_size_limit(tmp_path, options.max_repo_bytes)
+}
+
+/// Checked on `.git` before checkout, so oversized repos never get a working
+/// tree, and on the whole repo after it, since a partial clone only fetches
+/// the filtered blobs while checking out.
+fn ensure_within_size_limit(path: &Path, max_repo_bytes: u64) -> Result<(), CloneError> {
+    let size_bytes = directory_size_bytes(path);
+    if size_bytes > max_repo_bytes {
+        return Err(CloneError::Oversized { size_bytes });
+    }
+    Ok(())
+}
+
+fn set_sparse_checkout(repo_path: &Path, timeout: Duration) -> Result<(), CloneError> {
+    let mut command = git_command();
+    command
+        .arg("-C")
+        .arg(repo_path)
+        .args(["sparse-checkout", "set", "--no-cone"])
+        .args(PARTIAL_CLONE_SPARSE_PATTERNS);
+    run_clone_command(&mut command, timeout)
+}
+
+fn blob_filter_arg(blob_limit_bytes: u64) -> String {
+    format!("--filter=blob:limit={blob_limit_bytes}")
+}
+
+/// Moves an existing shallow clone to `target_release_tag` by fetching only
+/// that tag. The working tree is forced onto the tag and untracked files are
+/// removed so the result matches a fresh clone of the same tag.
+fn fetch_tag_into_existing_clone(
+    repo_path: &Path,
+    repo_url: &str,
+    target_release_tag: &str,
+    options: &CloneOptions,
 ) -> Result<(), CloneError> {
-    let repo_url =
-        github_repo_url(&plugin.current_entry.repo).map_err(CloneError::InvalidRepoSlug)?;
+    let clone_timeout = options.timeout;
+    let tag_ref = format!("refs/tags/{target_release_tag}");
+    let started = Instant::now();
+
+    let mut fetch = git_command();
+    fetch
+        .arg("-C")
+        .arg(repo_path)
+        .args(["fetch", "--depth", "1", "--no-tags", "--quiet"]);
+    // The sparse checkout patterns are kept in the clone's config, so only
+    // the blob filter has to be repeated here.
+    if options.mode == CloneMode::Partial {
+        fetch.arg(blob_filter_arg(options.blob_limit_bytes));
+    }
+    fetch.arg(repo_url).arg(format!("+{tag_ref}:{tag_ref}"));
+    run_clone_command(&mut fetch, clone_timeout)?;
+    ensure_within_size_limit(&repo_path.join(".git"), options.max_repo_bytes)?;
+
+    let mut checkout = git_command();
+    checkout
+        .arg("-C")
+        .arg(repo_path)
+        .args(["checkout", "--quiet", "--force", "--detach", &tag_ref]);
+    run_clone_command(&mut checkout, remaining_timeout(clone_timeout, started))?;
+
+    let mut clean = git_command();
+    clean.arg("-C").arg(repo_path).args(["clean", "-ffdxq"]);
+    run_clone_command(&mut clean, remaining_timeout(clone_timeout, started))?;
+    ensure_within_size_limit(repo_path, options.max_repo_bytes)
+}
+
+fn remaining_timeout(clone_timeout: Duration, started: Instant) -> Duration {
+    clone_timeout
+        .saturating_sub(started.elapsed())
+        .max(Duration::from_secs(1))
+}
+
+/// Total size of all files below `path`, including the `.git` directory.
+/// Symlinks are counted by their own size and not followed.
+fn directory_size_bytes(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => directory_size_bytes(&entry.path()),
+            Ok(_) => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+pub(super) fn git_command() -> Command {
     let mut command = Command::new("git");
     command
-        .args([
-            "clone",
-            "--depth",
-            "1",
-            "--branch",
-            target_release_tag,
-            "--single-branch",
-            "--quiet",
-            &repo_url,
-        ])
-        .arg(tmp_path)
         .env("GIT_TERMINAL_PROMPT", "0")
         .env("GIT_LFS_SKIP_SMUDGE", "1")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
-
-    run_clone_command(&mut command, clone_timeout)
+    command
 }
 
-fn run_clone_command(command: &mut Command, clone_timeout: Duration) -> Result<(), CloneError> {
+pub(super) fn run_clone_command(
+    command: &mut Command,
+    clone_timeout: Duration,
+) -> Result<(), CloneError> {
     #[cfg(unix)]
     command.process_group(0);
 
@@ -381,7 +683,13 @@ mod tests {
         time::{Duration, Instant},
     };
 
-    use super::{CloneError, run_clone_command, run_with_timeout_retry};
+    use crate::forge::{Forge, ForgeConfig};
+
+    use super::{
+        CloneError, CloneFailureCategory, CloneMode, CloneOptions, CloneStrategy, clone_tag,
+        directory_size_bytes, fetch_tag_into_existing_clone, git_command, run_clone_command,
+        run_with_transient_retry,
+    };
 
     #[test]
     fn clone_error_status_keeps_failed_prefix_and_detail() {
@@ -396,12 +704,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn missing_local_mirror_is_a_local_failure() {
+        let config = ForgeConfig {
+            default: Forge::LocalMirror {
+                dir: std::env::temp_dir().join(format!(
+                    "obsidian-stats-missing-mirror-test-{}-{}",
+                    std::process::id(),
+                    crate::state::now_unix_seconds()
+                )),
+            },
+            ..ForgeConfig::default()
+        };
+
+        let error = config
+            .repo_url("owner/repo")
+            .map_err(CloneError::from_repo_url_error)
+            .unwrap_err();
+
+        assert!(matches!(error, CloneError::MirrorMissing(_)));
+        assert_eq!(error.category(), CloneFailureCategory::Local);
+    }
+
     #[test]
     fn timeout_is_retried_once() {
         let attempts = Cell::new(0);
         let retries = Cell::new(0);
 
-        let result = run_with_timeout_retry(
+        let result = run_with_transient_retry(
             || {
                 attempts.set(attempts.get() + 1);
                 if attempts.get() == 1 {
@@ -421,11 +751,30 @@ mod tests {
         assert_eq!(retries.get(), 1);
     }
 
+    #[test]
+    fn network_failure_is_retried_once() {
+        let attempts = Cell::new(0);
+
+        let result: Result<(), _> = run_with_transient_retry(
+            || {
+                attempts.set(attempts.get() + 1);
+                Err(CloneError::GitFailed(
+                    "fatal: unable to access 'https://github.com/a/b.git/': Could not resolve host"
+                        .to_string(),
+                ))
+            },
+            |_| {},
+        );
+
+        assert!(matches!(result, Err(CloneError::GitFailed(_))));
+        assert_eq!(attempts.get(), 2);
+    }
+
     #[test]
     fn non_timeout_failure_is_not_retried() {
         let attempts = Cell::new(0);
 
-        let result: Result<(), _> = run_with_timeout_retry(
+        let result: Result<(), _> = run_with_transient_retry(
             || {
                 attempts.set(attempts.get() + 1);
                 Err(CloneError::GitFailed("failed".to_string()))
@@ -441,7 +790,7 @@ mod tests {
     fn repeated_timeout_stops_after_single_retry() {
         let attempts = Cell::new(0);
 
-        let result: Result<(), _> = run_with_timeout_retry(
+        let result: Result<(), _> = run_with_transient_retry(
             || {
                 attempts.set(attempts.get() + 1);
                 Err(CloneError::GitTimeout {
@@ -485,4 +834,314 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn clone_strategy_values_match_persisted_labels() {
+        assert_eq!(CloneStrategy::FullClone.as_state_value(), "full_clone");
+        assert_eq!(
+            CloneStrategy::IncrementalFetch.as_state_value(),
+            "incremental_fetch"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fetch_moves_existing_shallow_clone_to_new_tag() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-fetch-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let clone = root.join("clone");
+        std::fs::create_dir_all(&source).unwrap();
+
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            let mut command = git_command();
+            command
+                .arg("-C")
+                .arg(dir)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args);
+            run_clone_command(&mut command, Duration::from_secs(30)).unwrap();
+        };
+        git(&source, &["init", "--quiet"]);
+        std::fs::write(source.join("manifest.json"), "1.0.0").unwrap();
+        git(&source, &["add", "."]);
+        git(&source, &["commit", "--quiet", "-m", "first"]);
+        git(&source, &["tag", "1.0.0"]);
+        std::fs::write(source.join("manifest.json"), "1.1.0").unwrap();
+        git(&source, &["commit", "--quiet", "-am", "second"]);
+        git(&source, &["tag", "1.1.0"]);
+
+        let source_url = format!("file://{}", source.display());
+        let mut clone_command = git_command();
+        clone_command
+            .args(["clone", "--depth", "1", "--branch", "1.0.0", "--quiet"])
+            .arg(&source_url)
+            .arg(&clone);
+        run_clone_command(&mut clone_command, Duration::from_secs(30)).unwrap();
+        std::fs::write(clone.join("stale.txt"), "left over").unwrap();
+
+        let result = fetch_tag_into_existing_clone(
+            &clone,
+            &source_url,
+            "1.1.0",
+            &test_options(CloneMode::Full),
+        );
+        let manifest = std::fs::read_to_string(clone.join("manifest.json"));
+        let stale_exists = clone.join("stale.txt").exists();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(result.is_ok());
+        assert_eq!(manifest.unwrap(), "1.1.0");
+        assert!(!stale_exists);
+    }
+
+    #[test]
+    fn clone_mode_defaults_to_full() {
+        assert_eq!(CloneMode::from_env_value(None), CloneMode::Full);
+        assert_eq!(CloneMode::from_env_value(Some("unknown")), CloneMode::Full);
+        assert_eq!(
+            CloneMode::from_env_value(Some("Partial")),
+            CloneMode::Partial
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn partial_clone_only_checks_out_analyzable_files() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-partial-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let clone = root.join("clone");
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::create_dir_all(source.join("assets")).unwrap();
+        std::fs::write(source.join("src/main.ts"), "export {}").unwrap();
+        std::fs::write(source.join("LICENSE"), "MIT").unwrap();
+        std::fs::write(source.join("assets/demo.gif"), [0_u8; 64]).unwrap();
+
+        let mut init = git_command();
+        init.arg("-C").arg(&source).args(["init", "--quiet"]);
+        run_clone_command(&mut init, Duration::from_secs(30)).unwrap();
+        let mut add = git_command();
+        add.arg("-C").arg(&source).args(["add", "."]);
+        run_clone_command(&mut add, Duration::from_secs(30)).unwrap();
+        let mut commit = git_command();
+        commit.arg("-C").arg(&source).args([
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "init",
+        ]);
+        run_clone_command(&mut commit, Duration::from_secs(30)).unwrap();
+        let mut tag = git_command();
+        tag.arg("-C").arg(&source).args(["tag", "1.0.0"]);
+        run_clone_command(&mut tag, Duration::from_secs(30)).unwrap();
+
+        let source_url = format!("file://{}", source.display());
+        let result = clone_tag(
+            &source_url,
+            "1.0.0",
+            &clone,
+            &test_options(CloneMode::Partial),
+        );
+        let has_source = clone.join("src/main.ts").exists();
+        let has_license = clone.join("LICENSE").exists();
+        let has_asset = clone.join("assets/demo.gif").exists();
+        let size_bytes = directory_size_bytes(&clone);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(result.is_ok());
+        assert!(has_source);
+        assert!(has_license);
+        assert!(!has_asset);
+        assert!(size_bytes > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clones_from_local_bare_mirror() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-mirror-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let mirror_dir = root.join("mirrors");
+        let clone = root.join("clone");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("manifest.json"), "{}").unwrap();
+
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["add", "."],
+            vec![
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+            vec!["tag", "1.0.0"],
+        ] {
+            let mut command = git_command();
+            command.arg("-C").arg(&source).args(args);
+            run_clone_command(&mut command, Duration::from_secs(30)).unwrap();
+        }
+        let mut mirror = git_command();
+        mirror
+            .args(["clone", "--bare", "--quiet"])
+            .arg(&source)
+            .arg(mirror_dir.join("owner/repo.git"));
+        run_clone_command(&mut mirror, Duration::from_secs(30)).unwrap();
+
+        let mut options = test_options(CloneMode::Full);
+        options.forge = ForgeConfig {
+            default: Forge::LocalMirror {
+                dir: mirror_dir.clone(),
+            },
+            ..ForgeConfig::default()
+        };
+        let result = options
+            .forge
+            .repo_url("owner/repo")
+            .map_err(|e| e.to_string())
+            .and_then(|url| clone_tag(&url, "1.0.0", &clone, &options).map_err(|e| e.to_string()));
+        let has_manifest = clone.join("manifest.json").exists();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(result.is_ok());
+        assert!(has_manifest);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn oversized_clone_stops_before_checkout() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-oversized-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let clone = root.join("clone");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("manifest.json"), "{}").unwrap();
+
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["add", "."],
+            vec![
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+            vec!["tag", "1.0.0"],
+        ] {
+            let mut command = git_command();
+            command.arg("-C").arg(&source).args(args);
+            run_clone_command(&mut command, Duration::from_secs(30)).unwrap();
+        }
+
+        let mut options = test_options(CloneMode::Full);
+        options.max_repo_bytes = 1;
+        let result = clone_tag(
+            &format!("file://{}", source.display()),
+            "1.0.0",
+            &clone,
+            &options,
+        );
+        let has_manifest = clone.join("manifest.json").exists();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(matches!(result, Err(CloneError::Oversized { size_bytes }) if size_bytes > 1));
+        assert!(!has_manifest);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn partial_clone_size_is_checked_after_checkout() {
+        let root = std::env::temp_dir().join(format!(
+            "obsidian-stats-partial-size-test-{}-{}",
+            std::process::id(),
+            crate::state::now_unix_seconds()
+        ));
+        let source = root.join("source");
+        let clone = root.join("clone");
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        // Incompressible, so the lazily fetched blob keeps its size.
+        let mut seed = 0x2545_f491_u32;
+        let large_source = (0..512 * 1024)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                b'a' + (seed % 26) as u8
+            })
+            .collect::<Vec<_>>();
+        std::fs::write(source.join("src/main.ts"), large_source).unwrap();
+
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["config", "uploadpack.allowFilter", "true"],
+            vec!["add", "."],
+            vec![
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+            vec!["tag", "1.0.0"],
+        ] {
+            let mut command = git_command();
+            command.arg("-C").arg(&source).args(args);
+            run_clone_command(&mut command, Duration::from_secs(30)).unwrap();
+        }
+
+        let mut options = test_options(CloneMode::Partial);
+        options.blob_limit_bytes = 1024;
+        options.max_repo_bytes = 256 * 1024;
+        let result = clone_tag(
+            &format!("file://{}", source.display()),
+            "1.0.0",
+            &clone,
+            &options,
+        );
+        let has_source = clone.join("src/main.ts").exists();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(has_source);
+        assert!(
+            matches!(result, Err(CloneError::Oversized { size_bytes }) if size_bytes > 512 * 1024)
+        );
+    }
+
+    fn test_options(mode: CloneMode) -> CloneOptions {
+        CloneOptions {
+            timeout: Duration::from_secs(30),
+            forge: ForgeConfig::default(),
+            mode,
+            blob_limit_bytes: 1024 * 1024,
+            max_repo_bytes: u64::MAX,
+        }
+    }
 }
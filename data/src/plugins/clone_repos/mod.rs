@@ -13,29 +13,42 @@ use serde::{Deserialize, Serialize};
 
 use hashbrown::HashMap;
 
+mod archive;
+mod failure;
 mod git_clone;
 
-use git_clone::{CloneResult, clone_repo_preserving_previous};
+pub use archive::materialize_snapshot;
+
+use archive::{archive_snapshot, pack_archive};
+use failure::CloneFailureCategory;
+use git_clone::{
+    CloneMode, CloneOptions, CloneResult, CloneStrategy, clone_repo_preserving_previous,
+};
 
 use crate::{
     alerts,
     constants::{
-        CLONE_STATE_PATH, DEFAULT_CLONE_TIMEOUT_SECONDS, DEFAULT_MAX_CLONE_THREADS,
-        PLUGIN_REPO_PATH,
+        CLONE_STATE_PATH, DEFAULT_CLONE_BLOB_LIMIT_KB, DEFAULT_CLONE_MAX_REPO_MB,
+        DEFAULT_CLONE_TIMEOUT_SECONDS, DEFAULT_MAX_CLONE_THREADS, PLUGIN_REPO_PATH,
     },
     file_utils::ensure_dir,
+    forge::ForgeConfig,
     plugins::{data::read_plugin_data, stats_helper::HelperPluginStore},
     progress::should_log_progress,
     security::validated_plugin_path,
-    state::{now_unix_seconds, read_json_or_default, write_json_atomic},
+    state::{is_fresh, now_unix_seconds, read_json_or_default, write_json_atomic},
 };
 
 const CLONE_THREADS_ENV: &str = "CLONE_THREADS";
+const CLONE_MODE_ENV: &str = "CLONE_MODE";
+const CLONE_BLOB_LIMIT_KB_ENV: &str = "CLONE_BLOB_LIMIT_KB";
+const CLONE_MAX_REPO_MB_ENV: &str = "CLONE_MAX_REPO_MB";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CloneStatus {
     Ok,
     SkippedRemoved,
+    Oversized,
 }
 
 impl CloneStatus {
@@ -43,6 +56,7 @@ impl CloneStatus {
         match self {
             Self::Ok => "ok",
             Self::SkippedRemoved => "skipped_removed",
+            Self::Oversized => "oversized",
         }
     }
 }
@@ -50,6 +64,7 @@ impl CloneStatus {
 struct CloneJob {
     plugin: PluginData,
     target_release_tag: String,
+    try_incremental: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -59,7 +74,11 @@ struct CloneState {
 
 type CloneStateEntry = PluginPageCloneFreshness;
 
-pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub fn clone_plugin_repos(
+    force: bool,
+    no_clone: bool,
+    archive: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     ensure_dir(Path::new(PLUGIN_REPO_PATH))?;
 
     println!("Loading data...");
@@ -75,14 +94,26 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
         .map(|n| default_clone_thread_count(n.get()))
         .unwrap_or(4);
     let thread_count = configured_thread_count(CLONE_THREADS_ENV, default_threads);
+    let clone_options = CloneOptions {
+        timeout: clone_timeout,
+        forge: ForgeConfig::from_env()?,
+        mode: CloneMode::from_env_value(std::env::var(CLONE_MODE_ENV).ok().as_deref()),
+        blob_limit_bytes: configured_u64(CLONE_BLOB_LIMIT_KB_ENV, DEFAULT_CLONE_BLOB_LIMIT_KB)
+            * 1024,
+        max_repo_bytes: configured_u64(CLONE_MAX_REPO_MB_ENV, DEFAULT_CLONE_MAX_REPO_MB)
+            * 1024
+            * 1024,
+    };
 
     let mut state: CloneState = read_json_or_default(Path::new(CLONE_STATE_PATH));
     let run_started_unix = now_unix_seconds();
     let helper_store = HelperPluginStore::read()?;
 
     println!(
-        "Starting cloning process (clone timeout: {}s, force: {}, no_clone: {}, threads: {})...",
+        "Starting cloning process (clone timeout: {}s, mode: {}, max repo size: {} MB, force: {}, no_clone: {}, threads: {})...",
         clone_timeout.as_secs(),
+        clone_options.mode.as_state_value(),
+        clone_options.max_repo_bytes / 1024 / 1024,
         force,
         no_clone,
         thread_count
@@ -97,6 +128,8 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
     let mut skipped_removed = 0;
     let mut skipped_missing_version = 0;
     let mut skipped_current = 0;
+    let mut skipped_oversized = 0;
+    let mut skipped_backoff = 0;
 
     for plugin in &data {
         if plugin.removed_commit.is_some() {
@@ -109,6 +142,9 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                     last_attempt_unix: run_started_unix,
                     last_success_unix: None,
                     status: CloneStatus::SkippedRemoved.as_state_value().to_string(),
+                    clone_strategy: None,
+                    clone_mode: None,
+                    failure_category: None,
                 },
             );
             continue;
@@ -133,6 +169,9 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                         last_attempt_unix: run_started_unix,
                         last_success_unix: previous_success,
                         status: error.as_state_value().to_string(),
+                        clone_strategy: None,
+                        clone_mode: None,
+                        failure_category: None,
                     },
                 );
                 continue;
@@ -152,17 +191,41 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
             continue;
         }
 
+        if !force
+            && let Some(state_entry) = state_entry
+            && state_entry.repo == plugin.current_entry.repo
+            && state_entry.target_release_tag.as_deref() == Some(target_release_tag.as_str())
+            && state_entry.status == CloneStatus::Oversized.as_state_value()
+        {
+            skipped_oversized += 1;
+            continue;
+        }
+
+        if !force
+            && let Some(state_entry) = state_entry
+            && is_backing_off(state_entry, plugin, &target_release_tag)
+        {
+            skipped_backoff += 1;
+            continue;
+        }
+
+        let try_incremental = !force
+            && state_entry
+                .is_some_and(|entry| can_update_incrementally(entry, plugin, clone_options.mode));
         clone_jobs.push(CloneJob {
             plugin: plugin.clone(),
             target_release_tag,
+            try_incremental,
         });
     }
 
     println!(
-        "Clone plan: total={}, queued={}, current_skipped={}, removed_skipped={}, missing_version_skipped={}",
+        "Clone plan: total={}, queued={}, current_skipped={}, oversized_skipped={}, backoff_skipped={}, removed_skipped={}, missing_version_skipped={}",
         data.len(),
         clone_jobs.len(),
         skipped_current,
+        skipped_oversized,
+        skipped_backoff,
         skipped_removed,
         skipped_missing_version
     );
@@ -190,15 +253,24 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                 let result = clone_repo_preserving_previous(
                     &job.plugin,
                     &job.target_release_tag,
-                    clone_timeout,
+                    &clone_options,
+                    job.try_incremental,
                 );
 
                 let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
                 let elapsed = job_started.elapsed();
                 match &result {
-                    CloneResult::Success { id, .. } => {
+                    CloneResult::Success { id, strategy, .. } => {
                         println!(
-                            "  Clone done: {done} / {total_jobs} {id} ok in {:.1}s",
+                            "  Clone done: {done} / {total_jobs} {id} ok ({}) in {:.1}s",
+                            strategy.as_state_value(),
+                            elapsed.as_secs_f32()
+                        );
+                    }
+                    CloneResult::Oversized { id, size_bytes, .. } => {
+                        println!(
+                            "  Clone done: {done} / {total_jobs} {id} oversized ({} MB) in {:.1}s",
+                            size_bytes / 1024 / 1024,
                             elapsed.as_secs_f32()
                         );
                     }
@@ -225,6 +297,7 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                 id,
                 repo,
                 target_release_tag,
+                strategy,
             } => {
                 state.entries.insert(
                     id.clone(),
@@ -234,6 +307,29 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                         last_attempt_unix: state_updated_unix,
                         last_success_unix: Some(state_updated_unix),
                         status: CloneStatus::Ok.as_state_value().to_string(),
+                        clone_strategy: Some(strategy.as_state_value().to_string()),
+                        clone_mode: Some(clone_options.mode.as_state_value().to_string()),
+                        failure_category: None,
+                    },
+                );
+            }
+            CloneResult::Oversized {
+                id,
+                repo,
+                target_release_tag,
+                ..
+            } => {
+                state.entries.insert(
+                    id.clone(),
+                    CloneStateEntry {
+                        repo: repo.clone(),
+                        target_release_tag: Some(target_release_tag.clone()),
+                        last_attempt_unix: state_updated_unix,
+                        last_success_unix: None,
+                        status: CloneStatus::Oversized.as_state_value().to_string(),
+                        clone_strategy: None,
+                        clone_mode: Some(clone_options.mode.as_state_value().to_string()),
+                        failure_category: None,
                     },
                 );
             }
@@ -258,6 +354,9 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
                         last_attempt_unix: state_updated_unix,
                         last_success_unix: previous_success,
                         status: error.as_state_value(),
+                        clone_strategy: None,
+                        clone_mode: None,
+                        failure_category: Some(error.category().as_state_value().to_string()),
                     },
                 );
             }
@@ -266,6 +365,10 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
 
     write_json_atomic(Path::new(CLONE_STATE_PATH), &state)?;
 
+    if archive {
+        archive_successful_clones(&clone_results, &clone_options);
+    }
+
     let failed_plugins: Vec<_> = clone_results
         .iter()
         .filter_map(|result| match result {
@@ -278,13 +381,47 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
         .iter()
         .filter(|result| matches!(result, CloneResult::Success { .. }))
         .count();
+    let incremental_count = clone_results
+        .iter()
+        .filter(|result| {
+            matches!(
+                result,
+                CloneResult::Success {
+                    strategy: CloneStrategy::IncrementalFetch,
+                    ..
+                }
+            )
+        })
+        .count();
 
     println!("Clone summary:");
     println!("  Success: {}", success_count);
+    println!("    Incremental fetch: {}", incremental_count);
+    println!("    Full clone: {}", success_count - incremental_count);
     println!("  Skipped (removed): {}", skipped_removed);
+    println!(
+        "  Oversized: {}",
+        skipped_oversized
+            + clone_results
+                .iter()
+                .filter(|result| matches!(result, CloneResult::Oversized { .. }))
+                .count()
+    );
     println!("  Skipped (current tag): {}", skipped_current);
+    println!("  Skipped (failure backoff): {}", skipped_backoff);
     println!("  Skipped (missing version): {}", skipped_missing_version);
     println!("  Failed: {}", failed_plugins.len());
+    let mut failure_category_counts = HashMap::<&str, usize>::new();
+    for (_, error) in &failed_plugins {
+        *failure_category_counts
+            .entry(error.category().as_state_value())
+            .or_insert(0) += 1;
+    }
+    let mut failure_category_counts = failure_category_counts.into_iter().collect::<Vec<_>>();
+    failure_category_counts.sort_by(|left, right| right.1.cmp(&left.1).then(left.0.cmp(right.0)));
+    for (category, count) in failure_category_counts {
+        println!("    {category}: {count}");
+    }
     println!();
 
     for (id, error) in &failed_plugins {
@@ -308,6 +445,85 @@ pub fn clone_plugin_repos(force: bool, no_clone: bool) -> Result<(), Box<dyn std
     Ok(())
 }
 
+/// A failed clone is not retried until its category's backoff has passed, as
+/// long as the repo and target tag are the same as in the failed attempt.
+fn is_backing_off(entry: &CloneStateEntry, plugin: &PluginData, target_release_tag: &str) -> bool {
+    let Some(category) = entry.failure_category.as_deref() else {
+        return false;
+    };
+
+    entry.repo == plugin.current_entry.repo
+        && entry.target_release_tag.as_deref() == Some(target_release_tag)
+        && entry.status.starts_with("failed:")
+        && is_fresh(
+            entry.last_attempt_unix,
+            CloneFailureCategory::from_state_value(category).backoff_days(),
+        )
+}
+
+/// Stores a snapshot of every clone that succeeded in this run. Partial clones
+/// are skipped because their object store is incomplete.
+fn archive_successful_clones(clone_results: &[CloneResult], clone_options: &CloneOptions) {
+    if clone_options.mode == CloneMode::Partial {
+        println!("Skipping snapshot archive because partial clone mode is enabled.");
+        return;
+    }
+
+    let mut archived = 0;
+    let mut failed = 0;
+    for result in clone_results {
+        let CloneResult::Success {
+            id,
+            target_release_tag,
+            ..
+        } = result
+        else {
+            continue;
+        };
+        let archive_result = validated_plugin_path(Path::new(PLUGIN_REPO_PATH), id)
+            .map_err(|error| error.to_string())
+            .and_then(|path| {
+                archive_snapshot(id, target_release_tag, &path, clone_options.timeout)
+                    .map_err(|error| error.to_string())
+            });
+        match archive_result {
+            Ok(()) => archived += 1,
+            Err(error) => {
+                failed += 1;
+                eprintln!("Failed to archive snapshot of {id} at {target_release_tag}: {error}");
+            }
+        }
+    }
+
+    if archived > 0
+        && let Err(error) = pack_archive(clone_options.timeout)
+    {
+        eprintln!("Failed to pack snapshot archive: {error}");
+    }
+    println!("Snapshot archive: archived={archived}, failed={failed}");
+}
+
+/// An existing clone can be moved to a new tag in place only if it was
+/// cloned successfully from the same repository in the same clone mode.
+fn can_update_incrementally(entry: &CloneStateEntry, plugin: &PluginData, mode: CloneMode) -> bool {
+    entry.repo == plugin.current_entry.repo
+        && entry.target_release_tag.is_some()
+        && entry.status == CloneStatus::Ok.as_state_value()
+        && entry
+            .clone_mode
+            .as_deref()
+            .unwrap_or(CloneMode::Full.as_state_value())
+            == mode.as_state_value()
+}
+
+fn configured_u64(env_var: &str, default_value: u64) -> u64 {
+    std::env::var(env_var)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default_value)
+}
+
 fn configured_thread_count(env_var: &str, default_threads: usize) -> usize {
     std::env::var(env_var)
         .ok()
@@ -331,6 +547,7 @@ mod tests {
             CloneStatus::SkippedRemoved.as_state_value(),
             "skipped_removed"
         );
+        assert_eq!(CloneStatus::Oversized.as_state_value(), "oversized");
     }
 
     #[test]
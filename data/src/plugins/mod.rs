@@ -10,6 +10,7 @@ use serde::Serialize;
 use serde_json::value;
 
 pub mod analysis;
+pub mod cache_gc;
 pub mod clone_repos;
 pub mod data;
 pub mod download_backfill;
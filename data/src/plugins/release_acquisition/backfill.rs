@@ -0,0 +1,290 @@
+//! Backfill of `main.js` for older releases. The regular acquisition only
+//! keeps the target release current; the backfill walks the release history
+//! from the stats helper so that the analysis can build a release timeline.
+
+use std::{error::Error, path::Path};
+
+use data_lib::{date::Date, plugin::PluginData};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        DEFAULT_RELEASE_BACKFILL_MAX_AGE_DAYS, DEFAULT_RELEASE_BACKFILL_MAX_RELEASES,
+        PLUGIN_RELEASE_BACKFILL_STATE_PATH,
+    },
+    github::GithubScheduler,
+    plugins::stats_helper::{HelperPluginData, HelperPluginStore, HelperRelease},
+    progress::should_log_progress,
+    security::github_client,
+    state::{read_json_or_default, write_json_atomic},
+};
+
+use super::{
+    ReleaseFetchStatus,
+    cache::{
+        AssetDownloadError, ReleaseAssetKind, release_main_js_cache_path,
+        save_release_asset_to_cache,
+    },
+    fetch::{fetch_release_by_tag, find_release_asset},
+};
+
+const RELEASE_BACKFILL_MAX_RELEASES_ENV: &str = "RELEASE_BACKFILL_MAX_RELEASES";
+const RELEASE_BACKFILL_MAX_AGE_DAYS_ENV: &str = "RELEASE_BACKFILL_MAX_AGE_DAYS";
+
+/// Bounds how far back the backfill walks for each plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReleaseBackfillLimits {
+    pub max_releases: usize,
+    pub max_age_days: i64,
+}
+
+impl ReleaseBackfillLimits {
+    pub fn from_env() -> Self {
+        Self {
+            max_releases: std::env::var(RELEASE_BACKFILL_MAX_RELEASES_ENV)
+                .ok()
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(DEFAULT_RELEASE_BACKFILL_MAX_RELEASES),
+            max_age_days: std::env::var(RELEASE_BACKFILL_MAX_AGE_DAYS_ENV)
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(DEFAULT_RELEASE_BACKFILL_MAX_AGE_DAYS),
+        }
+    }
+
+    /// Oldest publish date (`YYYY-MM-DD`) that is still within the age limit.
+    pub fn cutoff_date(&self) -> String {
+        let mut cutoff = Date::now();
+        cutoff.reverse_days(u32::try_from(self.max_age_days).unwrap_or(u32::MAX));
+        cutoff.to_fancy_string()
+    }
+}
+
+/// The releases the backfill covers, oldest first: published, non-draft,
+/// non-prerelease releases no older than `cutoff`, limited to the newest
+/// `max_releases`.
+pub fn backfill_releases<'a>(
+    helper_plugin: &'a HelperPluginData,
+    limits: &ReleaseBackfillLimits,
+    cutoff: &str,
+) -> Vec<&'a HelperRelease> {
+    let mut releases = helper_plugin
+        .releases
+        .iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .filter(|release| {
+            release
+                .published_at
+                .as_deref()
+                .and_then(|published_at| published_at.get(..10))
+                .is_some_and(|published_on| published_on >= cutoff)
+        })
+        .collect::<Vec<_>>();
+
+    releases.sort_by(|left, right| right.published_at.cmp(&left.published_at));
+    releases.truncate(limits.max_releases);
+    releases.reverse();
+    releases
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ReleaseBackfillState {
+    /// Tags per plugin that were looked up but have no `main.js` asset, so
+    /// that later runs do not ask GitHub again.
+    #[serde(default)]
+    unavailable_tags: HashMap<String, Vec<String>>,
+}
+
+impl ReleaseBackfillState {
+    fn is_unavailable(&self, plugin_id: &str, tag: &str) -> bool {
+        self.unavailable_tags
+            .get(plugin_id)
+            .is_some_and(|tags| tags.iter().any(|unavailable| unavailable == tag))
+    }
+
+    fn mark_unavailable(&mut self, plugin_id: &str, tag: &str) {
+        let tags = self
+            .unavailable_tags
+            .entry(plugin_id.to_string())
+            .or_default();
+        if !tags.iter().any(|unavailable| unavailable == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+}
+
+#[derive(Default)]
+struct BackfillRunStats {
+    cached: usize,
+    downloaded: usize,
+    unavailable: usize,
+    failed: usize,
+    rate_limited: bool,
+}
+
+/// Downloads the `main.js` of every release within `limits` that is not yet
+/// cached. Stops early when GitHub rate limits us; the next run continues
+/// where this one left off because cached releases are skipped.
+pub fn backfill_plugin_release_main_js(
+    plugins: &[PluginData],
+    limits: &ReleaseBackfillLimits,
+) -> Result<(), Box<dyn Error>> {
+    println!(
+        "Release backfill: up to {} releases per plugin from the last {} days",
+        limits.max_releases, limits.max_age_days
+    );
+
+    let helper_store = HelperPluginStore::read()?;
+    let client = github_client()?;
+    let scheduler = GithubScheduler::shared();
+    let cutoff = limits.cutoff_date();
+    let mut state: ReleaseBackfillState =
+        read_json_or_default(Path::new(PLUGIN_RELEASE_BACKFILL_STATE_PATH));
+    let mut stats = BackfillRunStats::default();
+
+    'plugins: for (idx, plugin) in plugins.iter().enumerate() {
+        if should_log_progress(idx + 1, plugins.len()) {
+            println!(
+                "  Release backfill progress: {} / {}",
+                idx + 1,
+                plugins.len()
+            );
+        }
+        if plugin.removed_commit.is_some() {
+            continue;
+        }
+        let Some(helper_plugin) = helper_store
+            .get(&plugin.id)
+            .filter(|helper_plugin| helper_plugin.repo == plugin.current_entry.repo)
+        else {
+            continue;
+        };
+
+        for release in backfill_releases(helper_plugin, limits, &cutoff) {
+            let Ok(cache_path) = release_main_js_cache_path(&plugin.id, &release.tag) else {
+                continue;
+            };
+            if cache_path.is_file() {
+                stats.cached += 1;
+                continue;
+            }
+            if state.is_unavailable(&plugin.id, &release.tag) {
+                stats.unavailable += 1;
+                continue;
+            }
+
+            let github_release = match fetch_release_by_tag(
+                &client,
+                scheduler,
+                &plugin.current_entry.repo,
+                &release.tag,
+            ) {
+                Ok(github_release) => github_release,
+                Err(ReleaseFetchStatus::RateLimited) => {
+                    stats.rate_limited = true;
+                    break 'plugins;
+                }
+                Err(status) => {
+                    stats.failed += 1;
+                    eprintln!(
+                        "  Release lookup for {} {} failed: {}",
+                        plugin.id,
+                        release.tag,
+                        status.as_state_value()
+                    );
+                    continue;
+                }
+            };
+            let Some(asset) = github_release.as_ref().and_then(|github_release| {
+                find_release_asset(github_release, ReleaseAssetKind::MainJs)
+            }) else {
+                state.mark_unavailable(&plugin.id, &release.tag);
+                stats.unavailable += 1;
+                continue;
+            };
+
+            match save_release_asset_to_cache(
+                &client,
+                scheduler,
+                &plugin.id,
+                &release.tag,
+                ReleaseAssetKind::MainJs,
+                asset,
+            ) {
+                Ok(_) => stats.downloaded += 1,
+                Err(AssetDownloadError::RateLimited(_) | AssetDownloadError::Deferred(_)) => {
+                    stats.rate_limited = true;
+                    break 'plugins;
+                }
+                Err(error) => {
+                    stats.failed += 1;
+                    eprintln!(
+                        "  main.js download for {} {} failed: {}",
+                        plugin.id,
+                        release.tag,
+                        error.detail_message()
+                    );
+                }
+            }
+        }
+    }
+
+    write_json_atomic(Path::new(PLUGIN_RELEASE_BACKFILL_STATE_PATH), &state)?;
+
+    println!("Release backfill summary:");
+    println!("  main.js already cached: {}", stats.cached);
+    println!("  main.js downloaded: {}", stats.downloaded);
+    println!("  Releases without main.js: {}", stats.unavailable);
+    println!("  Failed: {}", stats.failed);
+    if stats.rate_limited {
+        println!("  Stopped early because of the GitHub rate limit; rerun to continue.");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReleaseBackfillLimits, backfill_releases};
+    use crate::plugins::stats_helper::{HelperPluginData, HelperRelease};
+
+    fn release(tag: &str, published_at: &str, prerelease: bool) -> HelperRelease {
+        HelperRelease {
+            tag: tag.to_string(),
+            published_at: Some(published_at.to_string()),
+            download_count: None,
+            prerelease,
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn selects_newest_stable_releases_within_limits() {
+        let helper_plugin = HelperPluginData {
+            id: "plugin".to_string(),
+            repo: "owner/plugin".to_string(),
+            manifest: None,
+            releases: vec![
+                release("0.9.0", "2022-06-01T00:00:00Z", false),
+                release("1.0.0", "2024-01-01T00:00:00Z", false),
+                release("1.1.0-beta", "2024-02-01T00:00:00Z", true),
+                release("1.1.0", "2024-03-01T00:00:00Z", false),
+                release("1.2.0", "2024-04-01T00:00:00Z", false),
+            ],
+        };
+        let limits = ReleaseBackfillLimits {
+            max_releases: 2,
+            max_age_days: 365,
+        };
+
+        let tags = backfill_releases(&helper_plugin, &limits, "2023-01-01")
+            .into_iter()
+            .map(|release| release.tag.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(tags, ["1.1.0", "1.2.0"]);
+    }
+}
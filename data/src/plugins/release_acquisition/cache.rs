@@ -4,40 +4,83 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use reqwest::blocking::Client;
+use sha2::{Digest, Sha256};
 
 use crate::{
     constants::PLUGIN_RELEASE_MAIN_JS_PATH,
+    github::{GithubClient, GithubScheduler, RateLimitDeferred, rate_limit_remaining_is_zero},
     security::{validate_github_download_url, validated_plugin_path},
     state::now_unix_seconds,
 };
 
-use super::ReleaseFetchStatus;
+use super::{
+    ReleaseFetchStatus,
+    fetch::GithubReleaseAsset,
+    store::{
+        ReleaseAssetIndex, ReleaseAssetRevision, hash_file, hex_digest, mark_release_asset_stale,
+        record_release_asset, release_asset_blob_path, store_blob,
+    },
+    verify::{QuarantineReason, quarantine_release_asset, verify_release_asset},
+};
 
 const MAX_MAIN_JS_DOWNLOAD_BYTES: u64 = 512 * 1024 * 1024; // 512 MB
+const MAX_MANIFEST_JSON_DOWNLOAD_BYTES: u64 = 1024 * 1024; // 1 MB
+const MAX_STYLES_CSS_DOWNLOAD_BYTES: u64 = 32 * 1024 * 1024; // 32 MB
+
+/// Release assets that Obsidian installs for a plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReleaseAssetKind {
+    MainJs,
+    ManifestJson,
+    StylesCss,
+}
+
+impl ReleaseAssetKind {
+    pub(super) const ALL: [Self; 3] = [Self::MainJs, Self::ManifestJson, Self::StylesCss];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::MainJs => "main.js",
+            Self::ManifestJson => "manifest.json",
+            Self::StylesCss => "styles.css",
+        }
+    }
+
+    fn max_download_bytes(self) -> u64 {
+        match self {
+            Self::MainJs => MAX_MAIN_JS_DOWNLOAD_BYTES,
+            Self::ManifestJson => MAX_MANIFEST_JSON_DOWNLOAD_BYTES,
+            Self::StylesCss => MAX_STYLES_CSS_DOWNLOAD_BYTES,
+        }
+    }
+}
 
 #[derive(Clone, Copy)]
-pub(super) enum MainJsCacheOutcome {
+pub(super) enum AssetCacheOutcome {
     Downloaded,
     Reused,
 }
 
-pub(super) enum MainJsDownloadError {
+pub(super) enum AssetDownloadError {
     RateLimited(u16),
+    Deferred(RateLimitDeferred),
     InvalidCachePath(String),
     InvalidDownloadUrl(String),
     InvalidSize(u64),
     Request(String),
     Http(u16),
     Read(String),
-    SizeMismatch { expected: u64, actual: u64 },
+    /// The download failed verification and was moved to the quarantine.
+    Quarantined(QuarantineReason),
     Write(String),
 }
 
-impl MainJsDownloadError {
+impl AssetDownloadError {
+    /// Maps a failed main.js download to the release status. Failures of the
+    /// other assets are only reported as alerts.
     pub(super) fn status(&self) -> ReleaseFetchStatus {
         match self {
-            Self::RateLimited(_) => ReleaseFetchStatus::MainJsRateLimited,
+            Self::RateLimited(_) | Self::Deferred(_) => ReleaseFetchStatus::MainJsRateLimited,
             Self::InvalidCachePath(err) => {
                 ReleaseFetchStatus::MainJsDownloadFailed(format!("invalid_cache_path:{err}"))
             }
@@ -54,9 +97,9 @@ impl MainJsDownloadError {
                 ReleaseFetchStatus::MainJsDownloadFailed(format!("http:{status}"))
             }
             Self::Read(err) => ReleaseFetchStatus::MainJsDownloadFailed(format!("read:{err}")),
-            Self::SizeMismatch { expected, actual } => ReleaseFetchStatus::MainJsDownloadFailed(
-                format!("size_mismatch:{expected}:{actual}"),
-            ),
+            Self::Quarantined(reason) => {
+                ReleaseFetchStatus::MainJsQuarantined(reason.as_state_value())
+            }
             Self::Write(err) => ReleaseFetchStatus::MainJsDownloadFailed(format!("write:{err}")),
         }
     }
@@ -64,71 +107,89 @@ impl MainJsDownloadError {
     pub(super) fn detail_message(&self) -> String {
         match self {
             Self::RateLimited(status) => format!("GitHub returned HTTP {status}"),
+            Self::Deferred(deferred) => deferred.to_string(),
             Self::InvalidCachePath(err) => err.clone(),
             Self::InvalidDownloadUrl(err) => err.clone(),
             Self::InvalidSize(size) => format!("asset size {size} bytes is outside allowed bounds"),
             Self::Request(err) => err.clone(),
             Self::Http(status) => format!("GitHub returned HTTP {status}"),
             Self::Read(err) => err.clone(),
-            Self::SizeMismatch { expected, actual } => {
-                format!("expected {expected} bytes, downloaded {actual} bytes")
-            }
+            Self::Quarantined(reason) => format!("quarantined: {}", reason.detail_message()),
             Self::Write(err) => err.clone(),
         }
     }
 }
 
-pub(super) fn save_main_js_to_cache(
-    client: &Client,
+pub(super) fn save_release_asset_to_cache(
+    client: &GithubClient,
+    scheduler: &GithubScheduler,
     plugin_id: &str,
     release_tag: &str,
-    download_url: &str,
-    size: u64,
-) -> Result<MainJsCacheOutcome, MainJsDownloadError> {
-    if size == 0 || size > MAX_MAIN_JS_DOWNLOAD_BYTES {
-        return Err(MainJsDownloadError::InvalidSize(size));
+    kind: ReleaseAssetKind,
+    asset: &GithubReleaseAsset,
+) -> Result<AssetCacheOutcome, AssetDownloadError> {
+    let size = asset.size;
+    let max_size = kind.max_download_bytes();
+    if size == 0 || size > max_size {
+        return Err(AssetDownloadError::InvalidSize(size));
     }
 
-    validate_github_download_url(download_url).map_err(MainJsDownloadError::InvalidDownloadUrl)?;
+    let download_url = asset.browser_download_url.as_str();
+    validate_github_download_url(download_url).map_err(AssetDownloadError::InvalidDownloadUrl)?;
+
+    let revision = asset.revision();
+    let index = ReleaseAssetIndex::read(plugin_id).map_err(AssetDownloadError::InvalidCachePath)?;
+    if is_indexed_asset_current(&index, release_tag, kind, asset) {
+        return Ok(AssetCacheOutcome::Reused);
+    }
 
-    let cache_path = release_main_js_cache_path(plugin_id, release_tag)
-        .map_err(MainJsDownloadError::InvalidCachePath)?;
+    // Adopt assets cached per tag before the content-addressed store existed.
+    let cache_path = legacy_release_asset_path(plugin_id, release_tag, kind)
+        .map_err(AssetDownloadError::InvalidCachePath)?;
     if let Ok(meta) = fs::metadata(&cache_path)
         && meta.len() == size
     {
-        return Ok(MainJsCacheOutcome::Reused);
+        verify_or_quarantine(&cache_path, plugin_id, release_tag, kind, size)?;
+        let sha256 =
+            hash_file(&cache_path).map_err(|error| AssetDownloadError::Read(error.to_string()))?;
+        store_asset(
+            &cache_path,
+            plugin_id,
+            release_tag,
+            kind,
+            &sha256,
+            size,
+            &revision,
+        )?;
+        return Ok(AssetCacheOutcome::Reused);
     }
 
     if let Some(parent) = cache_path.parent() {
         let _ = fs::create_dir_all(parent);
     }
 
-    let mut request = client
+    let request = client
         .get(download_url)
         .header("Accept", "application/octet-stream")
         .header("User-Agent", "obsidian-stats-data");
 
-    if let Ok(token) = std::env::var("GITHUB_TOKEN")
-        && !token.is_empty()
-    {
-        request = request.bearer_auth(token);
-    }
-
-    let response = match request.send() {
-        Ok(resp) => resp,
-        Err(err) => return Err(MainJsDownloadError::Request(err.to_string())),
-    };
+    let permit = scheduler
+        .acquire_download()
+        .map_err(AssetDownloadError::Deferred)?;
+    let response = client
+        .send(request, permit)
+        .map_err(AssetDownloadError::Request)?;
 
     if rate_limit_remaining_is_zero(response.headers()) {
-        return Err(MainJsDownloadError::RateLimited(response.status().as_u16()));
+        return Err(AssetDownloadError::RateLimited(response.status().as_u16()));
     }
 
     if response.status().as_u16() == 403 || response.status().as_u16() == 429 {
-        return Err(MainJsDownloadError::RateLimited(response.status().as_u16()));
+        return Err(AssetDownloadError::RateLimited(response.status().as_u16()));
     }
 
     if !response.status().is_success() {
-        return Err(MainJsDownloadError::Http(response.status().as_u16()));
+        return Err(AssetDownloadError::Http(response.status().as_u16()));
     }
 
     let tmp_path = cache_path.with_file_name(format!(
@@ -136,23 +197,32 @@ pub(super) fn save_main_js_to_cache(
         cache_path
             .file_name()
             .and_then(|name| name.to_str())
-            .unwrap_or("main.js"),
+            .unwrap_or(kind.file_name()),
         now_unix_seconds()
     ));
 
-    let write_result = stream_response_to_file(response, &tmp_path, size);
+    let write_result = stream_response_to_file(response, &tmp_path, size, max_size);
 
     match write_result {
-        Ok(actual) if actual == size => fs::rename(&tmp_path, cache_path)
-            .map(|()| MainJsCacheOutcome::Downloaded)
-            .map_err(|error| MainJsDownloadError::Write(error.to_string())),
-        Ok(actual) => {
-            let _ = fs::remove_file(&tmp_path);
-            Err(MainJsDownloadError::SizeMismatch {
-                expected: size,
-                actual,
+        Ok((_, sha256)) => {
+            verify_or_quarantine(&tmp_path, plugin_id, release_tag, kind, size)?;
+            store_asset(
+                &tmp_path,
+                plugin_id,
+                release_tag,
+                kind,
+                &sha256,
+                size,
+                &revision,
+            )
+            .map(|()| AssetCacheOutcome::Downloaded)
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&tmp_path);
             })
         }
+        Err(AssetDownloadError::Quarantined(reason)) => {
+            Err(quarantine(&tmp_path, plugin_id, release_tag, kind, reason))
+        }
         Err(error) => {
             let _ = fs::remove_file(&tmp_path);
             Err(error)
@@ -160,53 +230,190 @@ pub(super) fn save_main_js_to_cache(
     }
 }
 
+fn verify_or_quarantine(
+    file: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+    size: u64,
+) -> Result<(), AssetDownloadError> {
+    verify_release_asset(file, kind, size)
+        .map_err(|reason| quarantine(file, plugin_id, release_tag, kind, reason))
+}
+
+fn quarantine(
+    file: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+    reason: QuarantineReason,
+) -> AssetDownloadError {
+    if let Err(error) = mark_release_asset_stale(plugin_id, release_tag, kind) {
+        eprintln!(
+            "  Failed to mark {} of {plugin_id} {release_tag} as stale: {error}",
+            kind.file_name()
+        );
+    }
+    match quarantine_release_asset(file, plugin_id, release_tag, kind) {
+        Ok(path) => println!(
+            "  Quarantined {} of {plugin_id} {release_tag} at {}: {}",
+            kind.file_name(),
+            path.display(),
+            reason.detail_message()
+        ),
+        Err(error) => {
+            let _ = fs::remove_file(file);
+            eprintln!(
+                "  Failed to quarantine {} of {plugin_id} {release_tag}: {error}",
+                kind.file_name()
+            );
+        }
+    }
+    AssetDownloadError::Quarantined(reason)
+}
+
+fn store_asset(
+    file: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+    sha256: &str,
+    size: u64,
+    revision: &ReleaseAssetRevision,
+) -> Result<(), AssetDownloadError> {
+    store_blob(file, sha256).map_err(AssetDownloadError::Write)?;
+    let replaced = record_release_asset(plugin_id, release_tag, kind, sha256, size, revision)
+        .map_err(AssetDownloadError::Write)?;
+    if replaced {
+        println!(
+            "  {} of {plugin_id} {release_tag} changed since it was first downloaded",
+            kind.file_name()
+        );
+    }
+    Ok(())
+}
+
+/// Whether the cache already holds the content GitHub currently serves for
+/// `asset`, so neither a download nor a new hash is needed.
+pub(super) fn cached_asset_is_current(
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+    asset: &GithubReleaseAsset,
+) -> bool {
+    ReleaseAssetIndex::read(plugin_id)
+        .is_ok_and(|index| is_indexed_asset_current(&index, release_tag, kind, asset))
+}
+
+fn is_indexed_asset_current(
+    index: &ReleaseAssetIndex,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+    asset: &GithubReleaseAsset,
+) -> bool {
+    index.get(release_tag, kind).is_some_and(|entry| {
+        can_reuse_indexed_asset(
+            &entry.revision,
+            entry.size_bytes,
+            &asset.revision(),
+            asset.size,
+        ) && release_asset_blob_path(&entry.sha256).is_file()
+    })
+}
+
+/// Whether the indexed content still matches the asset GitHub lists. Assets
+/// are identified by their revision, so a same-size re-upload is downloaded
+/// again; the size is only relied on when GitHub reported no revision.
+fn can_reuse_indexed_asset(
+    indexed_revision: &ReleaseAssetRevision,
+    indexed_size: u64,
+    revision: &ReleaseAssetRevision,
+    size: u64,
+) -> bool {
+    indexed_size == size && (!revision.is_known() || indexed_revision == revision)
+}
+
+/// Writes the response to `path` and returns its size and SHA-256.
 fn stream_response_to_file(
     mut response: reqwest::blocking::Response,
     path: &Path,
     expected_size: u64,
-) -> Result<u64, MainJsDownloadError> {
-    let file = File::create(path).map_err(|error| MainJsDownloadError::Write(error.to_string()))?;
+    max_size: u64,
+) -> Result<(u64, String), AssetDownloadError> {
+    let file = File::create(path).map_err(|error| AssetDownloadError::Write(error.to_string()))?;
     let mut writer = BufWriter::new(file);
+    let mut hasher = Sha256::new();
     let mut buffer = [0u8; 64 * 1024];
     let mut total = 0u64;
 
     loop {
         let read = response
             .read(&mut buffer)
-            .map_err(|error| MainJsDownloadError::Read(error.to_string()))?;
+            .map_err(|error| AssetDownloadError::Read(error.to_string()))?;
         if read == 0 {
             break;
         }
 
         total += read as u64;
-        if total > expected_size || total > MAX_MAIN_JS_DOWNLOAD_BYTES {
-            return Err(MainJsDownloadError::SizeMismatch {
-                expected: expected_size,
-                actual: total,
-            });
+        if total > expected_size || total > max_size {
+            return Err(AssetDownloadError::Quarantined(
+                QuarantineReason::SizeMismatch {
+                    expected: expected_size,
+                    actual: total,
+                },
+            ));
         }
 
+        hasher.update(&buffer[..read]);
         writer
             .write_all(&buffer[..read])
-            .map_err(|error| MainJsDownloadError::Write(error.to_string()))?;
+            .map_err(|error| AssetDownloadError::Write(error.to_string()))?;
     }
 
     writer
         .flush()
-        .map_err(|error| MainJsDownloadError::Write(error.to_string()))?;
+        .map_err(|error| AssetDownloadError::Write(error.to_string()))?;
 
-    Ok(total)
+    Ok((total, hex_digest(hasher)))
 }
 
-fn rate_limit_remaining_is_zero(headers: &reqwest::header::HeaderMap) -> bool {
-    headers
-        .get("x-ratelimit-remaining")
-        .and_then(|v| v.to_str().ok())
-        .is_some_and(|value| value.trim() == "0")
+pub fn release_main_js_cache_path(plugin_id: &str, release_tag: &str) -> Result<PathBuf, String> {
+    release_asset_cache_path(plugin_id, release_tag, ReleaseAssetKind::MainJs)
 }
 
-pub fn release_main_js_cache_path(plugin_id: &str, release_tag: &str) -> Result<PathBuf, String> {
-    let sanitized_tag = release_tag
+/// Where the asset of a release is stored: the content-addressed blob if the
+/// plugin's index knows it, otherwise the per-tag file that older runs wrote.
+pub fn release_asset_cache_path(
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+) -> Result<PathBuf, String> {
+    let legacy_path = legacy_release_asset_path(plugin_id, release_tag, kind)?;
+    Ok(ReleaseAssetIndex::read(plugin_id)?
+        .get(release_tag, kind)
+        .map_or(legacy_path, |entry| release_asset_blob_path(&entry.sha256)))
+}
+
+/// Before the content-addressed store, assets of all kinds shared the
+/// per-plugin directory and were keyed by the sanitized tag and the asset file
+/// name, e.g. `1.2.0-manifest.json`. Downloads are still staged there.
+pub fn legacy_release_asset_path(
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+) -> Result<PathBuf, String> {
+    Ok(
+        validated_plugin_path(Path::new(PLUGIN_RELEASE_MAIN_JS_PATH), plugin_id)?.join(format!(
+            "{}-{}",
+            sanitize_release_tag(release_tag),
+            kind.file_name()
+        )),
+    )
+}
+
+/// Makes a release tag safe to use in a file name.
+pub(super) fn sanitize_release_tag(release_tag: &str) -> String {
+    release_tag
         .chars()
         .map(|ch| {
             if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' || ch == '_' {
@@ -215,24 +422,58 @@ pub fn release_main_js_cache_path(plugin_id: &str, release_tag: &str) -> Result<
                 '_'
             }
         })
-        .collect::<String>();
-
-    Ok(
-        validated_plugin_path(Path::new(PLUGIN_RELEASE_MAIN_JS_PATH), plugin_id)?
-            .join(format!("{sanitized_tag}-main.js")),
-    )
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::rate_limit_remaining_is_zero;
-    use reqwest::header::{HeaderMap, HeaderValue};
+    use super::{
+        ReleaseAssetKind, ReleaseAssetRevision, can_reuse_indexed_asset, release_asset_cache_path,
+        release_main_js_cache_path,
+    };
 
     #[test]
-    fn detects_zero_rate_limit_remaining_header() {
-        let mut headers = HeaderMap::new();
-        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+    fn asset_cache_paths_are_keyed_by_tag_and_kind() {
+        assert_eq!(
+            release_asset_cache_path("plugin", "v1/2", ReleaseAssetKind::ManifestJson)
+                .unwrap()
+                .file_name()
+                .unwrap(),
+            "v1_2-manifest.json"
+        );
+        assert_eq!(
+            release_main_js_cache_path("plugin", "1.0.0").unwrap(),
+            release_asset_cache_path("plugin", "1.0.0", ReleaseAssetKind::MainJs).unwrap()
+        );
+        assert!(
+            release_asset_cache_path("../plugin", "1.0.0", ReleaseAssetKind::StylesCss).is_err()
+        );
+    }
 
-        assert!(rate_limit_remaining_is_zero(&headers));
+    #[test]
+    fn same_size_reupload_is_not_reused() {
+        let indexed = ReleaseAssetRevision {
+            id: Some("100".to_string()),
+            updated_at: Some("2024-05-02T10:00:00Z".to_string()),
+        };
+        let reuploaded = ReleaseAssetRevision {
+            id: Some("101".to_string()),
+            updated_at: Some("2024-06-01T08:00:00Z".to_string()),
+        };
+
+        assert!(can_reuse_indexed_asset(&indexed, 10, &indexed, 10));
+        assert!(!can_reuse_indexed_asset(&indexed, 10, &reuploaded, 10));
+        assert!(!can_reuse_indexed_asset(
+            &ReleaseAssetRevision::default(),
+            10,
+            &indexed,
+            10
+        ));
+        assert!(can_reuse_indexed_asset(
+            &indexed,
+            10,
+            &ReleaseAssetRevision::default(),
+            10
+        ));
     }
 }
@@ -0,0 +1,476 @@
+//! Release metadata from the GitHub GraphQL API. One query looks up the target
+//! release of a whole batch of repositories, which costs a fraction of the rate
+//! limit that one REST request per plugin does. GraphQL has no ETags, so an
+//! unchanged release is recognized by comparing it with the previous state
+//! entry instead.
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::{
+    alerts,
+    github::{
+        GithubClient, GithubResource, GithubScheduler, RequestPriority,
+        rate_limit_remaining_is_zero,
+    },
+};
+
+use super::{
+    ReleaseFetchStatus,
+    cache::{ReleaseAssetKind, cached_asset_is_current},
+    fetch::{
+        GithubRelease, GithubReleaseAsset, ReleaseFetchRequest, ReleaseFetchResult,
+        build_success_result, find_release_asset, github_error_detail, state_entry,
+    },
+    should_retry_release_fetch,
+};
+
+const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+/// Releases with more assets than this are cut off; Obsidian plugins ship
+/// three.
+const MAX_RELEASE_ASSETS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlRelease {
+    tag_name: String,
+    published_at: Option<String>,
+    release_assets: GraphqlReleaseAssets,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlReleaseAssets {
+    nodes: Vec<GraphqlReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlReleaseAsset {
+    name: String,
+    size: u64,
+    download_url: String,
+    id: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl From<GraphqlRelease> for GithubRelease {
+    fn from(release: GraphqlRelease) -> Self {
+        Self {
+            tag_name: release.tag_name,
+            published_at: release.published_at.unwrap_or_default(),
+            assets: release
+                .release_assets
+                .nodes
+                .into_iter()
+                .map(|asset| GithubReleaseAsset {
+                    name: asset.name,
+                    size: asset.size,
+                    browser_download_url: asset.download_url,
+                    id: asset.id,
+                    updated_at: asset.updated_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum BatchResponse {
+    RateLimited(String),
+    /// The release of every request in query order, `None` if the repository
+    /// or the release does not exist, and the failure status if its alias
+    /// failed for any other reason.
+    Releases(Vec<Result<Option<GithubRelease>, ReleaseFetchStatus>>),
+}
+
+/// Fetches the target releases of all `requests` with a single GraphQL query
+/// and returns one result per request, in the same order.
+pub(super) fn fetch_release_info_batch(
+    requests: &[ReleaseFetchRequest<'_>],
+    client: &GithubClient,
+    scheduler: &GithubScheduler,
+) -> Vec<ReleaseFetchResult> {
+    if requests.is_empty() {
+        return Vec::new();
+    }
+    let context = format!(
+        "plugin release GraphQL batch of {} plugins starting with {}",
+        requests.len(),
+        requests[0].plugin_id
+    );
+    let failed = |status: ReleaseFetchStatus| {
+        requests
+            .iter()
+            .map(|request| {
+                ReleaseFetchResult::Updated(
+                    Box::new(state_entry(request.repo, None, None, status.clone())),
+                    Vec::new(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let priority = if requests
+        .iter()
+        .any(|request| request.priority == RequestPriority::High)
+    {
+        RequestPriority::High
+    } else {
+        RequestPriority::Low
+    };
+    let permit = match scheduler.acquire(GithubResource::Graphql, priority) {
+        Ok(permit) => permit,
+        Err(deferred) => {
+            alerts::record_rate_limit(context, deferred.to_string());
+            return failed(ReleaseFetchStatus::RateLimited);
+        }
+    };
+
+    let http_request = client
+        .post(GITHUB_GRAPHQL_URL)
+        .header("User-Agent", "obsidian-stats-data")
+        .json(&batch_query(requests));
+
+    let response = match client.send(http_request, permit) {
+        Ok(response) => response,
+        Err(err) => {
+            alerts::record_unexpected_error(context, err.clone());
+            return failed(ReleaseFetchStatus::RequestError(err));
+        }
+    };
+    let status = response.status().as_u16();
+
+    if status == 403 || status == 429 || rate_limit_remaining_is_zero(response.headers()) {
+        alerts::record_rate_limit(context, github_error_detail(scheduler, status, response));
+        return failed(ReleaseFetchStatus::RateLimited);
+    }
+    if !response.status().is_success() {
+        alerts::record_unexpected_error(context, github_error_detail(scheduler, status, response));
+        return failed(ReleaseFetchStatus::HttpError(status.to_string()));
+    }
+
+    let body = match response.text() {
+        Ok(body) => body,
+        Err(err) => {
+            alerts::record_unexpected_error(context, err.to_string());
+            return failed(ReleaseFetchStatus::RequestError(err.to_string()));
+        }
+    };
+    let releases = match parse_batch_response(&body, requests.len()) {
+        Ok(BatchResponse::Releases(releases)) => releases,
+        Ok(BatchResponse::RateLimited(detail)) => {
+            alerts::record_rate_limit(context, detail);
+            return failed(ReleaseFetchStatus::RateLimited);
+        }
+        Err(err) => {
+            alerts::record_unexpected_error(context, err.clone());
+            return failed(ReleaseFetchStatus::ParseError(err));
+        }
+    };
+
+    requests
+        .iter()
+        .zip(releases)
+        .map(|(request, release)| match release {
+            Err(status) => {
+                if let ReleaseFetchStatus::RequestError(detail) = &status {
+                    alerts::record_unexpected_error(
+                        format!("plugin release GraphQL lookup for {}", request.plugin_id),
+                        detail.clone(),
+                    );
+                }
+                ReleaseFetchResult::Updated(
+                    Box::new(state_entry(request.repo, None, None, status)),
+                    Vec::new(),
+                )
+            }
+            Ok(None) => ReleaseFetchResult::Updated(
+                Box::new(state_entry(
+                    request.repo,
+                    None,
+                    None,
+                    ReleaseFetchStatus::NoReleaseForVersion,
+                )),
+                Vec::new(),
+            ),
+            Ok(Some(release)) if is_unchanged(request, &release) => ReleaseFetchResult::NotModified,
+            Ok(Some(release)) => build_success_result(request, client, scheduler, release, None),
+        })
+        .collect()
+}
+
+/// One aliased `repository` field per request, `r{index}`. Values are passed
+/// as variables so that repository names and tags need no escaping. Requests
+/// with a malformed repository are left out and come back as missing.
+fn batch_query(requests: &[ReleaseFetchRequest<'_>]) -> Value {
+    let mut parameters = Vec::new();
+    let mut fields = Vec::new();
+    let mut variables = Map::new();
+
+    for (index, request) in requests.iter().enumerate() {
+        let Some((owner, name)) = request.repo.split_once('/') else {
+            continue;
+        };
+        parameters.push(format!(
+            "$owner{index}: String!, $name{index}: String!, $tag{index}: String!"
+        ));
+        fields.push(format!(
+            "r{index}: repository(owner: $owner{index}, name: $name{index}) {{ \
+             release(tagName: $tag{index}) {{ tagName publishedAt \
+             releaseAssets(first: {MAX_RELEASE_ASSETS}) {{ nodes {{ id name size downloadUrl updatedAt }} }} }} }}"
+        ));
+        variables.insert(format!("owner{index}"), json!(owner));
+        variables.insert(format!("name{index}"), json!(name));
+        variables.insert(format!("tag{index}"), json!(request.target_release_tag));
+    }
+
+    json!({
+        "query": format!("query({}) {{ {} }}", parameters.join(", "), fields.join(" ")),
+        "variables": variables,
+    })
+}
+
+fn parse_batch_response(body: &str, request_count: usize) -> Result<BatchResponse, String> {
+    let response: Value = serde_json::from_str(body).map_err(|err| err.to_string())?;
+
+    let errors = response
+        .get("errors")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    // A rate limit that is not tied to one alias stops the whole batch.
+    if let Some(error) = errors.iter().find(|error| {
+        error.get("type").and_then(Value::as_str) == Some("RATE_LIMITED")
+            && error.get("path").is_none()
+    }) {
+        return Ok(BatchResponse::RateLimited(error_message(error)));
+    }
+
+    // Failed aliases come back as `null` with an error whose path starts with
+    // the alias; only a response without any data means the query itself
+    // failed.
+    let Some(data) = response.get("data").and_then(Value::as_object) else {
+        return Err(errors
+            .first()
+            .map(error_message)
+            .unwrap_or_else(|| "GraphQL response without data".to_string()));
+    };
+
+    (0..request_count)
+        .map(|index| {
+            let alias = format!("r{index}");
+            // Requests with a malformed repository are not part of the query.
+            let Some(repository) = data.get(&alias) else {
+                return Ok(Ok(None));
+            };
+            match repository.get("release") {
+                Some(Value::Null) | None => Ok(missing_release_status(repository, &alias, errors)),
+                Some(release) => serde_json::from_value::<GraphqlRelease>(release.clone())
+                    .map(|release| Ok(Some(release.into())))
+                    .map_err(|err| err.to_string()),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(BatchResponse::Releases)
+}
+
+/// Why the alias `alias` has no release. Only NOT_FOUND errors and a
+/// repository without that release mean the release does not exist; any
+/// other error is a failed request that is retried later.
+fn missing_release_status(
+    repository: &Value,
+    alias: &str,
+    errors: &[Value],
+) -> Result<Option<GithubRelease>, ReleaseFetchStatus> {
+    let alias_error = errors.iter().find(|error| {
+        error
+            .get("path")
+            .and_then(|path| path.get(0))
+            .and_then(Value::as_str)
+            == Some(alias)
+    });
+
+    match alias_error {
+        Some(error) => match error.get("type").and_then(Value::as_str) {
+            Some("NOT_FOUND") => Ok(None),
+            Some("RATE_LIMITED") => Err(ReleaseFetchStatus::RateLimited),
+            _ => Err(ReleaseFetchStatus::RequestError(error_message(error))),
+        },
+        None if repository.is_object() => Ok(None),
+        None => Err(ReleaseFetchStatus::RequestError(
+            errors
+                .iter()
+                .find(|error| error.get("path").is_none())
+                .map(error_message)
+                .unwrap_or_else(|| format!("GraphQL returned null for {alias}")),
+        )),
+    }
+}
+
+fn error_message(error: &Value) -> String {
+    error
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown GraphQL error")
+        .to_string()
+}
+
+/// The GraphQL counterpart of an ETag match: the previous fetch succeeded for
+/// the same release and its main.js did not change. A main.js whose revision
+/// differs from the cached one was re-uploaded and is not unchanged.
+fn is_unchanged(request: &ReleaseFetchRequest<'_>, release: &GithubRelease) -> bool {
+    let main_js = find_release_asset(release, ReleaseAssetKind::MainJs);
+    request.previous_entry.is_some_and(|entry| {
+        !should_retry_release_fetch(entry)
+            && entry.latest_release_tag.as_deref() == Some(release.tag_name.as_str())
+            && entry.latest_release_published_at.as_deref() == Some(release.published_at.as_str())
+            && entry.latest_release_main_js_size_bytes == main_js.map(|asset| asset.size)
+            && main_js.is_none_or(|asset| {
+                !asset.revision().is_known()
+                    || cached_asset_is_current(
+                        request.plugin_id,
+                        &release.tag_name,
+                        ReleaseAssetKind::MainJs,
+                        asset,
+                    )
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchResponse, batch_query, is_unchanged, parse_batch_response};
+    use crate::{
+        github::RequestPriority,
+        plugins::release_acquisition::{
+            PluginReleaseStateEntry, ReleaseFetchStatus, fetch::ReleaseFetchRequest,
+        },
+    };
+
+    const RECORDED_BATCH_RESPONSE: &str = include_str!("fixtures/graphql_release_batch.json");
+    const PARTIAL_ERROR_RESPONSE: &str =
+        include_str!("fixtures/graphql_release_batch_partial_errors.json");
+
+    fn request<'a>(
+        repo: &'a str,
+        tag: &'a str,
+        previous_entry: Option<&'a PluginReleaseStateEntry>,
+    ) -> ReleaseFetchRequest<'a> {
+        ReleaseFetchRequest {
+            plugin_id: "plugin",
+            repo,
+            target_release_tag: tag,
+            previous_entry,
+            previous_etag: None,
+            priority: RequestPriority::High,
+        }
+    }
+
+    fn releases(
+        body: &str,
+        request_count: usize,
+    ) -> Vec<Result<Option<super::GithubRelease>, ReleaseFetchStatus>> {
+        match parse_batch_response(body, request_count).unwrap() {
+            BatchResponse::Releases(releases) => releases,
+            BatchResponse::RateLimited(detail) => panic!("unexpected rate limit: {detail}"),
+        }
+    }
+
+    #[test]
+    fn builds_one_aliased_field_per_repository() {
+        let requests = [
+            request("owner/plugin-a", "1.4.0", None),
+            request("not-a-repo", "1.0.0", None),
+            request("owner/plugin-c", "v2.0.0", None),
+        ];
+
+        let query = batch_query(&requests);
+
+        let text = query["query"].as_str().unwrap();
+        assert!(text.contains("r0: repository(owner: $owner0, name: $name0)"));
+        assert!(!text.contains("r1:"));
+        assert!(text.contains("release(tagName: $tag2)"));
+        assert_eq!(query["variables"]["owner0"], "owner");
+        assert_eq!(query["variables"]["name2"], "plugin-c");
+        assert_eq!(query["variables"]["tag2"], "v2.0.0");
+    }
+
+    #[test]
+    fn maps_recorded_response_to_releases() {
+        let releases = releases(RECORDED_BATCH_RESPONSE, 4);
+
+        let release = releases[0].as_ref().unwrap().as_ref().unwrap();
+        assert_eq!(release.tag_name, "1.4.0");
+        assert_eq!(release.published_at, "2024-05-02T10:00:00Z");
+        assert_eq!(release.assets.len(), 3);
+        assert_eq!(release.assets[0].name, "main.js");
+        assert_eq!(release.assets[0].size, 48213);
+        assert_eq!(
+            release.assets[0].browser_download_url,
+            "https://github.com/owner/plugin-a/releases/download/1.4.0/main.js"
+        );
+        // Missing release, missing repository, request left out of the query.
+        assert!(matches!(releases[1], Ok(None)));
+        assert!(matches!(releases[2], Ok(None)));
+        assert!(matches!(releases[3], Ok(None)));
+    }
+
+    #[test]
+    fn only_not_found_aliases_count_as_missing_releases() {
+        let releases = releases(PARTIAL_ERROR_RESPONSE, 4);
+
+        assert!(matches!(releases[0], Ok(None)));
+        assert!(matches!(
+            &releases[1],
+            Err(ReleaseFetchStatus::RequestError(detail)) if detail.starts_with("Resource not accessible")
+        ));
+        assert!(matches!(releases[2], Err(ReleaseFetchStatus::RateLimited)));
+        assert!(matches!(
+            &releases[3],
+            Err(ReleaseFetchStatus::RequestError(detail)) if detail == "Something went wrong while executing your query."
+        ));
+    }
+
+    #[test]
+    fn detects_rate_limited_responses() {
+        let body = r#"{"errors":[{"type":"RATE_LIMITED","message":"API rate limit exceeded for user ID 1."}]}"#;
+
+        assert!(matches!(
+            parse_batch_response(body, 1),
+            Ok(BatchResponse::RateLimited(message)) if message.starts_with("API rate limit")
+        ));
+    }
+
+    #[test]
+    fn unchanged_release_counts_as_not_modified() {
+        let release = releases(RECORDED_BATCH_RESPONSE, 1)
+            .remove(0)
+            .unwrap()
+            .unwrap();
+        let mut previous = PluginReleaseStateEntry {
+            repo: "owner/plugin-a".to_string(),
+            last_checked_unix: 0,
+            latest_release_etag: None,
+            latest_release_main_js_size_bytes: Some(48213),
+            latest_release_manifest_json_size_bytes: Some(310),
+            latest_release_styles_css_size_bytes: Some(1024),
+            last_successful_main_js_release_tag: Some("1.4.0".to_string()),
+            last_successful_main_js_release_published_at: Some("2024-05-02T10:00:00Z".to_string()),
+            estimated_target_es_version: None,
+            latest_release_tag: Some("1.4.0".to_string()),
+            latest_release_published_at: Some("2024-05-02T10:00:00Z".to_string()),
+            latest_release_fetch_status: Some("ok".to_string()),
+        };
+
+        assert!(is_unchanged(
+            &request("owner/plugin-a", "1.4.0", Some(&previous)),
+            &release
+        ));
+
+        previous.latest_release_main_js_size_bytes = Some(1);
+        assert!(!is_unchanged(
+            &request("owner/plugin-a", "1.4.0", Some(&previous)),
+            &release
+        ));
+    }
+}
@@ -8,30 +8,87 @@ use std::{
 
 use data_lib::plugin::PluginData;
 use hashbrown::HashMap;
-use rayon::{
-    ThreadPoolBuilder,
-    iter::{IntoParallelIterator, ParallelIterator},
-};
-use reqwest::blocking::Client;
+use rayon::ThreadPoolBuilder;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::PLUGIN_RELEASE_ENRICHMENT_STATE_PATH,
-    github::RateLimitMode,
+    constants::{DEFAULT_RELEASE_GRAPHQL_BATCH_SIZE, PLUGIN_RELEASE_ENRICHMENT_STATE_PATH},
+    github::{GithubClient, GithubScheduler, RequestPriority},
     plugins::stats_helper::{HelperPluginStore, TargetRelease, TargetReleaseError},
     progress::should_log_progress,
-    security::http_client,
+    security::github_client,
     state::{now_unix_seconds, read_json_or_default, write_json_atomic},
 };
 
+mod backfill;
 mod cache;
 mod fetch;
-
-use cache::MainJsCacheOutcome;
-pub use cache::release_main_js_cache_path;
+mod graphql;
+mod store;
+mod verify;
+
+pub use backfill::{ReleaseBackfillLimits, backfill_plugin_release_main_js, backfill_releases};
+use cache::AssetCacheOutcome;
+pub use cache::{
+    ReleaseAssetKind, legacy_release_asset_path, release_asset_cache_path,
+    release_main_js_cache_path,
+};
 use fetch::{ReleaseFetchRequest, ReleaseFetchResult, fetch_release_info};
+use graphql::fetch_release_info_batch;
+pub use store::{
+    IndexedReleaseAsset, ReleaseAssetIndex, prune_release_assets, release_asset_blob_path,
+    release_asset_index_path,
+};
+use verify::QuarantineReason;
+pub use verify::quarantined_release_asset_path;
 
 const PLUGIN_RELEASE_THREADS_ENV: &str = "PLUGIN_RELEASE_THREADS";
+const PLUGIN_RELEASE_FETCH_MODE_ENV: &str = "PLUGIN_RELEASE_FETCH_MODE";
+const PLUGIN_RELEASE_GRAPHQL_BATCH_SIZE_ENV: &str = "PLUGIN_RELEASE_GRAPHQL_BATCH_SIZE";
+
+/// Which GitHub API the release metadata is fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReleaseFetchMode {
+    /// One request per plugin, revalidated with ETags.
+    Rest,
+    /// Many plugins per query; needs a token.
+    Graphql,
+}
+
+impl ReleaseFetchMode {
+    fn from_env() -> Self {
+        let requested = std::env::var(PLUGIN_RELEASE_FETCH_MODE_ENV).unwrap_or_default();
+        if !requested.eq_ignore_ascii_case("graphql") {
+            return Self::Rest;
+        }
+        if GithubScheduler::shared().has_tokens() {
+            return Self::Graphql;
+        }
+
+        eprintln!(
+            "{PLUGIN_RELEASE_FETCH_MODE_ENV}=graphql requires a GitHub token; using the REST API instead"
+        );
+        Self::Rest
+    }
+
+    fn batch_size(self) -> usize {
+        match self {
+            Self::Rest => 1,
+            Self::Graphql => std::env::var(PLUGIN_RELEASE_GRAPHQL_BATCH_SIZE_ENV)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|size| *size > 0)
+                .unwrap_or(DEFAULT_RELEASE_GRAPHQL_BATCH_SIZE),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Rest => "rest",
+            Self::Graphql => "graphql",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PluginReleaseState {
@@ -44,6 +101,10 @@ pub struct PluginReleaseStateEntry {
     pub last_checked_unix: i64,
     pub latest_release_etag: Option<String>,
     pub latest_release_main_js_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub latest_release_manifest_json_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub latest_release_styles_css_size_bytes: Option<u64>,
     pub last_successful_main_js_release_tag: Option<String>,
     pub last_successful_main_js_release_published_at: Option<String>,
     pub estimated_target_es_version: Option<String>,
@@ -68,6 +129,8 @@ enum ReleaseFetchStatus {
     HttpError(String),
     ParseError(String),
     MainJsDownloadFailed(String),
+    /// The downloaded main.js failed verification; the detail names why.
+    MainJsQuarantined(String),
     Unknown(String),
 }
 
@@ -85,6 +148,9 @@ impl ReleaseFetchStatus {
         if let Some(detail) = status.strip_prefix("main_js_download_failed:") {
             return Self::MainJsDownloadFailed(detail.to_string());
         }
+        if let Some(detail) = status.strip_prefix("main_js_quarantined:") {
+            return Self::MainJsQuarantined(detail.to_string());
+        }
 
         match status {
             "ok" => Self::Ok,
@@ -134,15 +200,17 @@ impl ReleaseFetchStatus {
                 "main_js_download_failed".to_string()
             }
             Self::MainJsDownloadFailed(detail) => format!("main_js_download_failed:{detail}"),
+            Self::MainJsQuarantined(detail) => format!("main_js_quarantined:{detail}"),
             Self::Unknown(status) => status.clone(),
         }
     }
 
     fn is_retryable_main_js(&self) -> bool {
-        matches!(
-            self,
-            Self::MainJsRateLimited | Self::MainJsDownloadFailed(_)
-        )
+        match self {
+            Self::MainJsRateLimited | Self::MainJsDownloadFailed(_) => true,
+            Self::MainJsQuarantined(detail) => QuarantineReason::is_retryable_state_value(detail),
+            _ => false,
+        }
     }
 
     fn is_retryable_release_fetch(&self) -> bool {
@@ -169,10 +237,35 @@ struct ReleaseAcquireJob {
     previous_entry: Option<PluginReleaseStateEntry>,
 }
 
+impl ReleaseAcquireJob {
+    /// Plugins that were never fetched go first; refreshes leave part of the
+    /// rate limit to them.
+    fn priority(&self) -> RequestPriority {
+        if self.previous_entry.is_some() {
+            RequestPriority::Low
+        } else {
+            RequestPriority::High
+        }
+    }
+}
+
+/// Orders jobs so that never-fetched plugins come first, followed by the
+/// refreshes that were checked longest ago.
+fn prioritize_jobs(jobs: &mut [ReleaseAcquireJob]) {
+    jobs.sort_by_key(|job| {
+        (
+            job.previous_entry.is_some(),
+            job.previous_entry
+                .as_ref()
+                .map_or(0, |entry| entry.last_checked_unix),
+        )
+    });
+}
+
 struct ReleaseAcquireJobResult {
     key: String,
     entry: PluginReleaseStateEntry,
-    cache_outcome: Option<MainJsCacheOutcome>,
+    cache_outcomes: Vec<(ReleaseAssetKind, AssetCacheOutcome)>,
     not_modified: bool,
     rate_limited: bool,
 }
@@ -183,8 +276,8 @@ struct AcquireRunStats {
     skipped_current: usize,
     fetched_http: usize,
     not_modified: usize,
-    downloaded_main_js: usize,
-    reused_main_js: usize,
+    downloaded_assets: HashMap<ReleaseAssetKind, usize>,
+    reused_assets: HashMap<ReleaseAssetKind, usize>,
     status_counts: HashMap<String, usize>,
 }
 
@@ -192,17 +285,19 @@ pub fn acquire_plugin_release_main_js(
     plugins: &[PluginData],
     force: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let rate_limit_mode = RateLimitMode::from_env();
+    let scheduler = GithubScheduler::shared();
+    let fetch_mode = ReleaseFetchMode::from_env();
     let default_threads = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(4);
     let thread_count = configured_thread_count(PLUGIN_RELEASE_THREADS_ENV, default_threads);
 
     println!(
-        "Release acquisition: processing {} plugins (force: {}, threads: {})",
+        "Release acquisition: processing {} plugins (force: {}, threads: {}, API: {})",
         plugins.len(),
         force,
-        thread_count
+        thread_count,
+        fetch_mode.as_str()
     );
 
     let mut state: PluginReleaseState =
@@ -260,7 +355,7 @@ pub fn acquire_plugin_release_main_js(
         stats.fetched_http += jobs.len();
         let total_jobs = jobs.len();
 
-        let client = http_client()?;
+        let client = github_client()?;
         let thread_pool = ThreadPoolBuilder::new()
             .num_threads(thread_count)
             .build()
@@ -268,43 +363,56 @@ pub fn acquire_plugin_release_main_js(
         let processed = AtomicUsize::new(0);
         let abort_rate_limited = AtomicBool::new(false);
         let results = Mutex::new(Vec::new());
-
-        let run_result = thread_pool.install(|| {
-            jobs.into_par_iter().try_for_each(|job| {
-                if abort_rate_limited.load(Ordering::Relaxed) {
-                    return Err(());
-                }
-                let result = process_release_job(job, &client, &rate_limit_mode);
-                let rate_limited = result.rate_limited;
-                let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
-                if should_log_progress(done, total_jobs) {
-                    println!("  Release acquisition progress: {done} / {total_jobs}");
-                }
-                results
-                    .lock()
-                    .expect("release result mutex poisoned")
-                    .push(result);
-                if rate_limited {
-                    abort_rate_limited.store(true, Ordering::Relaxed);
-                    return Err(());
-                }
-                Ok(())
-            })
+        // Workers pull from one queue so that jobs start in priority order.
+        prioritize_jobs(&mut jobs);
+        let queue = Mutex::new(jobs.into_iter());
+
+        thread_pool.scope(|scope| {
+            for _ in 0..thread_count {
+                scope.spawn(|_| {
+                    while !abort_rate_limited.load(Ordering::Relaxed) {
+                        let batch = queue
+                            .lock()
+                            .expect("release job mutex poisoned")
+                            .by_ref()
+                            .take(fetch_mode.batch_size())
+                            .collect::<Vec<_>>();
+                        if batch.is_empty() {
+                            break;
+                        }
+
+                        for result in process_release_batch(batch, &client, scheduler, fetch_mode) {
+                            let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                            if should_log_progress(done, total_jobs) {
+                                println!("  Release acquisition progress: {done} / {total_jobs}");
+                            }
+                            if result.rate_limited {
+                                abort_rate_limited.store(true, Ordering::Relaxed);
+                            }
+                            results
+                                .lock()
+                                .expect("release result mutex poisoned")
+                                .push(result);
+                        }
+                    }
+                });
+            }
         });
 
         let results = results.into_inner().expect("release result mutex poisoned");
-        let aborted_rate_limited = run_result.is_err();
+        let aborted_rate_limited = abort_rate_limited.load(Ordering::Relaxed);
 
         for result in results {
             if result.not_modified {
                 stats.not_modified += 1;
             }
 
-            if let Some(cache_outcome) = result.cache_outcome {
-                match cache_outcome {
-                    MainJsCacheOutcome::Downloaded => stats.downloaded_main_js += 1,
-                    MainJsCacheOutcome::Reused => stats.reused_main_js += 1,
-                }
+            for (kind, cache_outcome) in result.cache_outcomes {
+                let counts = match cache_outcome {
+                    AssetCacheOutcome::Downloaded => &mut stats.downloaded_assets,
+                    AssetCacheOutcome::Reused => &mut stats.reused_assets,
+                };
+                *counts.entry(kind).or_insert(0) += 1;
             }
 
             if let Some(status) = &result.entry.latest_release_fetch_status {
@@ -330,8 +438,18 @@ pub fn acquire_plugin_release_main_js(
     println!("  Skipped (current release): {}", stats.skipped_current);
     println!("  HTTP checks: {}", stats.fetched_http);
     println!("  Not modified (ETag): {}", stats.not_modified);
-    println!("  main.js downloaded: {}", stats.downloaded_main_js);
-    println!("  main.js reused: {}", stats.reused_main_js);
+    for kind in ReleaseAssetKind::ALL {
+        println!(
+            "  {} downloaded: {}",
+            kind.file_name(),
+            stats.downloaded_assets.get(&kind).copied().unwrap_or(0)
+        );
+        println!(
+            "  {} reused: {}",
+            kind.file_name(),
+            stats.reused_assets.get(&kind).copied().unwrap_or(0)
+        );
+    }
 
     let mut status_counts = stats.status_counts.into_iter().collect::<Vec<_>>();
     status_counts.sort_by(|a, b| a.0.cmp(&b.0));
@@ -352,6 +470,8 @@ fn target_release_error_state_entry(
         last_checked_unix: now_unix_seconds(),
         latest_release_etag: None,
         latest_release_main_js_size_bytes: None,
+        latest_release_manifest_json_size_bytes: None,
+        latest_release_styles_css_size_bytes: None,
         last_successful_main_js_release_tag: previous_entry
             .and_then(|prev| prev.last_successful_main_js_release_tag.clone()),
         last_successful_main_js_release_published_at: previous_entry
@@ -377,13 +497,36 @@ fn previous_entry_for_repo<'a>(
         .filter(|entry| entry.repo == repo)
 }
 
-fn process_release_job(
-    job: ReleaseAcquireJob,
-    client: &Client,
-    rate_limit_mode: &RateLimitMode,
-) -> ReleaseAcquireJobResult {
-    let previous_entry = job.previous_entry.clone();
+fn process_release_batch(
+    jobs: Vec<ReleaseAcquireJob>,
+    client: &GithubClient,
+    scheduler: &GithubScheduler,
+    fetch_mode: ReleaseFetchMode,
+) -> Vec<ReleaseAcquireJobResult> {
+    match fetch_mode {
+        ReleaseFetchMode::Rest => jobs
+            .into_iter()
+            .map(|job| {
+                let fetch_result =
+                    fetch_release_info(release_fetch_request(&job), client, scheduler);
+                finish_release_job(job, fetch_result)
+            })
+            .collect(),
+        ReleaseFetchMode::Graphql => {
+            let fetch_results = fetch_release_info_batch(
+                &jobs.iter().map(release_fetch_request).collect::<Vec<_>>(),
+                client,
+                scheduler,
+            );
+            jobs.into_iter()
+                .zip(fetch_results)
+                .map(|(job, fetch_result)| finish_release_job(job, fetch_result))
+                .collect()
+        }
+    }
+}
 
+fn release_fetch_request(job: &ReleaseAcquireJob) -> ReleaseFetchRequest<'_> {
     let previous_etag = job
         .previous_entry
         .as_ref()
@@ -393,23 +536,31 @@ fn process_release_job(
         })
         .and_then(|entry| entry.latest_release_etag.as_deref());
 
-    let (mut entry, cache_outcome, not_modified) = match fetch_release_info(
-        ReleaseFetchRequest {
-            plugin_id: &job.plugin_id,
-            repo: &job.repo,
-            target_release_tag: &job.target_release.tag,
-            previous_entry: job.previous_entry.as_ref(),
-            previous_etag,
-        },
-        client,
-        rate_limit_mode,
-    ) {
+    ReleaseFetchRequest {
+        plugin_id: &job.plugin_id,
+        repo: &job.repo,
+        target_release_tag: &job.target_release.tag,
+        previous_entry: job.previous_entry.as_ref(),
+        previous_etag,
+        priority: job.priority(),
+    }
+}
+
+fn finish_release_job(
+    job: ReleaseAcquireJob,
+    fetch_result: ReleaseFetchResult,
+) -> ReleaseAcquireJobResult {
+    let previous_entry = job.previous_entry;
+
+    let (mut entry, cache_outcomes, not_modified) = match fetch_result {
         ReleaseFetchResult::NotModified => {
             let mut reused = previous_entry.clone().unwrap_or(PluginReleaseStateEntry {
                 repo: job.repo.clone(),
                 last_checked_unix: now_unix_seconds(),
                 latest_release_etag: None,
                 latest_release_main_js_size_bytes: None,
+                latest_release_manifest_json_size_bytes: None,
+                latest_release_styles_css_size_bytes: None,
                 last_successful_main_js_release_tag: None,
                 last_successful_main_js_release_published_at: None,
                 estimated_target_es_version: None,
@@ -418,9 +569,9 @@ fn process_release_job(
                 latest_release_fetch_status: Some(ReleaseFetchStatus::NotModified.as_state_value()),
             });
             reused.last_checked_unix = now_unix_seconds();
-            (reused, None, true)
+            (reused, Vec::new(), true)
         }
-        ReleaseFetchResult::Updated(entry, cache_outcome) => (*entry, cache_outcome, false),
+        ReleaseFetchResult::Updated(entry, cache_outcomes) => (*entry, cache_outcomes, false),
     };
 
     entry.repo = job.repo;
@@ -451,7 +602,7 @@ fn process_release_job(
     ReleaseAcquireJobResult {
         key: job.key,
         entry,
-        cache_outcome,
+        cache_outcomes,
         not_modified,
         rate_limited,
     }
@@ -468,9 +619,10 @@ fn configured_thread_count(env_var: &str, default_threads: usize) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{
-        PluginReleaseState, PluginReleaseStateEntry, ReleaseFetchStatus, previous_entry_for_repo,
-        should_retry_release_fetch,
+        PluginReleaseState, PluginReleaseStateEntry, ReleaseAcquireJob, ReleaseFetchStatus,
+        previous_entry_for_repo, prioritize_jobs, should_retry_release_fetch,
     };
+    use crate::{github::RequestPriority, plugins::stats_helper::TargetRelease};
     use hashbrown::HashMap;
 
     fn state_entry(status: &str) -> PluginReleaseStateEntry {
@@ -479,6 +631,8 @@ mod tests {
             last_checked_unix: 0,
             latest_release_etag: None,
             latest_release_main_js_size_bytes: None,
+            latest_release_manifest_json_size_bytes: None,
+            latest_release_styles_css_size_bytes: None,
             last_successful_main_js_release_tag: None,
             last_successful_main_js_release_published_at: None,
             estimated_target_es_version: None,
@@ -541,4 +695,33 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn never_fetched_plugins_are_processed_first() {
+        let job = |id: &str, last_checked_unix: Option<i64>| ReleaseAcquireJob {
+            key: id.to_string(),
+            plugin_id: id.to_string(),
+            repo: format!("owner/{id}"),
+            target_release: TargetRelease {
+                tag: "1.0.0".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            previous_entry: last_checked_unix.map(|last_checked_unix| PluginReleaseStateEntry {
+                last_checked_unix,
+                ..state_entry("ok")
+            }),
+        };
+        let mut jobs = vec![
+            job("recent", Some(200)),
+            job("new", None),
+            job("stale", Some(100)),
+        ];
+
+        prioritize_jobs(&mut jobs);
+
+        let order = jobs.iter().map(|job| job.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(order, ["new", "stale", "recent"]);
+        assert_eq!(jobs[0].priority(), RequestPriority::High);
+        assert_eq!(jobs[1].priority(), RequestPriority::Low);
+    }
 }
@@ -0,0 +1,332 @@
+//! Content-addressed storage of release assets. Files live under
+//! `PLUGIN_RELEASE_ASSET_BLOB_PATH/sha256/{prefix}/{hash}`, so identical
+//! bundles are stored once. Every plugin has an index that maps its release
+//! tags to the stored hashes, which also reveals assets that were replaced
+//! under an existing tag.
+
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    constants::{PLUGIN_RELEASE_ASSET_BLOB_PATH, PLUGIN_RELEASE_MAIN_JS_PATH},
+    security::validated_plugin_path,
+    state::{now_unix_seconds, read_json_or_default, write_json_atomic},
+};
+
+use super::cache::ReleaseAssetKind;
+
+const RELEASE_ASSET_INDEX_FILE_NAME: &str = "index.json";
+
+/// Serializes read-modify-write cycles of the per-plugin indexes.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReleaseAssetIndex {
+    #[serde(default)]
+    pub assets: Vec<IndexedReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedReleaseAsset {
+    pub tag: String,
+    /// File name of the asset, e.g. `main.js`.
+    pub asset: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub downloaded_unix: i64,
+    /// The GitHub asset the content was downloaded from.
+    #[serde(default)]
+    pub revision: ReleaseAssetRevision,
+    /// A later download of the same tag was quarantined, so `sha256` no longer
+    /// reflects what GitHub serves and must not be analyzed.
+    #[serde(default)]
+    pub stale: bool,
+    /// Earlier contents of the same asset under the same tag, oldest first.
+    #[serde(default)]
+    pub replaced: Vec<ReplacedReleaseAsset>,
+}
+
+/// Identifies an uploaded asset on GitHub. Re-uploading a file under the same
+/// tag creates a new asset, so a changed revision means the content has to be
+/// downloaded and hashed again, even if the size stayed the same.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseAssetRevision {
+    pub id: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl ReleaseAssetRevision {
+    pub fn is_known(&self) -> bool {
+        self.id.is_some() || self.updated_at.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacedReleaseAsset {
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub downloaded_unix: i64,
+}
+
+impl ReleaseAssetIndex {
+    pub fn read(plugin_id: &str) -> Result<Self, String> {
+        Ok(read_json_or_default(&release_asset_index_path(plugin_id)?))
+    }
+
+    /// The current content of an asset. Stale entries are left out.
+    pub fn get(&self, tag: &str, kind: ReleaseAssetKind) -> Option<&IndexedReleaseAsset> {
+        self.assets
+            .iter()
+            .find(|entry| entry.tag == tag && entry.asset == kind.file_name() && !entry.stale)
+    }
+
+    /// Records the content of an asset. Returns `true` if the tag previously
+    /// pointed to different content.
+    fn record(
+        &mut self,
+        tag: &str,
+        kind: ReleaseAssetKind,
+        sha256: &str,
+        size_bytes: u64,
+        revision: &ReleaseAssetRevision,
+        now: i64,
+    ) -> bool {
+        let Some(entry) = self
+            .assets
+            .iter_mut()
+            .find(|entry| entry.tag == tag && entry.asset == kind.file_name())
+        else {
+            self.assets.push(IndexedReleaseAsset {
+                tag: tag.to_string(),
+                asset: kind.file_name().to_string(),
+                sha256: sha256.to_string(),
+                size_bytes,
+                downloaded_unix: now,
+                revision: revision.clone(),
+                stale: false,
+                replaced: Vec::new(),
+            });
+            return false;
+        };
+
+        let replaced = entry.sha256 != sha256;
+        if replaced {
+            entry.replaced.push(ReplacedReleaseAsset {
+                sha256: std::mem::replace(&mut entry.sha256, sha256.to_string()),
+                size_bytes: entry.size_bytes,
+                downloaded_unix: entry.downloaded_unix,
+            });
+        }
+        entry.size_bytes = size_bytes;
+        entry.downloaded_unix = now;
+        entry.revision = revision.clone();
+        entry.stale = false;
+        replaced
+    }
+
+    /// Marks an existing entry as stale. Returns `false` if there is none.
+    fn mark_stale(&mut self, tag: &str, kind: ReleaseAssetKind) -> bool {
+        let mut found = false;
+        for entry in self
+            .assets
+            .iter_mut()
+            .filter(|entry| entry.tag == tag && entry.asset == kind.file_name())
+        {
+            entry.stale = true;
+            found = true;
+        }
+        found
+    }
+}
+
+pub fn release_asset_index_path(plugin_id: &str) -> Result<PathBuf, String> {
+    Ok(
+        validated_plugin_path(Path::new(PLUGIN_RELEASE_MAIN_JS_PATH), plugin_id)?
+            .join(RELEASE_ASSET_INDEX_FILE_NAME),
+    )
+}
+
+pub fn release_asset_blob_path(sha256: &str) -> PathBuf {
+    Path::new(PLUGIN_RELEASE_ASSET_BLOB_PATH)
+        .join("sha256")
+        .join(sha256.get(..2).unwrap_or("00"))
+        .join(sha256)
+}
+
+/// Moves `file` into the store under `sha256`. If the store already holds the
+/// content, `file` is removed instead.
+pub(super) fn store_blob(file: &Path, sha256: &str) -> Result<PathBuf, String> {
+    let blob_path = release_asset_blob_path(sha256);
+    if blob_path.is_file() {
+        fs::remove_file(file).map_err(|error| error.to_string())?;
+        return Ok(blob_path);
+    }
+
+    if let Some(parent) = blob_path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    fs::rename(file, &blob_path).map_err(|error| error.to_string())?;
+    Ok(blob_path)
+}
+
+/// Adds the asset to the plugin's index. Returns `true` if the tag previously
+/// pointed to different content, i.e. the asset was re-uploaded.
+pub(super) fn record_release_asset(
+    plugin_id: &str,
+    tag: &str,
+    kind: ReleaseAssetKind,
+    sha256: &str,
+    size_bytes: u64,
+    revision: &ReleaseAssetRevision,
+) -> Result<bool, String> {
+    let _guard = INDEX_LOCK
+        .lock()
+        .expect("release asset index mutex poisoned");
+    let path = release_asset_index_path(plugin_id)?;
+    let mut index: ReleaseAssetIndex = read_json_or_default(&path);
+    let replaced = index.record(tag, kind, sha256, size_bytes, revision, now_unix_seconds());
+    write_json_atomic(&path, &index).map_err(|error| error.to_string())?;
+    Ok(replaced)
+}
+
+/// Marks the indexed asset as stale after a new download of it was
+/// quarantined, so the old content is no longer analyzed.
+pub(super) fn mark_release_asset_stale(
+    plugin_id: &str,
+    tag: &str,
+    kind: ReleaseAssetKind,
+) -> Result<(), String> {
+    let _guard = INDEX_LOCK
+        .lock()
+        .expect("release asset index mutex poisoned");
+    let path = release_asset_index_path(plugin_id)?;
+    let mut index: ReleaseAssetIndex = read_json_or_default(&path);
+    if index.mark_stale(tag, kind) {
+        write_json_atomic(&path, &index).map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+/// Drops the index entries of `plugin_id` for which `keep` returns `false` and
+/// returns them. Their blobs stay in the store until no index references them.
+pub fn prune_release_assets(
+    plugin_id: &str,
+    keep: impl Fn(&IndexedReleaseAsset) -> bool,
+) -> Result<Vec<IndexedReleaseAsset>, String> {
+    let _guard = INDEX_LOCK
+        .lock()
+        .expect("release asset index mutex poisoned");
+    let path = release_asset_index_path(plugin_id)?;
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let index: ReleaseAssetIndex = read_json_or_default(&path);
+    let (kept, removed): (Vec<_>, Vec<_>) = index.assets.into_iter().partition(|entry| keep(entry));
+    if !removed.is_empty() {
+        write_json_atomic(&path, &ReleaseAssetIndex { assets: kept })
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(removed)
+}
+
+pub(super) fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex_digest(hasher))
+}
+
+pub(super) fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use super::{ReleaseAssetIndex, ReleaseAssetRevision, hex_digest, release_asset_blob_path};
+    use crate::plugins::release_acquisition::ReleaseAssetKind;
+
+    #[test]
+    fn hashes_as_lowercase_hex() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+
+        assert_eq!(
+            hex_digest(hasher),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(release_asset_blob_path("ba7816bf").ends_with("sha256/ba/ba7816bf"));
+    }
+
+    #[test]
+    fn records_replaced_content_under_the_same_tag() {
+        let mut index = ReleaseAssetIndex::default();
+        let revision = ReleaseAssetRevision::default();
+
+        assert!(!index.record("1.0.0", ReleaseAssetKind::MainJs, "aaa", 10, &revision, 100));
+        assert!(!index.record(
+            "1.0.0",
+            ReleaseAssetKind::StylesCss,
+            "bbb",
+            5,
+            &revision,
+            100
+        ));
+        assert!(!index.record("1.0.0", ReleaseAssetKind::MainJs, "aaa", 10, &revision, 150));
+        assert!(index.record("1.0.0", ReleaseAssetKind::MainJs, "ccc", 12, &revision, 200));
+
+        let entry = index.get("1.0.0", ReleaseAssetKind::MainJs).unwrap();
+        assert_eq!(entry.sha256, "ccc");
+        assert_eq!(entry.size_bytes, 12);
+        assert_eq!(entry.replaced.len(), 1);
+        assert_eq!(entry.replaced[0].sha256, "aaa");
+        assert_eq!(entry.replaced[0].downloaded_unix, 150);
+        assert!(
+            index
+                .get("1.0.0", ReleaseAssetKind::StylesCss)
+                .unwrap()
+                .replaced
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn stale_entries_are_hidden_until_recorded_again() {
+        let mut index = ReleaseAssetIndex::default();
+        let revision = ReleaseAssetRevision {
+            id: Some("1".to_string()),
+            updated_at: Some("2024-05-02T10:00:00Z".to_string()),
+        };
+        index.record("1.0.0", ReleaseAssetKind::MainJs, "aaa", 10, &revision, 100);
+
+        assert!(index.mark_stale("1.0.0", ReleaseAssetKind::MainJs));
+        assert!(!index.mark_stale("2.0.0", ReleaseAssetKind::MainJs));
+        assert!(index.get("1.0.0", ReleaseAssetKind::MainJs).is_none());
+
+        index.record("1.0.0", ReleaseAssetKind::MainJs, "bbb", 10, &revision, 200);
+        assert_eq!(
+            index.get("1.0.0", ReleaseAssetKind::MainJs).unwrap().sha256,
+            "bbb"
+        );
+    }
+}
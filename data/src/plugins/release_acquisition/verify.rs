@@ -0,0 +1,181 @@
+//! Checks a downloaded release asset before it enters the store. Files that
+//! fail are moved to `PLUGIN_RELEASE_QUARANTINE_PATH/{plugin_id}` so that a
+//! truncated or bogus bundle is never analyzed but can still be inspected.
+
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{constants::PLUGIN_RELEASE_QUARANTINE_PATH, security::validated_plugin_path};
+
+use super::cache::{ReleaseAssetKind, sanitize_release_tag};
+
+/// Bytes at the start of `main.js` that the probe looks at.
+const JAVASCRIPT_PROBE_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum QuarantineReason {
+    SizeMismatch { expected: u64, actual: u64 },
+    InvalidUtf8,
+    NotJavaScript(&'static str),
+}
+
+impl QuarantineReason {
+    pub(super) fn as_state_value(&self) -> String {
+        match self {
+            Self::SizeMismatch { expected, actual } => {
+                format!("size_mismatch:{expected}:{actual}")
+            }
+            Self::InvalidUtf8 => "invalid_utf8".to_string(),
+            Self::NotJavaScript(what) => format!("not_javascript:{what}"),
+        }
+    }
+
+    pub(super) fn detail_message(&self) -> String {
+        match self {
+            Self::SizeMismatch { expected, actual } => {
+                format!("expected {expected} bytes, downloaded {actual} bytes")
+            }
+            Self::InvalidUtf8 => "asset is not valid UTF-8".to_string(),
+            Self::NotJavaScript(what) => format!("main.js looks like {what}, not JavaScript"),
+        }
+    }
+
+    /// Only a short download can be fixed by downloading again; the other
+    /// reasons stay until the release changes.
+    pub(super) fn is_retryable_state_value(state_value: &str) -> bool {
+        state_value.starts_with("size_mismatch:")
+    }
+}
+
+/// Verifies the downloaded file at `path` against the size GitHub advertised
+/// and the expected content of `kind`.
+pub(super) fn verify_release_asset(
+    path: &Path,
+    kind: ReleaseAssetKind,
+    expected_size: u64,
+) -> Result<(), QuarantineReason> {
+    let actual = fs::metadata(path).map_or(0, |metadata| metadata.len());
+    if actual != expected_size {
+        return Err(QuarantineReason::SizeMismatch {
+            expected: expected_size,
+            actual,
+        });
+    }
+
+    // All release assets are text. Reading them fully is fine because their
+    // size is bounded by the download limits.
+    let mut bytes = Vec::new();
+    fs::File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|_| QuarantineReason::InvalidUtf8)?;
+    let source = std::str::from_utf8(&bytes).map_err(|_| QuarantineReason::InvalidUtf8)?;
+
+    if kind == ReleaseAssetKind::MainJs {
+        probe_javascript(source).map_err(QuarantineReason::NotJavaScript)?;
+    }
+    Ok(())
+}
+
+/// A cheap check that `source` could be a JavaScript bundle. It rejects what
+/// typically ends up in place of one: empty files, binaries, HTML or XML error
+/// pages and JSON error bodies. Bundles are not parsed here because the
+/// analysis copes with code the strict parser rejects.
+fn probe_javascript(source: &str) -> Result<(), &'static str> {
+    let trimmed = source.trim_start_matches('\u{feff}').trim();
+    if trimmed.is_empty() {
+        return Err("an empty file");
+    }
+
+    let mut end = trimmed.len().min(JAVASCRIPT_PROBE_BYTES);
+    while !trimmed.is_char_boundary(end) {
+        end -= 1;
+    }
+    let head = &trimmed[..end];
+    if head.contains('\0') {
+        return Err("binary data");
+    }
+    if head.starts_with('<') {
+        return Err("markup");
+    }
+    if head.starts_with('{')
+        && serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(trimmed).is_ok()
+    {
+        return Err("JSON");
+    }
+    Ok(())
+}
+
+/// Where a failed download of the asset is kept for inspection.
+pub fn quarantined_release_asset_path(
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+) -> Result<PathBuf, String> {
+    Ok(
+        validated_plugin_path(Path::new(PLUGIN_RELEASE_QUARANTINE_PATH), plugin_id)?.join(format!(
+            "{}-{}",
+            sanitize_release_tag(release_tag),
+            kind.file_name()
+        )),
+    )
+}
+
+/// Moves a failed download to the quarantine and returns where it went.
+pub(super) fn quarantine_release_asset(
+    file: &Path,
+    plugin_id: &str,
+    release_tag: &str,
+    kind: ReleaseAssetKind,
+) -> Result<PathBuf, String> {
+    let target = quarantined_release_asset_path(plugin_id, release_tag, kind)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    fs::rename(file, &target).map_err(|error| error.to_string())?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuarantineReason, probe_javascript};
+
+    #[test]
+    fn accepts_javascript_bundles() {
+        assert!(probe_javascript("\"use strict\";var a=require(\"obsidian\");").is_ok());
+        assert!(probe_javascript("/* banner */\n(()=>{})();").is_ok());
+        assert!(probe_javascript("{ let scoped = 1; }").is_ok());
+    }
+
+    #[test]
+    fn rejects_error_pages_and_empty_files() {
+        assert_eq!(probe_javascript("  \n"), Err("an empty file"));
+        assert_eq!(
+            probe_javascript("<!DOCTYPE html><html></html>"),
+            Err("markup")
+        );
+        assert_eq!(
+            probe_javascript(r#"{"message":"Not Found","documentation_url":"x"}"#),
+            Err("JSON")
+        );
+        assert_eq!(probe_javascript("PK\u{3}\u{4}\0\0"), Err("binary data"));
+    }
+
+    #[test]
+    fn only_short_downloads_are_retried() {
+        let short = QuarantineReason::SizeMismatch {
+            expected: 10,
+            actual: 4,
+        };
+
+        assert_eq!(short.as_state_value(), "size_mismatch:10:4");
+        assert!(QuarantineReason::is_retryable_state_value(
+            &short.as_state_value()
+        ));
+        assert!(!QuarantineReason::is_retryable_state_value(
+            &QuarantineReason::InvalidUtf8.as_state_value()
+        ));
+    }
+}
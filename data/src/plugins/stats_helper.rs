@@ -138,6 +138,12 @@ impl HelperPluginStore {
             .filter(|helper_plugin| helper_plugin.repo == plugin.current_entry.repo)
             .and_then(|helper_plugin| helper_plugin.manifest.clone())
     }
+
+    pub fn helper_releases_for_plugin(&self, plugin: &PluginData) -> &[HelperRelease] {
+        self.get(&plugin.id)
+            .filter(|helper_plugin| helper_plugin.repo == plugin.current_entry.repo)
+            .map_or(&[], |helper_plugin| helper_plugin.releases.as_slice())
+    }
 }
 
 pub fn build_version_history(helper_plugin: &HelperPluginData) -> Vec<VersionHistory> {
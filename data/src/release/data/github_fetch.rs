@@ -1,8 +1,11 @@
 use reqwest::header::HeaderMap;
 
 use crate::{
-    alerts, constants::RELEASE_STATS_URL, github::RateLimitMode, release::GithubReleaseEntry,
-    security::http_client, state::now_unix_seconds,
+    alerts,
+    constants::RELEASE_STATS_URL,
+    github::{GithubResource, GithubScheduler, RateLimitMode, RequestPriority, retry_wait_seconds},
+    release::GithubReleaseEntry,
+    security::github_client,
 };
 
 pub(super) struct FetchOutcome {
@@ -20,7 +23,7 @@ pub(super) fn refresh_completed(fetch_outcome: &FetchOutcome) -> bool {
 }
 
 pub(super) fn fetch_github_release_entries(
-    rate_limit_mode: &RateLimitMode,
+    scheduler: &GithubScheduler,
     previous_etag: Option<&str>,
 ) -> FetchOutcome {
     let mut current_link = Some(RELEASE_STATS_URL.to_string());
@@ -30,7 +33,7 @@ pub(super) fn fetch_github_release_entries(
     let mut not_modified = false;
     let mut page_count = 0;
     let mut latest_etag = None;
-    let client = match http_client() {
+    let client = match github_client() {
         Ok(client) => client,
         Err(error) => {
             alerts::record_unexpected_error("release stats HTTP client", error.to_string());
@@ -56,17 +59,21 @@ pub(super) fn fetch_github_release_entries(
             request = request.header("If-None-Match", etag);
         }
 
-        if let Ok(token) = std::env::var("GITHUB_TOKEN")
-            && !token.is_empty()
-        {
-            request = request.bearer_auth(token);
-        }
+        let permit = match scheduler.acquire(GithubResource::Core, RequestPriority::High) {
+            Ok(permit) => permit,
+            Err(deferred) => {
+                hit_rate_limit = true;
+                alerts::record_rate_limit("release stats fetch", deferred.to_string());
+                eprintln!("Rate limited while fetching release stats.");
+                break;
+            }
+        };
 
-        let response = match request.send() {
+        let response = match client.send(request, permit) {
             Ok(response) => response,
             Err(error) => {
                 hit_unexpected_error = true;
-                alerts::record_unexpected_error("release stats fetch", error.to_string());
+                alerts::record_unexpected_error("release stats fetch", error.clone());
                 eprintln!("Failed to fetch release stats: {error}");
                 break;
             }
@@ -87,11 +94,11 @@ pub(super) fn fetch_github_release_entries(
                 format!("GitHub returned HTTP {}", response.status().as_u16()),
             );
 
-            if matches!(rate_limit_mode, RateLimitMode::Sleep)
-                && let Some(wait) = extract_retry_wait_seconds(response.headers())
-                && wait > 0
+            // The scheduler holds the retry back until GitHub accepts
+            // requests again.
+            if matches!(scheduler.mode(), RateLimitMode::Sleep)
+                && retry_wait_seconds(response.headers()).is_some_and(|wait| wait > 0)
             {
-                std::thread::sleep(std::time::Duration::from_secs(wait as u64));
                 continue;
             }
 
@@ -161,24 +168,6 @@ fn extract_next_link(headers: &HeaderMap) -> Option<String> {
     })
 }
 
-fn extract_retry_wait_seconds(headers: &HeaderMap) -> Option<i64> {
-    let retry_after = headers
-        .get("retry-after")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.parse::<i64>().ok());
-    if retry_after.is_some() {
-        return retry_after;
-    }
-
-    let reset_unix = headers
-        .get("x-ratelimit-reset")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.parse::<i64>().ok())?;
-
-    let now = now_unix_seconds();
-    Some((reset_unix - now).max(0))
-}
-
 #[cfg(test)]
 mod tests {
     use super::{FetchOutcome, refresh_completed};
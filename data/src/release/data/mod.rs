@@ -7,7 +7,7 @@ use crate::{
         RELEASE_GITHUB_INTERPOLATED_PATH, RELEASE_GITHUB_RAW_PATH, RELEASE_STATS_STATE_PATH,
     },
     file_utils::{read_chunked_data_or_default, write_in_chunks_atomic},
-    github::RateLimitMode,
+    github::GithubScheduler,
     state::{is_fresh, now_unix_seconds, read_json_or_default, write_json_atomic},
 };
 use data_lib::release::GithubReleaseInfo;
@@ -37,7 +37,7 @@ pub fn build_release_stats(force: bool) -> Result<(), Box<dyn std::error::Error>
         .ok()
         .and_then(|v| v.parse::<i64>().ok())
         .unwrap_or(DEFAULT_RELEASE_STATS_REFRESH_DAYS);
-    let rate_limit_mode = RateLimitMode::from_env();
+    let scheduler = GithubScheduler::shared();
     let mut state: ReleaseStatsState = read_json_or_default(Path::new(RELEASE_STATS_STATE_PATH));
 
     let mut raw_github_info: Vec<GithubReleaseInfo> =
@@ -55,8 +55,7 @@ pub fn build_release_stats(force: bool) -> Result<(), Box<dyn std::error::Error>
     );
 
     if should_refresh {
-        let fetch_outcome =
-            fetch_github_release_entries(&rate_limit_mode, state.latest_etag.as_deref());
+        let fetch_outcome = fetch_github_release_entries(scheduler, state.latest_etag.as_deref());
         let refresh_was_completed = refresh_completed(&fetch_outcome);
         let new_entry_count = fetch_outcome.entries.len();
 
@@ -5,6 +5,8 @@ use std::{
 
 use reqwest::{Url, blocking::Client};
 
+use crate::github::{GithubClient, GithubScheduler};
+
 const HTTP_TIMEOUT_SECONDS: u64 = 30;
 
 pub fn validate_plugin_id(plugin_id: &str) -> Result<(), String> {
@@ -52,11 +54,6 @@ pub fn validate_github_repo_slug(repo: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub fn github_repo_url(repo: &str) -> Result<String, String> {
-    validate_github_repo_slug(repo)?;
-    Ok(format!("https://github.com/{repo}.git"))
-}
-
 pub fn validated_plugin_path(base: &Path, plugin_id: &str) -> Result<PathBuf, String> {
     validate_plugin_id(plugin_id)?;
     Ok(base.join(plugin_id))
@@ -123,6 +120,12 @@ pub fn http_client() -> Result<Client, reqwest::Error> {
         .build()
 }
 
+/// Client for GitHub requests. It authenticates with the token pool of the
+/// shared [`GithubScheduler`], so no other code reads GitHub credentials.
+pub fn github_client() -> Result<GithubClient, reqwest::Error> {
+    Ok(GithubClient::new(http_client()?, GithubScheduler::shared()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{validate_github_download_url, validate_github_repo_slug, validate_plugin_id};